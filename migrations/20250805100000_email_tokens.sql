@@ -0,0 +1,18 @@
+-- Email verification and password reset
+
+ALTER TABLE users ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+-- Unix timestamp of the last password change; JWTs issued before it are rejected
+ALTER TABLE users ADD COLUMN password_changed_at INTEGER;
+
+--email_tokens table
+CREATE TABLE IF NOT EXISTS email_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE, -- SHA-256 hex of the emailed token, never the token itself
+    purpose TEXT NOT NULL CHECK (purpose IN ('verify_email', 'password_reset')),
+    created_at INTEGER NOT NULL, -- unix timestamp in seconds
+    expires_at INTEGER NOT NULL, -- unix timestamp in seconds
+    used_at INTEGER,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_email_tokens_user_id ON email_tokens(user_id);
@@ -0,0 +1,9 @@
+-- Brute-force protection for email/password logins
+
+ALTER TABLE users ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until INTEGER; -- unix timestamp, logins refused until then
+
+-- One outstanding nonce per wallet; request_nonce now replaces instead of piling up
+DELETE FROM nonces
+WHERE rowid NOT IN (SELECT MAX(rowid) FROM nonces GROUP BY wallet_address);
+CREATE INDEX idx_nonces_expires_at ON nonces(expires_at);
@@ -0,0 +1,46 @@
+-- One application model: proposals are folded into job_applications, which
+-- already drive approval, escrow and deliverables
+
+-- Migrated proposals may predate the freelancer connecting a wallet, so the payout
+-- wallet becomes optional: NULL until the freelancer adds one to the proposal
+ALTER TABLE job_applications RENAME COLUMN freelancer_wallet TO required_wallet;
+ALTER TABLE job_applications ADD COLUMN freelancer_wallet TEXT;
+UPDATE job_applications SET freelancer_wallet = required_wallet;
+ALTER TABLE job_applications DROP COLUMN required_wallet;
+
+ALTER TABLE job_applications ADD COLUMN cover_letter TEXT;
+ALTER TABLE job_applications ADD COLUMN bid_amount REAL;
+ALTER TABLE job_applications ADD COLUMN timeline_days INTEGER; -- proposed time to deliver
+ALTER TABLE job_applications ADD COLUMN status TEXT NOT NULL DEFAULT 'submitted'
+    CHECK (status IN ('submitted', 'accepted', 'rejected'));
+UPDATE job_applications SET status = 'accepted' WHERE approved = 1;
+
+-- A freelancer who both applied and sent a proposal keeps one application with the proposal's terms
+UPDATE job_applications
+SET cover_letter = p.cover_letter,
+    bid_amount = p.bid_amount,
+    status = CASE WHEN job_applications.approved = 1 THEN 'accepted' ELSE p.status END,
+    approved = (job_applications.approved = 1 OR p.status = 'accepted')
+FROM proposals p
+WHERE p.job_id = job_applications.job_id AND p.freelancer_id = job_applications.user_id;
+
+-- The rest become applications; the payout wallet is whatever the freelancer has connected
+INSERT INTO job_applications (
+    user_id, job_id, applied_at, approved, approved_at, freelancer_wallet,
+    cover_letter, bid_amount, status
+)
+SELECT
+    p.freelancer_id, p.job_id, datetime(p.created_at, 'unixepoch'), p.status = 'accepted',
+    CASE WHEN p.status = 'accepted' THEN datetime(p.created_at, 'unixepoch') END,
+    u.wallet_address, p.cover_letter, p.bid_amount, p.status
+FROM proposals p
+JOIN users u ON u.id = p.freelancer_id
+WHERE NOT EXISTS (
+    SELECT 1 FROM job_applications ja WHERE ja.job_id = p.job_id AND ja.user_id = p.freelancer_id
+);
+
+INSERT INTO job_user_interactions (user_id, job_id, applied)
+SELECT user_id, job_id, TRUE FROM job_applications WHERE TRUE
+ON CONFLICT(user_id, job_id) DO UPDATE SET applied = TRUE;
+
+DROP TABLE proposals;
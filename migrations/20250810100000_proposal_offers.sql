@@ -0,0 +1,30 @@
+-- Negotiation thread on an application: every bid and counter-offer, oldest first.
+-- The latest offer is the standing one; whoever didn't make it may accept or counter.
+CREATE TABLE IF NOT EXISTS proposal_offers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    application_id INTEGER NOT NULL,
+    author_id INTEGER NOT NULL,
+    amount REAL NOT NULL CHECK (amount > 0),
+    timeline_days INTEGER,
+    message TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (application_id) REFERENCES job_applications(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_proposal_offers_application ON proposal_offers(application_id, id);
+
+-- Terms locked in when the freelancer is hired; escrow is funded with agreed_amount
+ALTER TABLE job_applications ADD COLUMN agreed_amount REAL;
+ALTER TABLE job_applications ADD COLUMN agreed_timeline_days INTEGER;
+
+-- The original bid opens each existing thread
+INSERT INTO proposal_offers (application_id, author_id, amount, timeline_days, created_at)
+SELECT id, user_id, bid_amount, timeline_days, applied_at
+FROM job_applications
+WHERE bid_amount IS NOT NULL;
+
+UPDATE job_applications
+SET agreed_amount = COALESCE(bid_amount, (SELECT budget FROM jobs WHERE jobs.id = job_applications.job_id)),
+    agreed_timeline_days = timeline_days
+WHERE approved = 1;
@@ -0,0 +1,24 @@
+-- Freelancers can withdraw a submitted proposal, and proposals on closed or
+-- past-deadline jobs expire. SQLite can't alter a CHECK in place, so the status
+-- column is swapped for one that allows the new states.
+ALTER TABLE job_applications ADD COLUMN lifecycle_status TEXT NOT NULL DEFAULT 'submitted'
+    CHECK (lifecycle_status IN ('submitted', 'accepted', 'rejected', 'withdrawn', 'expired'));
+UPDATE job_applications SET lifecycle_status = status;
+ALTER TABLE job_applications DROP COLUMN status;
+ALTER TABLE job_applications RENAME COLUMN lifecycle_status TO status;
+
+ALTER TABLE job_applications ADD COLUMN updated_at TEXT;
+
+-- The terms as they stood before each edit, so the client can see what changed
+CREATE TABLE IF NOT EXISTS proposal_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    application_id INTEGER NOT NULL,
+    cover_letter TEXT,
+    bid_amount REAL,
+    timeline_days INTEGER,
+    freelancer_wallet TEXT,
+    revised_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (application_id) REFERENCES job_applications(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_proposal_revisions_application ON proposal_revisions(application_id, id);
@@ -0,0 +1,21 @@
+-- Invite-only jobs stay out of public search; only invited freelancers see and apply to them
+ALTER TABLE jobs ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public'
+    CHECK (visibility IN ('public', 'invite_only'));
+
+-- A client asking a specific freelancer to apply to one of their jobs
+CREATE TABLE IF NOT EXISTS job_invitations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id INTEGER NOT NULL,
+    client_id INTEGER NOT NULL,
+    freelancer_id INTEGER NOT NULL,
+    message TEXT,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined')),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    responded_at TEXT,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (client_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (freelancer_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(job_id, freelancer_id) -- One invitation per freelancer per job
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_invitations_freelancer ON job_invitations(freelancer_id, status);
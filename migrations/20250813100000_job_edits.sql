@@ -0,0 +1,17 @@
+-- Clients can edit a job until they hire, close it without hiring, or repost a
+-- closed/expired one as a fresh listing
+ALTER TABLE jobs ADD COLUMN updated_at TEXT;
+ALTER TABLE jobs ADD COLUMN reposted_from INTEGER REFERENCES jobs(id);
+
+-- One row per edit; changes is a JSON object of {field: {"from": old, "to": new}}
+CREATE TABLE IF NOT EXISTS job_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id INTEGER NOT NULL,
+    editor_id INTEGER NOT NULL,
+    changes TEXT NOT NULL,
+    edited_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (editor_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_revisions_job ON job_revisions(job_id, id);
@@ -0,0 +1,50 @@
+-- Jobs are either fixed-price or hourly. On an hourly job the budget and bids are
+-- hourly rates and the client caps the hours billed per week. job_type used to be
+-- free text; anything that mentions hours becomes 'hourly', the rest 'fixed'. The
+-- text as entered moves to legacy_job_type, which stays NULL for newer jobs.
+ALTER TABLE jobs ADD COLUMN legacy_job_type TEXT;
+UPDATE jobs SET legacy_job_type = job_type;
+ALTER TABLE jobs ADD COLUMN pricing TEXT NOT NULL DEFAULT 'fixed'
+    CHECK (pricing IN ('fixed', 'hourly'));
+UPDATE jobs SET pricing = CASE WHEN lower(job_type) LIKE '%hour%' THEN 'hourly' ELSE 'fixed' END;
+ALTER TABLE jobs DROP COLUMN job_type;
+ALTER TABLE jobs RENAME COLUMN pricing TO job_type;
+
+ALTER TABLE jobs ADD COLUMN weekly_hour_cap INTEGER CHECK (weekly_hour_cap BETWEEN 1 AND 168);
+UPDATE jobs SET weekly_hour_cap = 40 WHERE job_type = 'hourly';
+
+-- Hours the hired freelancer logs against an hourly contract
+CREATE TABLE IF NOT EXISTS time_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    application_id INTEGER NOT NULL,
+    work_date TEXT NOT NULL, -- YYYY-MM-DD
+    hours REAL NOT NULL CHECK (hours > 0 AND hours <= 24),
+    description TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (application_id) REFERENCES job_applications(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_application ON time_entries(application_id, work_date);
+
+-- One timesheet per contract week (starting Monday). The client approves it, then
+-- records the escrow they funded on-chain for the week's amount and, later, that
+-- they released it. Those two steps are bookkeeping only: nothing here checks
+-- the chain, so the escrow account itself is the source of truth for payment.
+CREATE TABLE IF NOT EXISTS timesheets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    application_id INTEGER NOT NULL,
+    week_start TEXT NOT NULL,
+    hours REAL NOT NULL,
+    hourly_rate REAL NOT NULL,
+    amount REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'submitted'
+        CHECK (status IN ('submitted', 'approved', 'rejected', 'escrow_recorded', 'release_recorded')),
+    review_note TEXT,
+    escrow_pda TEXT,
+    submitted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reviewed_at TEXT,
+    escrow_recorded_at TEXT,
+    release_recorded_at TEXT,
+    UNIQUE (application_id, week_start),
+    FOREIGN KEY (application_id) REFERENCES job_applications(id) ON DELETE CASCADE
+);
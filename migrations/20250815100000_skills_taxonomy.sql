@@ -0,0 +1,80 @@
+-- Canonical skills replace free-text matching on the comma-separated columns.
+-- jobs.skills and profiles.skills stay as the display copy (rewritten with the
+-- canonical names on save); filtering goes through job_skills/profile_skills.
+CREATE TABLE IF NOT EXISTS skills (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Every lowercased name a skill goes by, including its canonical name
+CREATE TABLE IF NOT EXISTS skill_aliases (
+    alias TEXT PRIMARY KEY,
+    skill_id INTEGER NOT NULL,
+    FOREIGN KEY (skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS job_skills (
+    job_id INTEGER NOT NULL,
+    skill_id INTEGER NOT NULL,
+    PRIMARY KEY (job_id, skill_id),
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_skills_skill ON job_skills(skill_id);
+
+CREATE TABLE IF NOT EXISTS profile_skills (
+    user_id INTEGER NOT NULL,
+    skill_id INTEGER NOT NULL,
+    PRIMARY KEY (user_id, skill_id),
+    FOREIGN KEY (user_id) REFERENCES profiles(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_profile_skills_skill ON profile_skills(skill_id);
+
+-- Seed the common spellings so existing lists resolve to one skill
+INSERT INTO skills (name) VALUES
+    ('JavaScript'), ('TypeScript'), ('Node.js'), ('React'), ('Go'), ('Python'),
+    ('PostgreSQL'), ('Kubernetes'), ('C#'), ('C++'), ('Rust'), ('Solidity');
+INSERT INTO skill_aliases (alias, skill_id) SELECT lower(name), id FROM skills;
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT v.column1, s.id
+FROM (VALUES
+    ('js', 'JavaScript'), ('ts', 'TypeScript'), ('node', 'Node.js'), ('nodejs', 'Node.js'),
+    ('reactjs', 'React'), ('react.js', 'React'), ('golang', 'Go'), ('py', 'Python'),
+    ('postgres', 'PostgreSQL'), ('k8s', 'Kubernetes'), ('csharp', 'C#'), ('cpp', 'C++')
+) v
+JOIN skills s ON s.name = v.column2;
+
+-- Split the existing comma lists once
+CREATE TEMP TABLE legacy_skills AS
+WITH RECURSIVE split(source, owner_id, item, rest) AS (
+    SELECT 'job', id, '', skills || ',' FROM jobs
+    UNION ALL
+    SELECT 'profile', user_id, '', skills || ',' FROM profiles WHERE skills IS NOT NULL
+    UNION ALL
+    SELECT source, owner_id,
+        trim(substr(rest, 1, instr(rest, ',') - 1)), substr(rest, instr(rest, ',') + 1)
+    FROM split WHERE rest <> ''
+)
+SELECT source, owner_id, item FROM split WHERE item <> '';
+
+INSERT INTO skills (name)
+SELECT MIN(item) FROM legacy_skills
+WHERE lower(item) NOT IN (SELECT alias FROM skill_aliases)
+GROUP BY lower(item);
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT lower(name), id FROM skills WHERE lower(name) NOT IN (SELECT alias FROM skill_aliases);
+
+INSERT INTO job_skills (job_id, skill_id)
+SELECT DISTINCT l.owner_id, sa.skill_id
+FROM legacy_skills l JOIN skill_aliases sa ON sa.alias = lower(l.item)
+WHERE l.source = 'job';
+INSERT INTO profile_skills (user_id, skill_id)
+SELECT DISTINCT l.owner_id, sa.skill_id
+FROM legacy_skills l JOIN skill_aliases sa ON sa.alias = lower(l.item)
+WHERE l.source = 'profile';
+
+DROP TABLE legacy_skills;
@@ -0,0 +1,21 @@
+-- Saved job filters that alert the freelancer about new matching jobs.
+-- `query` holds the serialized JobFilterQuery; `last_job_id` is the newest job
+-- already considered, so each job alerts at most once per search.
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    last_job_id INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_user ON saved_searches(user_id);
+
+-- saved_jobs is the source of truth; keep the interaction flag in step with it
+INSERT INTO job_user_interactions (user_id, job_id, saved)
+SELECT user_id, job_id, TRUE FROM saved_jobs WHERE TRUE
+ON CONFLICT(user_id, job_id) DO UPDATE SET saved = TRUE;
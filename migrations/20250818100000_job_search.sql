@@ -0,0 +1,41 @@
+-- Rebuild the job search index to cover skills and category too. Rows are keyed by
+-- rowid = jobs.id so the triggers update in place instead of scanning job_id.
+DROP TRIGGER IF EXISTS jobs_insert;
+DROP TRIGGER IF EXISTS jobs_update;
+DROP TRIGGER IF EXISTS jobs_delete;
+DROP TABLE IF EXISTS jobs_fts;
+
+CREATE VIRTUAL TABLE jobs_fts USING fts5(
+    title,
+    description,
+    skills,
+    category,
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO jobs_fts (rowid, title, description, skills, category)
+SELECT id, title, description, skills, category FROM jobs;
+
+-- Indexed terms with their document counts, for correcting typos in searches
+CREATE VIRTUAL TABLE jobs_fts_vocab USING fts5vocab(jobs_fts, row);
+
+CREATE TRIGGER jobs_insert AFTER INSERT ON jobs
+BEGIN
+    INSERT INTO jobs_fts (rowid, title, description, skills, category)
+    VALUES (new.id, new.title, new.description, new.skills, new.category);
+END;
+
+CREATE TRIGGER jobs_update AFTER UPDATE OF title, description, skills, category ON jobs
+BEGIN
+    UPDATE jobs_fts
+    SET title = new.title,
+        description = new.description,
+        skills = new.skills,
+        category = new.category
+    WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER jobs_delete AFTER DELETE ON jobs
+BEGIN
+    DELETE FROM jobs_fts WHERE rowid = old.id;
+END;
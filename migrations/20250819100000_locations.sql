@@ -0,0 +1,31 @@
+-- Structured locations for jobs and profiles. `jobs.location` stays as the
+-- free-text label; country is ISO 3166-1 alpha-2 and timezone an IANA name.
+-- Working hours are local "HH:MM" in that timezone, also kept as a window of UTC
+-- minutes (start of day offset and length) so overlaps can be compared in SQL.
+ALTER TABLE jobs ADD COLUMN remote BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE jobs ADD COLUMN country TEXT;
+ALTER TABLE jobs ADD COLUMN city TEXT;
+ALTER TABLE jobs ADD COLUMN latitude REAL;
+ALTER TABLE jobs ADD COLUMN longitude REAL;
+ALTER TABLE jobs ADD COLUMN timezone TEXT;
+ALTER TABLE jobs ADD COLUMN work_start TEXT;
+ALTER TABLE jobs ADD COLUMN work_end TEXT;
+ALTER TABLE jobs ADD COLUMN work_start_utc INTEGER;
+ALTER TABLE jobs ADD COLUMN work_minutes INTEGER;
+
+ALTER TABLE profiles ADD COLUMN country TEXT;
+ALTER TABLE profiles ADD COLUMN city TEXT;
+ALTER TABLE profiles ADD COLUMN latitude REAL;
+ALTER TABLE profiles ADD COLUMN longitude REAL;
+ALTER TABLE profiles ADD COLUMN timezone TEXT;
+ALTER TABLE profiles ADD COLUMN work_start TEXT;
+ALTER TABLE profiles ADD COLUMN work_end TEXT;
+ALTER TABLE profiles ADD COLUMN work_start_utc INTEGER;
+ALTER TABLE profiles ADD COLUMN work_minutes INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_country ON jobs(country);
+CREATE INDEX IF NOT EXISTS idx_jobs_latitude ON jobs(latitude);
+
+-- Free-text labels that already meant "anywhere"
+UPDATE jobs SET remote = TRUE
+WHERE LOWER(TRIM(location)) IN ('remote', 'anywhere', 'worldwide');
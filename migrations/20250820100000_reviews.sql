@@ -0,0 +1,34 @@
+-- Mutual reviews between a job's client and its approved freelancer, one per
+-- side, written once the job is completed. `completed_at` starts the review window.
+CREATE TABLE IF NOT EXISTS reviews (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id INTEGER NOT NULL,
+    reviewer_id INTEGER NOT NULL,
+    reviewee_id INTEGER NOT NULL,
+    rating INTEGER NOT NULL CHECK(rating BETWEEN 1 AND 5),
+    review TEXT,
+    review_ipfs_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (job_id, reviewer_id),
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (reviewer_id) REFERENCES users(id),
+    FOREIGN KEY (reviewee_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reviews_reviewee ON reviews(reviewee_id);
+
+ALTER TABLE jobs ADD COLUMN completed_at TEXT;
+
+-- Jobs completed before this have no completion time; the last submission is
+-- the closest thing to it. Reposted jobs carry an RFC 3339 `posted_at`, so the
+-- result is normalised to the "YYYY-MM-DD HH:MM:SS" that review windows compare
+UPDATE jobs SET completed_at = datetime(COALESCE(
+    (
+        SELECT MAX(d.submitted_at)
+        FROM job_deliverables d
+        JOIN job_applications ja ON ja.id = d.application_id
+        WHERE ja.job_id = jobs.id
+    ),
+    posted_at
+))
+WHERE status = 'completed';
@@ -0,0 +1,136 @@
+-- Postgres twin of ../20250717153940_create_tables.sql.
+-- Ids and unix timestamps are BIGINT so they decode as i64 like on SQLite;
+-- timestamp columns stored as TEXT keep SQLite's "YYYY-MM-DD HH:MM:SS" format.
+
+--users
+CREATE TABLE IF NOT EXISTS users (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT UNIQUE,
+    password TEXT, -- Hashed, nullable for Web3 users
+    wallet_address TEXT UNIQUE,
+    role TEXT NOT NULL CHECK ( role IN ('freelancer', 'client')),
+    wallet_user BOOLEAN NOT NULL DEFAULT FALSE,
+    verified_wallet BOOLEAN NOT NULL DEFAULT FALSE,
+    admin BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- Jobs
+CREATE TABLE IF NOT EXISTS jobs (
+    id BIGSERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    skills TEXT NOT NULL, -- comma-separated or JSON if needed
+    budget BIGINT NOT NULL,
+    location TEXT NOT NULL,
+    job_type TEXT NOT NULL,
+    job_ipfs_hash TEXT NOT NULL,
+    posted_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    deadline TEXT NOT NULL,
+    client_id BIGINT NOT NULL REFERENCES users(id),
+    category TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('open','closed','submitted', 'completed'))
+);
+
+-- Profiles
+CREATE TABLE IF NOT EXISTS profiles (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id),
+    username TEXT UNIQUE NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('freelancer', 'client')),
+    bio TEXT,
+    skills TEXT, -- comma-separated
+    certifications TEXT,
+    work_history TEXT,
+    profile_ipfs_hash TEXT,
+    created_at TEXT DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    updated_at TEXT DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+--nonces table
+CREATE TABLE IF NOT EXISTS nonces (
+    wallet_address TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    created_at TEXT NOT NULL, -- ISO 8601
+    expires_at TEXT NOT NULL, -- ISO 8601
+    PRIMARY KEY (wallet_address, nonce)
+);
+
+-- Full-text search over title and description; stands in for SQLite's jobs_fts (FTS5)
+ALTER TABLE jobs ADD COLUMN search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B')
+) STORED;
+CREATE INDEX idx_jobs_search ON jobs USING GIN (search);
+
+--blacklisted_token table
+CREATE TABLE blacklisted_tokens (
+    token TEXT PRIMARY KEY,
+    expires_at BIGINT NOT NULL
+);
+CREATE INDEX idx_blacklisted_tokens_expires_at ON blacklisted_tokens(expires_at);
+
+--Proposals table
+CREATE TABLE IF NOT EXISTS proposals (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    freelancer_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    cover_letter TEXT NOT NULL,
+    bid_amount DOUBLE PRECISION NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('submitted', 'accepted', 'rejected')),
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_applications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    applied_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    approved BOOLEAN DEFAULT FALSE,
+    approved_at TEXT,
+    freelancer_wallet TEXT NOT NULL,
+    UNIQUE(user_id, job_id) -- A user can only apply once to a job
+);
+
+CREATE TABLE IF NOT EXISTS saved_jobs (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    saved_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    PRIMARY KEY (user_id, job_id)
+);
+
+CREATE TABLE IF NOT EXISTS job_deliverables (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL UNIQUE REFERENCES job_applications(id) ON DELETE CASCADE,
+    ipfs_hash TEXT NOT NULL,
+    submitted BOOLEAN NOT NULL DEFAULT FALSE,
+    submitted_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    disputed BOOLEAN DEFAULT FALSE,
+    disputed_at TEXT,
+    timeout_claimed BOOLEAN DEFAULT FALSE,
+    timeout_claimed_at TEXT,
+    review_requested BOOLEAN DEFAULT FALSE,
+    review_requested_at TEXT,
+    cancelled BOOLEAN DEFAULT FALSE,
+    cancelled_at TEXT,
+    resolved TEXT DEFAULT '0',
+    arbiter_id BIGINT REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE, -- the recipient (job creator)
+    message TEXT NOT NULL,
+    read BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    type TEXT DEFAULT 'generic',
+    job_id BIGINT,
+    actor_id BIGINT,
+    escrow_pda TEXT
+);
+
+CREATE TABLE job_user_interactions (
+    user_id     BIGINT NOT NULL REFERENCES users(id),
+    job_id      BIGINT NOT NULL REFERENCES jobs(id),
+    applied     BOOLEAN DEFAULT FALSE,
+    saved       BOOLEAN DEFAULT FALSE,
+    PRIMARY KEY (user_id, job_id)
+);
@@ -0,0 +1,14 @@
+-- TOTP two-factor authentication
+
+ALTER TABLE users ADD COLUMN totp_secret TEXT; -- base32, written on enrolment
+ALTER TABLE users ADD COLUMN totp_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT; -- last accepted 30s step, blocks code replay
+
+--recovery codes table
+CREATE TABLE IF NOT EXISTS two_factor_recovery_codes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL, -- SHA-256 hex of the normalized code
+    used_at BIGINT
+);
+CREATE INDEX idx_two_factor_recovery_codes_user_id ON two_factor_recovery_codes(user_id);
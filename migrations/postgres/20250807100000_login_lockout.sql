@@ -0,0 +1,10 @@
+-- Brute-force protection for email/password logins
+
+ALTER TABLE users ADD COLUMN failed_login_attempts BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until BIGINT; -- unix timestamp, logins refused until then
+
+-- One outstanding nonce per wallet; request_nonce now replaces instead of piling up
+DELETE FROM nonces n
+USING nonces newer
+WHERE n.wallet_address = newer.wallet_address AND n.created_at < newer.created_at;
+CREATE INDEX idx_nonces_expires_at ON nonces(expires_at);
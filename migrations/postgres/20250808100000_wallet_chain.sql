@@ -0,0 +1,6 @@
+-- Wallets can be Solana (ed25519/base58) or EVM (secp256k1/0x-hex)
+
+ALTER TABLE users ADD COLUMN wallet_chain TEXT CHECK (wallet_chain IN ('solana', 'evm'));
+UPDATE users SET wallet_chain = 'solana' WHERE wallet_address IS NOT NULL;
+
+ALTER TABLE nonces ADD COLUMN chain TEXT NOT NULL DEFAULT 'solana' CHECK (chain IN ('solana', 'evm'));
@@ -0,0 +1,28 @@
+-- Negotiation thread on an application: every bid and counter-offer, oldest first.
+-- The latest offer is the standing one; whoever didn't make it may accept or counter.
+CREATE TABLE IF NOT EXISTS proposal_offers (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL REFERENCES job_applications(id) ON DELETE CASCADE,
+    author_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    timeline_days BIGINT,
+    message TEXT,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_proposal_offers_application ON proposal_offers(application_id, id);
+
+-- Terms locked in when the freelancer is hired; escrow is funded with agreed_amount
+ALTER TABLE job_applications ADD COLUMN agreed_amount DOUBLE PRECISION;
+ALTER TABLE job_applications ADD COLUMN agreed_timeline_days BIGINT;
+
+-- The original bid opens each existing thread
+INSERT INTO proposal_offers (application_id, author_id, amount, timeline_days, created_at)
+SELECT id, user_id, bid_amount, timeline_days, applied_at
+FROM job_applications
+WHERE bid_amount IS NOT NULL;
+
+UPDATE job_applications
+SET agreed_amount = COALESCE(bid_amount, (SELECT budget FROM jobs WHERE jobs.id = job_applications.job_id)),
+    agreed_timeline_days = timeline_days
+WHERE approved;
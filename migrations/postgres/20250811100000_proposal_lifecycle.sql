@@ -0,0 +1,20 @@
+-- Freelancers can withdraw a submitted proposal, and proposals on closed or
+-- past-deadline jobs expire
+ALTER TABLE job_applications DROP CONSTRAINT job_applications_status_check;
+ALTER TABLE job_applications ADD CONSTRAINT job_applications_status_check
+    CHECK (status IN ('submitted', 'accepted', 'rejected', 'withdrawn', 'expired'));
+
+ALTER TABLE job_applications ADD COLUMN updated_at TEXT;
+
+-- The terms as they stood before each edit, so the client can see what changed
+CREATE TABLE IF NOT EXISTS proposal_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL REFERENCES job_applications(id) ON DELETE CASCADE,
+    cover_letter TEXT,
+    bid_amount DOUBLE PRECISION,
+    timeline_days BIGINT,
+    freelancer_wallet TEXT,
+    revised_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_proposal_revisions_application ON proposal_revisions(application_id, id);
@@ -0,0 +1,18 @@
+-- Invite-only jobs stay out of public search; only invited freelancers see and apply to them
+ALTER TABLE jobs ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public'
+    CHECK (visibility IN ('public', 'invite_only'));
+
+-- A client asking a specific freelancer to apply to one of their jobs
+CREATE TABLE IF NOT EXISTS job_invitations (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    client_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    freelancer_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    message TEXT,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined')),
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    responded_at TEXT,
+    UNIQUE(job_id, freelancer_id) -- One invitation per freelancer per job
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_invitations_freelancer ON job_invitations(freelancer_id, status);
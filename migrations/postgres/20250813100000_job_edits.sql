@@ -0,0 +1,15 @@
+-- Clients can edit a job until they hire, close it without hiring, or repost a
+-- closed/expired one as a fresh listing
+ALTER TABLE jobs ADD COLUMN updated_at TEXT;
+ALTER TABLE jobs ADD COLUMN reposted_from BIGINT REFERENCES jobs(id);
+
+-- One row per edit; changes is a JSON object of {field: {"from": old, "to": new}}
+CREATE TABLE IF NOT EXISTS job_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    editor_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    changes TEXT NOT NULL,
+    edited_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_revisions_job ON job_revisions(job_id, id);
@@ -0,0 +1,46 @@
+-- Jobs are either fixed-price or hourly. On an hourly job the budget and bids are
+-- hourly rates and the client caps the hours billed per week. job_type used to be
+-- free text; anything that mentions hours becomes 'hourly', the rest 'fixed'. The
+-- text as entered moves to legacy_job_type, which stays NULL for newer jobs.
+ALTER TABLE jobs ADD COLUMN legacy_job_type TEXT;
+UPDATE jobs SET legacy_job_type = job_type;
+UPDATE jobs SET job_type = CASE WHEN lower(job_type) LIKE '%hour%' THEN 'hourly' ELSE 'fixed' END;
+ALTER TABLE jobs ALTER COLUMN job_type SET DEFAULT 'fixed';
+ALTER TABLE jobs ADD CONSTRAINT jobs_job_type_check CHECK (job_type IN ('fixed', 'hourly'));
+
+ALTER TABLE jobs ADD COLUMN weekly_hour_cap BIGINT CHECK (weekly_hour_cap BETWEEN 1 AND 168);
+UPDATE jobs SET weekly_hour_cap = 40 WHERE job_type = 'hourly';
+
+-- Hours the hired freelancer logs against an hourly contract
+CREATE TABLE IF NOT EXISTS time_entries (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL REFERENCES job_applications(id) ON DELETE CASCADE,
+    work_date TEXT NOT NULL, -- YYYY-MM-DD
+    hours DOUBLE PRECISION NOT NULL CHECK (hours > 0 AND hours <= 24),
+    description TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_application ON time_entries(application_id, work_date);
+
+-- One timesheet per contract week (starting Monday). The client approves it, then
+-- records the escrow they funded on-chain for the week's amount and, later, that
+-- they released it. Those two steps are bookkeeping only: nothing here checks
+-- the chain, so the escrow account itself is the source of truth for payment.
+CREATE TABLE IF NOT EXISTS timesheets (
+    id BIGSERIAL PRIMARY KEY,
+    application_id BIGINT NOT NULL REFERENCES job_applications(id) ON DELETE CASCADE,
+    week_start TEXT NOT NULL,
+    hours DOUBLE PRECISION NOT NULL,
+    hourly_rate DOUBLE PRECISION NOT NULL,
+    amount DOUBLE PRECISION NOT NULL,
+    status TEXT NOT NULL DEFAULT 'submitted'
+        CHECK (status IN ('submitted', 'approved', 'rejected', 'escrow_recorded', 'release_recorded')),
+    review_note TEXT,
+    escrow_pda TEXT,
+    submitted_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    reviewed_at TEXT,
+    escrow_recorded_at TEXT,
+    release_recorded_at TEXT,
+    UNIQUE (application_id, week_start)
+);
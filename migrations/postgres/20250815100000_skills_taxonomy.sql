@@ -0,0 +1,73 @@
+-- Canonical skills replace free-text matching on the comma-separated columns.
+-- jobs.skills and profiles.skills stay as the display copy (rewritten with the
+-- canonical names on save); filtering goes through job_skills/profile_skills.
+CREATE TABLE IF NOT EXISTS skills (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_skills_name ON skills(lower(name));
+
+-- Every lowercased name a skill goes by, including its canonical name
+CREATE TABLE IF NOT EXISTS skill_aliases (
+    alias TEXT PRIMARY KEY,
+    skill_id BIGINT NOT NULL REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS job_skills (
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    skill_id BIGINT NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+    PRIMARY KEY (job_id, skill_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_skills_skill ON job_skills(skill_id);
+
+CREATE TABLE IF NOT EXISTS profile_skills (
+    user_id BIGINT NOT NULL REFERENCES profiles(user_id) ON DELETE CASCADE,
+    skill_id BIGINT NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, skill_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_profile_skills_skill ON profile_skills(skill_id);
+
+-- Seed the common spellings so existing lists resolve to one skill
+INSERT INTO skills (name) VALUES
+    ('JavaScript'), ('TypeScript'), ('Node.js'), ('React'), ('Go'), ('Python'),
+    ('PostgreSQL'), ('Kubernetes'), ('C#'), ('C++'), ('Rust'), ('Solidity');
+INSERT INTO skill_aliases (alias, skill_id) SELECT lower(name), id FROM skills;
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT v.alias, s.id
+FROM (VALUES
+    ('js', 'JavaScript'), ('ts', 'TypeScript'), ('node', 'Node.js'), ('nodejs', 'Node.js'),
+    ('reactjs', 'React'), ('react.js', 'React'), ('golang', 'Go'), ('py', 'Python'),
+    ('postgres', 'PostgreSQL'), ('k8s', 'Kubernetes'), ('csharp', 'C#'), ('cpp', 'C++')
+) AS v(alias, name)
+JOIN skills s ON s.name = v.name;
+
+-- Split the existing comma lists once
+CREATE TEMP TABLE legacy_skills AS
+SELECT 'job' AS source, j.id AS owner_id, trim(item) AS item
+FROM jobs j, unnest(string_to_array(j.skills, ',')) AS item
+UNION ALL
+SELECT 'profile', p.user_id, trim(item)
+FROM profiles p, unnest(string_to_array(p.skills, ',')) AS item;
+DELETE FROM legacy_skills WHERE item = '';
+
+INSERT INTO skills (name)
+SELECT MIN(item) FROM legacy_skills
+WHERE lower(item) NOT IN (SELECT alias FROM skill_aliases)
+GROUP BY lower(item);
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT lower(name), id FROM skills WHERE lower(name) NOT IN (SELECT alias FROM skill_aliases);
+
+INSERT INTO job_skills (job_id, skill_id)
+SELECT DISTINCT l.owner_id, sa.skill_id
+FROM legacy_skills l JOIN skill_aliases sa ON sa.alias = lower(l.item)
+WHERE l.source = 'job';
+INSERT INTO profile_skills (user_id, skill_id)
+SELECT DISTINCT l.owner_id, sa.skill_id
+FROM legacy_skills l JOIN skill_aliases sa ON sa.alias = lower(l.item)
+WHERE l.source = 'profile';
+
+DROP TABLE legacy_skills;
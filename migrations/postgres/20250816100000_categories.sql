@@ -0,0 +1,40 @@
+-- Categories move from a hard-coded list to an admin-managed table with one level
+-- of subcategories. jobs.category keeps the display name; category_id is the link.
+CREATE TABLE IF NOT EXISTS categories (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    parent_id BIGINT REFERENCES categories(id),
+    description TEXT,
+    position BIGINT NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    updated_at TEXT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_name ON categories(lower(name));
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id, position);
+
+INSERT INTO categories (name, slug, position) VALUES
+    ('Web Development', 'web-development', 1),
+    ('Graphic Design', 'graphic-design', 2),
+    ('Writing', 'writing', 3),
+    ('Digital Marketing', 'digital-marketing', 4),
+    ('Video Editing', 'video-editing', 5),
+    ('Music Production', 'music-production', 6);
+
+-- Keep categories existing jobs already use. Slugs start as the lowercased name
+-- (unique per group) and get hyphens where that doesn't collide.
+INSERT INTO categories (name, slug, position)
+SELECT MIN(trim(category)), lower(trim(category)), 100 FROM jobs
+WHERE trim(category) <> '' AND lower(trim(category)) NOT IN (SELECT lower(name) FROM categories)
+GROUP BY lower(trim(category));
+UPDATE categories SET slug = replace(slug, ' ', '-')
+WHERE slug LIKE '% %'
+  AND NOT EXISTS (SELECT 1 FROM categories c WHERE c.slug = replace(categories.slug, ' ', '-'));
+
+ALTER TABLE jobs ADD COLUMN category_id BIGINT REFERENCES categories(id);
+UPDATE jobs SET category_id = (SELECT id FROM categories WHERE lower(name) = lower(trim(jobs.category)));
+UPDATE jobs SET category = (SELECT name FROM categories WHERE id = jobs.category_id)
+WHERE category_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_category ON jobs(category_id);
@@ -0,0 +1,20 @@
+-- Saved job filters that alert the freelancer about new matching jobs.
+-- `query` holds the serialized JobFilterQuery; `last_job_id` is the newest job
+-- already considered, so each job alerts at most once per search.
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    last_job_id BIGINT NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    updated_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_user ON saved_searches(user_id);
+
+-- saved_jobs is the source of truth; keep the interaction flag in step with it
+INSERT INTO job_user_interactions (user_id, job_id, saved)
+SELECT user_id, job_id, TRUE FROM saved_jobs
+ON CONFLICT(user_id, job_id) DO UPDATE SET saved = TRUE;
@@ -0,0 +1,12 @@
+-- Job search covers skills and category too, weighted like the SQLite bm25 ranking:
+-- title, then skills, then category, then description
+DROP INDEX IF EXISTS idx_jobs_search;
+ALTER TABLE jobs DROP COLUMN search;
+ALTER TABLE jobs ADD COLUMN search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(skills, '')), 'B') ||
+    setweight(to_tsvector('english', coalesce(category, '')), 'C') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'D')
+) STORED;
+CREATE INDEX idx_jobs_search ON jobs USING GIN (search);
+
@@ -0,0 +1,33 @@
+-- Mutual reviews between a job's client and its approved freelancer, one per
+-- side, written once the job is completed. `completed_at` starts the review window.
+CREATE TABLE IF NOT EXISTS reviews (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    reviewer_id BIGINT NOT NULL REFERENCES users(id),
+    reviewee_id BIGINT NOT NULL REFERENCES users(id),
+    rating BIGINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    review TEXT,
+    review_ipfs_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    UNIQUE (job_id, reviewer_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reviews_reviewee ON reviews(reviewee_id);
+
+ALTER TABLE jobs ADD COLUMN completed_at TEXT;
+
+-- Jobs completed before this have no completion time; the last submission is
+-- the closest thing to it. Reposted jobs carry an RFC 3339 `posted_at`, so the
+-- result is normalised to the "YYYY-MM-DD HH:MM:SS" that review windows compare.
+-- Migrations run in a transaction, so the UTC session zone stays local to this one
+SET LOCAL TIME ZONE 'UTC';
+UPDATE jobs SET completed_at = to_char(COALESCE(
+    (
+        SELECT MAX(d.submitted_at)
+        FROM job_deliverables d
+        JOIN job_applications ja ON ja.id = d.application_id
+        WHERE ja.job_id = jobs.id
+    ),
+    posted_at
+)::timestamptz, 'YYYY-MM-DD HH24:MI:SS')
+WHERE status = 'completed';
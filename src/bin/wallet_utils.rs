@@ -18,30 +18,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let derived_address = to_checksum(&wallet.address(), None); // 0x70997970C51812dc3A010C7d01b50e0d17dc79C8
 
     let args: Vec<String> = std::env::args().collect();
-    
-    if args.len() < 3 {
-        eprintln!("Usage: {} <nonce> <wallet_address>", args[0]);
-        eprintln!("Example: {} testnonce 0x70997970C51812dc3A010C7d01b50e0d17dc79C8", args[0]);
-        std::process::exit(1);
-    }
 
-    let nonce = args[1].clone();
-    let wallet_address = args[2].clone();
-
-    // Validate wallet_address format and match with private key
-    if !wallet_address.starts_with("0x") || wallet_address.len() != 42 {
-        eprintln!("Error: Invalid wallet_address format. Must be a 42-character hex string starting with '0x'.");
+    if args.len() < 2 {
+        eprintln!("Usage: {} <message>", args[0]);
+        eprintln!("Signs the `message` returned by /wallet/request-nonce (with \"chain\": \"evm\") for {}", derived_address);
         std::process::exit(1);
     }
-    if wallet_address.to_lowercase() != derived_address.to_lowercase() {
-        eprintln!("Error: Provided wallet_address {} does not match the address derived from the private key {}", wallet_address, derived_address);
+
+    // The SIWE message already names the wallet and nonce
+    let message = args[1].clone();
+    if !message.contains(&derived_address) {
+        eprintln!("Error: Message is not addressed to the wallet derived from the private key {}", derived_address);
         std::process::exit(1);
     }
 
-    let message = format!(
-        "Welcome to Freelancia!\n\nPlease sign this message to authenticate your wallet.\n\nWallet: {}\nNonce: {}",
-        wallet_address, nonce
-    );
     let signature = generate_signature(&message, private_key).await?;
     println!("Signature: {}", signature);
     Ok(())
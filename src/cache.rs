@@ -0,0 +1,59 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Past this many entries, expired ones are swept and new ones skipped while still full
+const MAX_ENTRIES: usize = 10_000;
+
+// In-process TTL cache for read-heavy responses that can be a little stale.
+// Values are stored as JSON so one cache can hold any serializable type.
+#[derive(Clone, Default)]
+pub struct Cache {
+    entries: Arc<Mutex<HashMap<String, (Instant, Value)>>>,
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => {
+                serde_json::from_value(value.clone()).ok()
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert<T: Serialize>(&self, key: impl Into<String>, value: &T, ttl: Duration) {
+        if let Ok(value) = serde_json::to_value(value) {
+            let mut entries = self.entries.lock().expect("cache lock poisoned");
+            if entries.len() >= MAX_ENTRIES {
+                let now = Instant::now();
+                entries.retain(|_, (expires_at, _)| *expires_at > now);
+                if entries.len() >= MAX_ENTRIES {
+                    return;
+                }
+            }
+            entries.insert(key.into(), (Instant::now() + ttl, value));
+        }
+    }
+
+    // Drop every entry whose key starts with `prefix`, e.g. after a write
+    pub fn invalidate_prefix(&self, prefix: &str) {
+        self.entries
+            .lock()
+            .expect("cache lock poisoned")
+            .retain(|key, _| !key.starts_with(prefix));
+    }
+}
@@ -1,5 +1,430 @@
-use std::env;
+use crate::middleware::rate_limit::{AuthRateLimits, EndpointLimit, Quota};
+use chrono::Duration;
+use clap::Parser;
+use http::HeaderValue;
+use serde::Deserialize;
+use std::{env, fs, net::SocketAddr, path::PathBuf};
+use thiserror::Error;
 
-pub fn jwt_secret() -> String {
-    env::var("JWT_SECRET").expect("JWT_SECRET must be set in .env")
-}
\ No newline at end of file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{0} must be set")]
+    Missing(&'static str),
+    #[error("{key} is invalid: {reason}")]
+    Invalid { key: &'static str, reason: String },
+    #[error("failed to read config file {path}: {reason}")]
+    File { path: PathBuf, reason: String },
+}
+
+// Command line flags; anything given here overrides the file and the environment
+#[derive(Debug, Default, Parser)]
+#[command(name = "freelancia_backend", about = "Freelancia API server")]
+pub struct Cli {
+    /// TOML config file (also FREELANCIA_CONFIG)
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Address to listen on, e.g. 0.0.0.0:3000
+    #[arg(long)]
+    pub bind_addr: Option<String>,
+    #[arg(long)]
+    pub database_url: Option<String>,
+    #[arg(long)]
+    pub frontend_url: Option<String>,
+    /// Override one auth rate limit, e.g. `login_per_account=5/1m`; repeatable
+    #[arg(long = "rate-limit", value_name = "NAME=QUOTA")]
+    pub rate_limits: Vec<String>,
+    /// Apply pending database migrations and exit
+    #[arg(long)]
+    pub migrate_only: bool,
+}
+
+// Every setting in its raw form; filled from the file, then env, then CLI
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    bind_addr: Option<String>,
+    database_url: Option<String>,
+    frontend_url: Option<String>,
+    cors_origins: Option<Vec<String>>,
+    jwt_secret: Option<String>,
+    token_ttl_hours: Option<i64>,
+    nonce_ttl_minutes: Option<i64>,
+    verify_email_ttl_hours: Option<i64>,
+    password_reset_ttl_minutes: Option<i64>,
+    claim_window_days: Option<i64>,
+    review_window_days: Option<i64>,
+    evm_chain_id: Option<u64>,
+    solana_rpc_url: Option<String>,
+    rate_limits: RawRateLimits,
+    #[serde(skip)]
+    migrate_only: bool,
+}
+
+// Auth endpoint quotas as "<burst>/<period>" strings; per-account ones may be "off".
+// In the file they sit under [rate_limits], in the environment as RATE_LIMIT_<NAME>
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRateLimits {
+    login_per_ip: Option<String>,
+    login_per_account: Option<String>,
+    signup_per_ip: Option<String>,
+    signup_per_account: Option<String>,
+    nonce_per_ip: Option<String>,
+    nonce_per_account: Option<String>,
+    verify_per_ip: Option<String>,
+    verify_per_account: Option<String>,
+    password_reset_per_ip: Option<String>,
+    password_reset_per_account: Option<String>,
+}
+
+impl RawRateLimits {
+    const NAMES: [&'static str; 10] = [
+        "login_per_ip",
+        "login_per_account",
+        "signup_per_ip",
+        "signup_per_account",
+        "nonce_per_ip",
+        "nonce_per_account",
+        "verify_per_ip",
+        "verify_per_account",
+        "password_reset_per_ip",
+        "password_reset_per_account",
+    ];
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut Option<String>> {
+        match name {
+            "login_per_ip" => Some(&mut self.login_per_ip),
+            "login_per_account" => Some(&mut self.login_per_account),
+            "signup_per_ip" => Some(&mut self.signup_per_ip),
+            "signup_per_account" => Some(&mut self.signup_per_account),
+            "nonce_per_ip" => Some(&mut self.nonce_per_ip),
+            "nonce_per_account" => Some(&mut self.nonce_per_account),
+            "verify_per_ip" => Some(&mut self.verify_per_ip),
+            "verify_per_account" => Some(&mut self.verify_per_account),
+            "password_reset_per_ip" => Some(&mut self.password_reset_per_ip),
+            "password_reset_per_account" => Some(&mut self.password_reset_per_account),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub bind_addr: SocketAddr,
+    pub database_url: String,
+    pub frontend_url: String,       // base for links in emails and the SIWE domain
+    pub cors_origins: Vec<HeaderValue>,
+    pub jwt_secret: String,
+    pub token_ttl: Duration,        // lifetime of session JWTs
+    pub nonce_ttl: Duration,        // wallet sign-in nonces
+    pub verify_email_ttl: Duration,
+    pub password_reset_ttl: Duration,
+    pub claim_window: Duration,     // client inactivity before a freelancer can claim completion
+    pub review_window: Duration,    // after completion, for both sides to review each other
+    pub evm_chain_id: u64,          // EIP-155 chain id put in Sign-In with Ethereum messages
+    pub solana_rpc_url: String,
+    pub rate_limits: AuthRateLimits, // per-IP and per-account quotas on the auth endpoints
+    pub migrate_only: bool,
+}
+
+impl RawConfig {
+    fn from_file(path: &PathBuf) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|e| ConfigError::File {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str(&contents).map_err(|e| ConfigError::File {
+            path: path.clone(),
+            reason: e.to_string(),
+        })
+    }
+
+    // Overlay values from an env-like lookup (`KEY` -> value)
+    fn merge_env(&mut self, lookup: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        fn parsed<T: std::str::FromStr>(
+            lookup: &impl Fn(&str) -> Option<String>,
+            key: &'static str,
+        ) -> Result<Option<T>, ConfigError>
+        where
+            T::Err: std::fmt::Display,
+        {
+            lookup(key)
+                .map(|value| {
+                    value.trim().parse().map_err(|e: T::Err| ConfigError::Invalid {
+                        key,
+                        reason: e.to_string(),
+                    })
+                })
+                .transpose()
+        }
+
+        macro_rules! overlay {
+            ($field:ident, $key:literal) => {
+                if let Some(value) = parsed(lookup, $key)? {
+                    self.$field = Some(value);
+                }
+            };
+        }
+
+        overlay!(bind_addr, "BIND_ADDR");
+        overlay!(database_url, "DATABASE_URL");
+        overlay!(frontend_url, "FRONTEND_URL");
+        overlay!(jwt_secret, "JWT_SECRET");
+        overlay!(token_ttl_hours, "TOKEN_TTL_HOURS");
+        overlay!(nonce_ttl_minutes, "NONCE_TTL_MINUTES");
+        overlay!(verify_email_ttl_hours, "VERIFY_EMAIL_TTL_HOURS");
+        overlay!(password_reset_ttl_minutes, "PASSWORD_RESET_TTL_MINUTES");
+        overlay!(claim_window_days, "CLAIM_WINDOW_DAYS");
+        overlay!(review_window_days, "REVIEW_WINDOW_DAYS");
+        overlay!(evm_chain_id, "EVM_CHAIN_ID");
+        overlay!(solana_rpc_url, "SOLANA_RPC_URL");
+
+        for name in RawRateLimits::NAMES {
+            if let Some(value) = lookup(&format!("RATE_LIMIT_{}", name.to_uppercase())) {
+                *self.rate_limits.field_mut(name).expect("listed in NAMES") = Some(value);
+            }
+        }
+
+        // Comma separated list
+        if let Some(origins) = lookup("CORS_ORIGINS") {
+            self.cors_origins = Some(
+                origins
+                    .split(',')
+                    .map(|origin| origin.trim().to_string())
+                    .filter(|origin| !origin.is_empty())
+                    .collect(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn merge_cli(&mut self, cli: Cli) -> Result<(), ConfigError> {
+        self.bind_addr = cli.bind_addr.or(self.bind_addr.take());
+        self.database_url = cli.database_url.or(self.database_url.take());
+        self.frontend_url = cli.frontend_url.or(self.frontend_url.take());
+        self.migrate_only = cli.migrate_only;
+
+        for setting in cli.rate_limits {
+            let field = setting
+                .split_once('=')
+                .and_then(|(name, value)| Some((self.rate_limits.field_mut(name.trim())?, value)));
+            match field {
+                Some((field, value)) => *field = Some(value.trim().to_string()),
+                None => {
+                    return Err(ConfigError::Invalid {
+                        key: "--rate-limit",
+                        reason: format!("`{}` must look like login_per_ip=20/1m", setting),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn positive(key: &'static str, value: i64) -> Result<i64, ConfigError> {
+    if value > 0 {
+        Ok(value)
+    } else {
+        Err(ConfigError::Invalid {
+            key,
+            reason: "must be greater than zero".to_string(),
+        })
+    }
+}
+
+fn quota(key: &'static str, value: &str) -> Result<Quota, ConfigError> {
+    Quota::parse(value).map_err(|reason| ConfigError::Invalid { key, reason })
+}
+
+// `keys` name the per-IP and per-account settings; per-account limits can be
+// switched off, as they are by default for signup
+fn endpoint_limit(
+    keys: [&'static str; 2],
+    per_ip: Option<String>,
+    per_account: Option<String>,
+    default: EndpointLimit,
+) -> Result<EndpointLimit, ConfigError> {
+    let per_ip = match per_ip {
+        Some(value) => quota(keys[0], &value)?,
+        None => default.per_ip,
+    };
+    let per_account = match per_account {
+        Some(value) if value.trim().eq_ignore_ascii_case("off") => None,
+        Some(value) => Some(quota(keys[1], &value)?),
+        None => default.per_account,
+    };
+    Ok(EndpointLimit {
+        per_ip,
+        per_account,
+    })
+}
+
+fn http_url(key: &'static str, value: String) -> Result<String, ConfigError> {
+    if value.starts_with("http://") || value.starts_with("https://") {
+        Ok(value.trim_end_matches('/').to_string())
+    } else {
+        Err(ConfigError::Invalid {
+            key,
+            reason: format!("`{}` must start with http:// or https://", value),
+        })
+    }
+}
+
+impl TryFrom<RawConfig> for Config {
+    type Error = ConfigError;
+
+    fn try_from(raw: RawConfig) -> Result<Self, Self::Error> {
+        let bind_addr = raw
+            .bind_addr
+            .unwrap_or_else(|| "127.0.0.1:3000".to_string())
+            .parse()
+            .map_err(|e: std::net::AddrParseError| ConfigError::Invalid {
+                key: "BIND_ADDR",
+                reason: e.to_string(),
+            })?;
+
+        let database_url = raw
+            .database_url
+            .filter(|url| !url.trim().is_empty())
+            .ok_or(ConfigError::Missing("DATABASE_URL"))?;
+        if !["sqlite:", "postgres://", "postgresql://"]
+            .iter()
+            .any(|scheme| database_url.starts_with(scheme))
+        {
+            return Err(ConfigError::Invalid {
+                key: "DATABASE_URL",
+                reason: "must be a sqlite: or postgres:// URL".to_string(),
+            });
+        }
+
+        let jwt_secret = raw
+            .jwt_secret
+            .filter(|secret| !secret.trim().is_empty())
+            .ok_or(ConfigError::Missing("JWT_SECRET"))?;
+
+        let frontend_url = http_url(
+            "FRONTEND_URL",
+            raw.frontend_url
+                .unwrap_or_else(|| "http://localhost:5173".to_string()),
+        )?;
+
+        // The frontend is the only allowed origin unless told otherwise
+        let cors_origins = raw
+            .cors_origins
+            .unwrap_or_else(|| vec![frontend_url.clone()])
+            .into_iter()
+            .map(|origin| {
+                let origin = http_url("CORS_ORIGINS", origin)?;
+                HeaderValue::from_str(&origin).map_err(|e| ConfigError::Invalid {
+                    key: "CORS_ORIGINS",
+                    reason: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let defaults = AuthRateLimits::default();
+        let limits = raw.rate_limits;
+        let rate_limits = AuthRateLimits {
+            login: endpoint_limit(
+                ["RATE_LIMIT_LOGIN_PER_IP", "RATE_LIMIT_LOGIN_PER_ACCOUNT"],
+                limits.login_per_ip,
+                limits.login_per_account,
+                defaults.login,
+            )?,
+            signup: endpoint_limit(
+                ["RATE_LIMIT_SIGNUP_PER_IP", "RATE_LIMIT_SIGNUP_PER_ACCOUNT"],
+                limits.signup_per_ip,
+                limits.signup_per_account,
+                defaults.signup,
+            )?,
+            nonce: endpoint_limit(
+                ["RATE_LIMIT_NONCE_PER_IP", "RATE_LIMIT_NONCE_PER_ACCOUNT"],
+                limits.nonce_per_ip,
+                limits.nonce_per_account,
+                defaults.nonce,
+            )?,
+            verify: endpoint_limit(
+                ["RATE_LIMIT_VERIFY_PER_IP", "RATE_LIMIT_VERIFY_PER_ACCOUNT"],
+                limits.verify_per_ip,
+                limits.verify_per_account,
+                defaults.verify,
+            )?,
+            password_reset: endpoint_limit(
+                [
+                    "RATE_LIMIT_PASSWORD_RESET_PER_IP",
+                    "RATE_LIMIT_PASSWORD_RESET_PER_ACCOUNT",
+                ],
+                limits.password_reset_per_ip,
+                limits.password_reset_per_account,
+                defaults.password_reset,
+            )?,
+        };
+
+        Ok(Config {
+            bind_addr,
+            database_url,
+            frontend_url,
+            cors_origins,
+            jwt_secret,
+            token_ttl: Duration::hours(positive("TOKEN_TTL_HOURS", raw.token_ttl_hours.unwrap_or(24))?),
+            nonce_ttl: Duration::minutes(positive(
+                "NONCE_TTL_MINUTES",
+                raw.nonce_ttl_minutes.unwrap_or(15),
+            )?),
+            verify_email_ttl: Duration::hours(positive(
+                "VERIFY_EMAIL_TTL_HOURS",
+                raw.verify_email_ttl_hours.unwrap_or(24),
+            )?),
+            password_reset_ttl: Duration::minutes(positive(
+                "PASSWORD_RESET_TTL_MINUTES",
+                raw.password_reset_ttl_minutes.unwrap_or(60),
+            )?),
+            claim_window: Duration::days(positive(
+                "CLAIM_WINDOW_DAYS",
+                raw.claim_window_days.unwrap_or(3),
+            )?),
+            review_window: Duration::days(positive(
+                "REVIEW_WINDOW_DAYS",
+                raw.review_window_days.unwrap_or(14),
+            )?),
+            evm_chain_id: raw.evm_chain_id.unwrap_or(1),
+            solana_rpc_url: http_url(
+                "SOLANA_RPC_URL",
+                raw.solana_rpc_url
+                    .unwrap_or_else(|| "https://api.devnet.solana.com".to_string()),
+            )?,
+            rate_limits,
+            migrate_only: raw.migrate_only,
+        })
+    }
+}
+
+impl Config {
+    // Load once at startup: defaults < config file < environment < CLI flags
+    pub fn load() -> Result<Self, ConfigError> {
+        let cli = Cli::parse();
+        let lookup = |key: &str| env::var(key).ok();
+
+        let mut raw = match cli
+            .config
+            .clone()
+            .or_else(|| lookup("FREELANCIA_CONFIG").map(PathBuf::from))
+        {
+            Some(path) => RawConfig::from_file(&path)?,
+            None => RawConfig::default(),
+        };
+        raw.merge_env(&lookup)?;
+        raw.merge_cli(cli)?;
+
+        Config::try_from(raw)
+    }
+
+    // Defaults plus the given variables only; no file or CLI. Handy for tests and tools.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut raw = RawConfig::default();
+        raw.merge_env(&lookup)?;
+        Config::try_from(raw)
+    }
+}
@@ -1,9 +1,102 @@
-use sqlx::{Pool, Sqlite, SqlitePool};
-use std::env;
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    postgres::PgPoolOptions,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    PgPool, Pool, Sqlite, SqlitePool,
+};
+use std::str::FromStr;
 
+pub mod pagination;
+pub mod postgres;
+pub mod repo;
 
-pub async fn init_pool() -> Result<Pool<Sqlite>, sqlx::Error>{
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env");
-    SqlitePool::connect(&database_url).await
+// Schema embedded in the binary. Both folders use the same version numbers so a
+// deployment can move between backends; sqlx skips the postgres/ subfolder when
+// reading ./migrations.
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+pub static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+// A connection pool for whichever backend DATABASE_URL points at
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    Sqlite(SqlitePool),
+    Postgres(PgPool),
+}
+
+impl DbPool {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            let pool = PgPoolOptions::new().connect(database_url).await?;
+            return Ok(DbPool::Postgres(pool));
+        }
+
+        // Create the file on first start so migrations can build the schema
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+        Ok(DbPool::Sqlite(pool))
+    }
+
+    // Apply any pending migrations for this backend
+    pub async fn migrate(&self) -> Result<(), MigrateError> {
+        match self {
+            DbPool::Sqlite(pool) => {
+                adopt_hand_migrated_sqlite(pool).await?;
+                SQLITE_MIGRATOR.run(pool).await
+            }
+            DbPool::Postgres(pool) => POSTGRES_MIGRATOR.run(pool).await,
+        }
+    }
+
+    pub fn backend(&self) -> &'static str {
+        match self {
+            DbPool::Sqlite(_) => "sqlite",
+            DbPool::Postgres(_) => "postgres",
+        }
+    }
 }
 
+async fn sqlite_table_exists(pool: &SqlitePool, name: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+}
+
+// Deployments from before embedded migrations ran the baseline script by hand, so
+// they have its tables but no `_sqlx_migrations`. Replaying it would fail on
+// jobs_fts, so record the baseline as applied and let later migrations run
+async fn adopt_hand_migrated_sqlite(pool: &SqlitePool) -> Result<(), MigrateError> {
+    if sqlite_table_exists(pool, "_sqlx_migrations").await?
+        || !sqlite_table_exists(pool, "users").await?
+    {
+        return Ok(());
+    }
+    let Some(baseline) = SQLITE_MIGRATOR.iter().next() else {
+        return Ok(());
+    };
+
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES (?, ?, TRUE, ?, 0)",
+    )
+    .bind(baseline.version)
+    .bind(&*baseline.description)
+    .bind(&*baseline.checksum)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn init_pool(database_url: &str) -> Result<Pool<Sqlite>, sqlx::Error> {
+    match DbPool::connect(database_url).await? {
+        DbPool::Sqlite(pool) => Ok(pool),
+        DbPool::Postgres(_) => Err(sqlx::Error::Configuration(
+            "init_pool expects a sqlite: DATABASE_URL".into(),
+        )),
+    }
+}
@@ -0,0 +1,126 @@
+// Keyset pagination for list endpoints. A page ends with an opaque cursor holding
+// the last row's sort key and id; the next page starts strictly after that row, so
+// rows inserted in the meantime don't shift pages the way OFFSET does.
+use crate::error::AppError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+// A page of rows and the cursor for the next one, if any
+pub type Page<T> = (Vec<T>, Option<String>);
+
+#[derive(Debug, Default, Deserialize, Validate)]
+pub struct PageQuery {
+    #[validate(length(min = 1))]
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<i64>,
+}
+
+impl PageQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+}
+
+// Position after the last row of a page. `sort` names the ordering the cursor was
+// made for, so it can't be replayed against a different one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort: String,
+    pub key: String,
+    pub id: i64,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    // Fails with `AppError::BadRequest` for a malformed cursor or one from
+    // another sort order
+    pub fn decode(raw: &str, sort: &str) -> Result<Self, AppError> {
+        let cursor: Cursor = URL_SAFE_NO_PAD
+            .decode(raw)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or(AppError::BadRequest("Invalid cursor".into()))?;
+        if cursor.sort != sort {
+            return Err(AppError::BadRequest(
+                "Cursor belongs to a different sort order".into(),
+            ));
+        }
+        Ok(cursor)
+    }
+}
+
+// An ORDER BY of one sort column plus the id as tie-breaker
+pub struct Keyset<'a> {
+    pub name: &'a str,
+    pub column: &'a str,
+    pub descending: bool,
+    pub id_column: &'a str,
+    pub id_descending: bool,
+    // Compare the cursor key as a number (it is bound as text)
+    pub numeric: bool,
+}
+
+impl Keyset<'_> {
+    pub fn order_by(&self) -> String {
+        let direction = |descending| if descending { "DESC" } else { "ASC" };
+        format!(
+            "{} {}, {} {}",
+            self.column,
+            direction(self.descending),
+            self.id_column,
+            direction(self.id_descending)
+        )
+    }
+
+    // The condition for rows after `cursor`, with its parameters in order
+    pub fn after(&self, cursor: &Cursor) -> (String, Vec<String>) {
+        let op = |descending| if descending { "<" } else { ">" };
+        let key = if self.numeric { "CAST(? AS REAL)" } else { "?" };
+        (
+            format!(
+                "({column} {op} {key} OR ({column} = {key} AND {id} {id_op} ?))",
+                column = self.column,
+                op = op(self.descending),
+                key = key,
+                id = self.id_column,
+                id_op = op(self.id_descending)
+            ),
+            vec![
+                cursor.key.clone(),
+                cursor.key.clone(),
+                cursor.id.to_string(),
+            ],
+        )
+    }
+}
+
+// Trim a result fetched with `limit + 1` rows to the page, returning the cursor for
+// the next page when there is one. `key` gives a row's sort key and id
+pub fn paginate<T>(
+    mut rows: Vec<T>,
+    limit: i64,
+    sort: &str,
+    key: impl Fn(&T) -> (String, i64),
+) -> Page<T> {
+    if rows.len() as i64 <= limit {
+        return (rows, None);
+    }
+    rows.truncate(limit as usize);
+    let next = rows.last().map(|row| {
+        let (key, id) = key(row);
+        Cursor {
+            sort: sort.to_string(),
+            key,
+            id,
+        }
+        .encode()
+    });
+    (rows, next)
+}
@@ -0,0 +1,44 @@
+// Postgres versions of the queries that can't be written once for both backends.
+// Keyword search swaps FTS5's MATCH, bm25 and highlight() for the generated
+// `jobs.search` tsvector, ts_rank and ts_headline.
+use crate::error::AppError;
+use crate::models::job::JobSearchHit;
+use crate::models::search::SearchQuery;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+// ts_rank weights are {D, C, B, A}, i.e. description, category, skills, title, in
+// the proportions of the SQLite bm25 weights (ts_rank caps each weight at 1)
+const RELEVANCE: &str = "ts_rank('{0.1, 0.2, 0.5, 1.0}', j.search, query)::DOUBLE PRECISION";
+
+// Open public jobs matching the keyword search, best match first
+pub async fn search_jobs(
+    pool: &PgPool,
+    search: &SearchQuery,
+    limit: i64,
+) -> Result<Vec<JobSearchHit>, AppError> {
+    let sql = format!(
+        r#"
+        SELECT
+            j.id, j.title, j.description, j.skills, j.budget, j.location, j.remote,
+            j.country, j.city, j.latitude, j.longitude, j.timezone, j.work_start,
+            j.work_end, j.job_type, j.weekly_hour_cap, j.job_ipfs_hash, j.posted_at,
+            j.deadline, j.client_id, j.category, j.status, j.visibility,
+            {} AS relevance,
+            ts_headline('english', j.title, query,
+                'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') AS title_highlight,
+            ts_headline('english', j.description, query,
+                'StartSel=<mark>, StopSel=</mark>, MaxWords=24, MinWords=8') AS snippet
+        FROM jobs j, to_tsquery('english', $1) query
+        WHERE j.search @@ query AND j.status = 'open' AND j.visibility = 'public'
+        ORDER BY relevance DESC, j.id DESC
+        LIMIT $2
+        "#,
+        RELEVANCE
+    );
+    Ok(sqlx::query_as::<_, JobSearchHit>(&sql)
+        .bind(search.to_tsquery(&HashMap::new()))
+        .bind(limit)
+        .fetch_all(pool)
+        .await?)
+}
@@ -0,0 +1,627 @@
+use crate::db::pagination::{paginate, Cursor, Keyset, Page, PageQuery};
+use crate::error::AppError;
+use crate::models::client::ApprovedWorkResponse;
+use crate::models::job::{ApplicantResponse, JobType};
+use crate::models::proposal::{
+    CreateOffer, EditProposal, ProposalFilter, ProposalOffer, ProposalResponse, ProposalRevision,
+    ProposalStatus,
+};
+use crate::models::review::Reputation;
+use async_trait::async_trait;
+use sqlx::{FromRow, SqlitePool};
+
+// What a freelancer submits when applying; the terms are optional for wallet-only applications
+#[derive(Debug, Clone)]
+pub struct NewApplication {
+    pub user_id: i64,
+    pub job_id: i64,
+    pub freelancer_wallet: String,
+    pub cover_letter: Option<String>,
+    pub bid_amount: Option<f64>,
+    pub timeline_days: Option<i64>,
+}
+
+impl NewApplication {
+    pub fn new(user_id: i64, job_id: i64, freelancer_wallet: impl Into<String>) -> Self {
+        NewApplication {
+            user_id,
+            job_id,
+            freelancer_wallet: freelancer_wallet.into(),
+            cover_letter: None,
+            bid_amount: None,
+            timeline_days: None,
+        }
+    }
+}
+
+// An application with the job it targets and both parties' names
+#[derive(Debug, Clone, FromRow)]
+pub struct ApplicationDetail {
+    pub id: i64,
+    pub job_id: i64,
+    pub freelancer_id: i64,
+    pub freelancer_wallet: Option<String>,
+    pub approved: bool,
+    pub status: ProposalStatus,
+    pub bid_amount: Option<f64>,
+    pub job_title: String,
+    pub job_status: String,
+    pub job_budget: i64,
+    pub job_type: JobType,
+    pub client_id: i64,
+    pub freelancer_username: Option<String>,
+    pub client_username: Option<String>,
+}
+
+const PROPOSAL_COLUMNS: &str = r#"
+    ja.id, ja.job_id, ja.user_id AS freelancer_id, ja.cover_letter, ja.bid_amount,
+    ja.timeline_days, ja.freelancer_wallet, ja.status, ja.applied_at,
+    ja.agreed_amount, ja.agreed_timeline_days, ja.updated_at
+"#;
+const PROPOSAL_FROM: &str = "FROM job_applications ja JOIN jobs j ON j.id = ja.job_id";
+
+const APPLICANTS_SORT: &str = "applied_at:desc";
+
+// A proposal with the value of the column it was sorted on, for its page cursor
+#[derive(FromRow)]
+struct SortedProposal {
+    #[sqlx(flatten)]
+    proposal: ProposalResponse,
+    sort_key: String,
+}
+
+#[async_trait]
+pub trait ApplicationRepo: Send + Sync {
+    // Fails with `AppError::Conflict` when the user already applied to the job
+    async fn create_application(&self, application: &NewApplication) -> Result<i64, AppError>;
+    async fn find_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<ApplicationDetail>, AppError>;
+    // Approve the application on the agreed terms and close its job to further
+    // applicants; the job's other submitted applications are rejected and their
+    // freelancers returned. Fails with `AppError::Conflict` when the application is
+    // no longer submitted or the job already has a hire
+    async fn approve_application(
+        &self,
+        application_id: i64,
+        job_id: i64,
+        agreed_amount: f64,
+        agreed_timeline_days: Option<i64>,
+    ) -> Result<Vec<i64>, AppError>;
+    // These return false if the application was no longer awaiting a decision
+    async fn reject_application(&self, application_id: i64) -> Result<bool, AppError>;
+    async fn withdraw_application(&self, application_id: i64) -> Result<bool, AppError>;
+    // Keeps the previous terms as a revision; a new bid also joins the offer thread
+    async fn edit_application(
+        &self,
+        application_id: i64,
+        edit: &EditProposal,
+    ) -> Result<bool, AppError>;
+    async fn revisions_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<ProposalRevision>, AppError>;
+    // Expire submitted applications on jobs that closed or passed their deadline
+    async fn expire_stale_applications(&self) -> Result<Vec<ProposalResponse>, AppError>;
+    // Expire the submitted applications on one job, e.g. when its client closes it
+    async fn expire_job_applications(&self, job_id: i64)
+        -> Result<Vec<ProposalResponse>, AppError>;
+    async fn proposal(&self, application_id: i64) -> Result<Option<ProposalResponse>, AppError>;
+    async fn create_offer(
+        &self,
+        application_id: i64,
+        author_id: i64,
+        offer: &CreateOffer,
+    ) -> Result<ProposalOffer, AppError>;
+    // Oldest first; the last one is the standing offer
+    async fn offers_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<ProposalOffer>, AppError>;
+    async fn latest_offer(&self, application_id: i64) -> Result<Option<ProposalOffer>, AppError>;
+    async fn proposals_for_job(&self, job_id: i64) -> Result<Vec<ProposalResponse>, AppError>;
+    async fn proposals_for_freelancer(
+        &self,
+        user_id: i64,
+        filter: &ProposalFilter,
+        page: &PageQuery,
+    ) -> Result<Page<ProposalResponse>, AppError>;
+    // Latest applications first
+    async fn applicants_for_job(
+        &self,
+        job_id: i64,
+        page: &PageQuery,
+    ) -> Result<Page<ApplicantResponse>, AppError>;
+    async fn approved_work(&self, job_id: i64) -> Result<Option<ApprovedWorkResponse>, AppError>;
+    async fn approved_freelancers(&self, job_id: i64) -> Result<Vec<i64>, AppError>;
+}
+
+#[async_trait]
+impl ApplicationRepo for SqlitePool {
+    async fn create_application(&self, application: &NewApplication) -> Result<i64, AppError> {
+        let mut tx = self.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO job_applications
+                (user_id, job_id, freelancer_wallet, cover_letter, bid_amount, timeline_days)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            application.user_id,
+            application.job_id,
+            application.freelancer_wallet,
+            application.cover_letter,
+            application.bid_amount,
+            application.timeline_days
+        )
+        .execute(&mut *tx)
+        .await?;
+        let application_id = result.last_insert_rowid();
+
+        // The bid opens the negotiation thread
+        if let Some(amount) = application.bid_amount {
+            sqlx::query!(
+                "INSERT INTO proposal_offers (application_id, author_id, amount, timeline_days) VALUES (?, ?, ?, ?)",
+                application_id,
+                application.user_id,
+                amount,
+                application.timeline_days
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(application_id)
+    }
+
+    async fn find_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<ApplicationDetail>, AppError> {
+        // Profiles are optional on both sides; a missing one must not hide the application
+        Ok(sqlx::query_as::<_, ApplicationDetail>(
+            r#"
+            SELECT
+                ja.id, ja.job_id, ja.user_id AS freelancer_id, ja.freelancer_wallet,
+                COALESCE(ja.approved, 0) AS approved, ja.status, ja.bid_amount,
+                j.title AS job_title, j.status AS job_status, j.budget AS job_budget,
+                j.job_type, j.client_id,
+                pf.username AS freelancer_username, pc.username AS client_username
+            FROM job_applications ja
+            JOIN jobs j ON j.id = ja.job_id
+            LEFT JOIN profiles pf ON pf.user_id = ja.user_id
+            LEFT JOIN profiles pc ON pc.user_id = j.client_id
+            WHERE ja.id = ?
+            "#,
+        )
+        .bind(application_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn approve_application(
+        &self,
+        application_id: i64,
+        job_id: i64,
+        agreed_amount: f64,
+        agreed_timeline_days: Option<i64>,
+    ) -> Result<Vec<i64>, AppError> {
+        let mut tx = self.begin().await?;
+
+        // Checked in the same statement so two concurrent accepts can't both hire
+        let approved = sqlx::query!(
+            r#"
+            UPDATE job_applications
+            SET approved = 1, approved_at = CURRENT_TIMESTAMP, status = 'accepted',
+                agreed_amount = ?, agreed_timeline_days = ?
+            WHERE id = ? AND status = 'submitted'
+                AND NOT EXISTS (
+                    SELECT 1 FROM job_applications hired WHERE hired.job_id = ? AND hired.approved = 1
+                )
+            "#,
+            agreed_amount,
+            agreed_timeline_days,
+            application_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if approved.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "The application is no longer open or a freelancer has already been hired".into(),
+            ));
+        }
+
+        sqlx::query!("UPDATE jobs SET status = 'closed' WHERE id = ?", job_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let rejected = sqlx::query_scalar!(
+            r#"
+            UPDATE job_applications SET status = 'rejected', updated_at = CURRENT_TIMESTAMP
+            WHERE job_id = ? AND id != ? AND status = 'submitted'
+            RETURNING user_id
+            "#,
+            job_id,
+            application_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(rejected)
+    }
+
+    async fn reject_application(&self, application_id: i64) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            "UPDATE job_applications SET status = 'rejected', updated_at = CURRENT_TIMESTAMP WHERE id = ? AND status = 'submitted'",
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn withdraw_application(&self, application_id: i64) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            "UPDATE job_applications SET status = 'withdrawn', updated_at = CURRENT_TIMESTAMP WHERE id = ? AND status = 'submitted'",
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn edit_application(
+        &self,
+        application_id: i64,
+        edit: &EditProposal,
+    ) -> Result<bool, AppError> {
+        let mut tx = self.begin().await?;
+
+        let snapshot = sqlx::query!(
+            r#"
+            INSERT INTO proposal_revisions
+                (application_id, cover_letter, bid_amount, timeline_days, freelancer_wallet)
+            SELECT id, cover_letter, bid_amount, timeline_days, freelancer_wallet
+            FROM job_applications WHERE id = ? AND status = 'submitted'
+            "#,
+            application_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if snapshot.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE job_applications
+            SET cover_letter = COALESCE(?, cover_letter),
+                bid_amount = COALESCE(?, bid_amount),
+                timeline_days = COALESCE(?, timeline_days),
+                freelancer_wallet = COALESCE(?, freelancer_wallet),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING user_id, timeline_days
+            "#,
+            edit.cover_letter,
+            edit.bid_amount,
+            edit.timeline_days,
+            edit.payout_wallet,
+            application_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(amount) = edit.bid_amount {
+            sqlx::query!(
+                "INSERT INTO proposal_offers (application_id, author_id, amount, timeline_days) VALUES (?, ?, ?, ?)",
+                application_id,
+                updated.user_id,
+                amount,
+                updated.timeline_days
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revisions_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<ProposalRevision>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalRevision>(
+            r#"
+            SELECT id, application_id, cover_letter, bid_amount, timeline_days, freelancer_wallet, revised_at
+            FROM proposal_revisions WHERE application_id = ? ORDER BY id
+            "#,
+        )
+        .bind(application_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn expire_stale_applications(&self) -> Result<Vec<ProposalResponse>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalResponse>(
+            r#"
+            UPDATE job_applications SET status = 'expired', updated_at = CURRENT_TIMESTAMP
+            WHERE status = 'submitted' AND job_id IN (
+                SELECT id FROM jobs WHERE status != 'open' OR deadline < date('now')
+            )
+            RETURNING
+                id, job_id, user_id AS freelancer_id, cover_letter, bid_amount, timeline_days,
+                freelancer_wallet, status, applied_at, agreed_amount, agreed_timeline_days, updated_at
+            "#,
+        )
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn expire_job_applications(
+        &self,
+        job_id: i64,
+    ) -> Result<Vec<ProposalResponse>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalResponse>(
+            r#"
+            UPDATE job_applications SET status = 'expired', updated_at = CURRENT_TIMESTAMP
+            WHERE status = 'submitted' AND job_id = ?
+            RETURNING
+                id, job_id, user_id AS freelancer_id, cover_letter, bid_amount, timeline_days,
+                freelancer_wallet, status, applied_at, agreed_amount, agreed_timeline_days, updated_at
+            "#,
+        )
+        .bind(job_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn proposal(&self, application_id: i64) -> Result<Option<ProposalResponse>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalResponse>(&format!(
+            "SELECT {} {} WHERE ja.id = ?",
+            PROPOSAL_COLUMNS, PROPOSAL_FROM
+        ))
+        .bind(application_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn create_offer(
+        &self,
+        application_id: i64,
+        author_id: i64,
+        offer: &CreateOffer,
+    ) -> Result<ProposalOffer, AppError> {
+        Ok(sqlx::query_as::<_, ProposalOffer>(
+            r#"
+            INSERT INTO proposal_offers (application_id, author_id, amount, timeline_days, message)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id, application_id, author_id, amount, timeline_days, message, created_at
+            "#,
+        )
+        .bind(application_id)
+        .bind(author_id)
+        .bind(offer.amount)
+        .bind(offer.timeline_days)
+        .bind(&offer.message)
+        .fetch_one(self)
+        .await?)
+    }
+
+    async fn offers_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<ProposalOffer>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalOffer>(
+            r#"
+            SELECT id, application_id, author_id, amount, timeline_days, message, created_at
+            FROM proposal_offers WHERE application_id = ? ORDER BY id
+            "#,
+        )
+        .bind(application_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn latest_offer(&self, application_id: i64) -> Result<Option<ProposalOffer>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalOffer>(
+            r#"
+            SELECT id, application_id, author_id, amount, timeline_days, message, created_at
+            FROM proposal_offers WHERE application_id = ? ORDER BY id DESC LIMIT 1
+            "#,
+        )
+        .bind(application_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn proposals_for_job(&self, job_id: i64) -> Result<Vec<ProposalResponse>, AppError> {
+        Ok(sqlx::query_as::<_, ProposalResponse>(&format!(
+            "SELECT {} {} WHERE ja.job_id = ? ORDER BY ja.applied_at DESC, ja.id DESC",
+            PROPOSAL_COLUMNS, PROPOSAL_FROM
+        ))
+        .bind(job_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn proposals_for_freelancer(
+        &self,
+        user_id: i64,
+        filter: &ProposalFilter,
+        page: &PageQuery,
+    ) -> Result<Page<ProposalResponse>, AppError> {
+        let (name, column) = match filter.sort_by.as_str() {
+            "job.title" => ("job.title", "j.title"),
+            _ => ("status", "ja.status"), // Default to status
+        };
+        let keyset = Keyset {
+            name,
+            column,
+            descending: false,
+            id_column: "ja.id",
+            id_descending: true,
+            numeric: false,
+        };
+        let mut conditions = String::from("ja.user_id = ?");
+        let mut params = vec![user_id.to_string()];
+        if let Some(status) = filter.status {
+            conditions.push_str(" AND ja.status = ?");
+            params.push(status.as_str().to_string());
+        }
+        if let Some(raw) = &page.cursor {
+            let (after, values) = keyset.after(&Cursor::decode(raw, name)?);
+            conditions.push_str(" AND ");
+            conditions.push_str(&after);
+            params.extend(values);
+        }
+        let sql = format!(
+            "SELECT {}, {} AS sort_key {} WHERE {} ORDER BY {} LIMIT ?",
+            PROPOSAL_COLUMNS,
+            column,
+            PROPOSAL_FROM,
+            conditions,
+            keyset.order_by()
+        );
+        params.push((page.limit() + 1).to_string());
+
+        let mut query = sqlx::query_as::<_, SortedProposal>(&sql);
+        for param in params {
+            query = query.bind(param);
+        }
+        let (rows, next_cursor) =
+            paginate(query.fetch_all(self).await?, page.limit(), name, |row| {
+                (row.sort_key.clone(), row.proposal.id)
+            });
+        Ok((
+            rows.into_iter().map(|row| row.proposal).collect(),
+            next_cursor,
+        ))
+    }
+
+    async fn applicants_for_job(
+        &self,
+        job_id: i64,
+        page: &PageQuery,
+    ) -> Result<Page<ApplicantResponse>, AppError> {
+        let after = page
+            .cursor
+            .as_deref()
+            .map(|raw| Cursor::decode(raw, APPLICANTS_SORT))
+            .transpose()?;
+        let (after_key, after_id) = after.map(|c| (c.key, c.id)).unzip();
+        let limit = page.limit() + 1;
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                ja.id AS application_id,
+                ja.user_id AS freelancer_id,
+                p.username AS freelancer_username,
+                p.skills,
+                p.profile_ipfs_hash,
+                ja.applied_at,
+                ja.approved,
+                ja.approved_at,
+                ja.freelancer_wallet,
+                ja.cover_letter,
+                ja.bid_amount,
+                ja.timeline_days,
+                ja.status
+            FROM job_applications ja
+            JOIN users u ON ja.user_id = u.id
+            JOIN profiles p ON p.user_id = u.id
+            WHERE ja.job_id = ?
+                AND (? IS NULL OR ja.applied_at < ? OR (ja.applied_at = ? AND ja.id < ?))
+            ORDER BY ja.applied_at DESC, ja.id DESC
+            LIMIT ?
+            "#,
+            job_id,
+            after_key,
+            after_key,
+            after_key,
+            after_id,
+            limit
+        )
+        .fetch_all(self)
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|row| ApplicantResponse {
+                application_id: row.application_id,
+                freelancer_id: row.freelancer_id,
+                freelancer_username: row.freelancer_username,
+                skills: row.skills,
+                profile_ipfs_hash: row.profile_ipfs_hash,
+                applied_at: row.applied_at,
+                approved: row.approved,
+                approved_at: row.approved_at,
+                freelancer_wallet: row.freelancer_wallet,
+                cover_letter: row.cover_letter,
+                bid_amount: row.bid_amount,
+                timeline_days: row.timeline_days,
+                status: row.status,
+                reputation: Reputation::default(),
+            })
+            .collect();
+        Ok(paginate(rows, page.limit(), APPLICANTS_SORT, |row| {
+            (
+                row.applied_at.clone(),
+                row.application_id.unwrap_or_default(),
+            )
+        }))
+    }
+
+    async fn approved_work(&self, job_id: i64) -> Result<Option<ApprovedWorkResponse>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                ja.job_id, j.status, ja.approved_at, ja.applied_at, ja.freelancer_wallet, ja.id,
+                ja.agreed_amount, ja.agreed_timeline_days,
+                jd.ipfs_hash, jd.submitted_at, jd.disputed, jd.disputed_at, jd.submitted,
+                jd.review_requested, jd.review_requested_at, p.username
+            FROM job_applications ja
+            LEFT JOIN job_deliverables jd ON jd.application_id = ja.id
+            LEFT JOIN profiles p ON p.user_id = ja.user_id
+            LEFT JOIN jobs j ON j.id = ja.job_id
+            WHERE ja.job_id = ? AND ja.approved = 1
+            "#,
+            job_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| ApprovedWorkResponse {
+            job_id: row.job_id,
+            approved_at: row.approved_at,
+            applied_at: row.applied_at,
+            freelancer_wallet: row.freelancer_wallet,
+            application_id: row.id,
+            agreed_amount: row.agreed_amount,
+            agreed_timeline_days: row.agreed_timeline_days,
+            work_ipfs_hash: row.ipfs_hash,
+            submitted_at: row.submitted_at,
+            disputed: row.disputed,
+            disputed_at: row.disputed_at,
+            submitted: row.submitted,
+            review_requested: row.review_requested,
+            review_requested_at: row.review_requested_at,
+            freelancer_username: row.username,
+            job_status: row.status,
+        }))
+    }
+
+    async fn approved_freelancers(&self, job_id: i64) -> Result<Vec<i64>, AppError> {
+        Ok(sqlx::query_scalar!(
+            "SELECT user_id FROM job_applications WHERE job_id = ? AND approved = 1",
+            job_id
+        )
+        .fetch_all(self)
+        .await?)
+    }
+}
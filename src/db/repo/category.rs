@@ -0,0 +1,151 @@
+use crate::error::AppError;
+use crate::models::category::{Category, CreateCategory, UpdateCategory};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const CATEGORY_SELECT: &str = r#"
+    SELECT
+        c.id, c.name, c.slug, c.parent_id, c.description, c.position,
+        (SELECT COUNT(*) FROM jobs j
+         WHERE j.category_id = c.id AND j.status = 'open' AND j.visibility = 'public') AS job_count
+    FROM categories c
+"#;
+
+#[async_trait]
+pub trait CategoryRepo: Send + Sync {
+    async fn list_categories(&self) -> Result<Vec<Category>, AppError>;
+    async fn find_category(&self, category_id: i64) -> Result<Option<Category>, AppError>;
+    // Match a job's category by name (any case) or slug
+    async fn resolve_category(&self, name_or_slug: &str) -> Result<Option<Category>, AppError>;
+    // Fails with `AppError::Conflict` when the name or slug is taken
+    async fn create_category(&self, category: &CreateCategory, slug: &str)
+        -> Result<i64, AppError>;
+    // Renaming also updates the display name on the category's jobs
+    async fn update_category(
+        &self,
+        category_id: i64,
+        update: &UpdateCategory,
+    ) -> Result<(), AppError>;
+    // Number of jobs (in any state) and subcategories still pointing at the category
+    async fn category_usage(&self, category_id: i64) -> Result<(i64, i64), AppError>;
+    async fn delete_category(&self, category_id: i64) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl CategoryRepo for SqlitePool {
+    async fn list_categories(&self) -> Result<Vec<Category>, AppError> {
+        Ok(sqlx::query_as::<_, Category>(&format!(
+            "{} ORDER BY c.position, c.name",
+            CATEGORY_SELECT
+        ))
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn find_category(&self, category_id: i64) -> Result<Option<Category>, AppError> {
+        Ok(
+            sqlx::query_as::<_, Category>(&format!("{} WHERE c.id = ?", CATEGORY_SELECT))
+                .bind(category_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn resolve_category(&self, name_or_slug: &str) -> Result<Option<Category>, AppError> {
+        let name_or_slug = name_or_slug.trim();
+        Ok(sqlx::query_as::<_, Category>(&format!(
+            "{} WHERE c.name = ? OR c.slug = ?",
+            CATEGORY_SELECT
+        ))
+        .bind(name_or_slug)
+        .bind(name_or_slug.to_lowercase())
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn create_category(
+        &self,
+        category: &CreateCategory,
+        slug: &str,
+    ) -> Result<i64, AppError> {
+        let name = category.name.trim();
+        let position = category.position.unwrap_or(0);
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO categories (name, slug, parent_id, description, position)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            name,
+            slug,
+            category.parent_id,
+            category.description,
+            position
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn update_category(
+        &self,
+        category_id: i64,
+        update: &UpdateCategory,
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        let name = update.name.as_deref().map(str::trim);
+
+        sqlx::query!(
+            r#"
+            UPDATE categories
+            SET name = COALESCE(?, name),
+                slug = COALESCE(?, slug),
+                description = COALESCE(?, description),
+                position = COALESCE(?, position),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            name,
+            update.slug,
+            update.description,
+            update.position,
+            category_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if let Some(name) = name {
+            sqlx::query!(
+                "UPDATE jobs SET category = ? WHERE category_id = ?",
+                name,
+                category_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn category_usage(&self, category_id: i64) -> Result<(i64, i64), AppError> {
+        let jobs = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM jobs WHERE category_id = ?",
+            category_id
+        )
+        .fetch_one(self)
+        .await?;
+        let subcategories = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM categories WHERE parent_id = ?",
+            category_id
+        )
+        .fetch_one(self)
+        .await?;
+        Ok((jobs.into(), subcategories.into()))
+    }
+
+    async fn delete_category(&self, category_id: i64) -> Result<(), AppError> {
+        sqlx::query!("DELETE FROM categories WHERE id = ?", category_id)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+}
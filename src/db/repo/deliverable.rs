@@ -0,0 +1,218 @@
+use crate::error::AppError;
+use crate::models::escrow::DisputedJobDetail;
+use async_trait::async_trait;
+use sqlx::{FromRow, SqlitePool};
+
+// Submitted work for an approved application, with the job and both parties
+#[derive(Debug, Clone, FromRow)]
+pub struct DeliverableDetail {
+    pub id: i64,
+    pub application_id: i64,
+    pub job_id: i64,
+    pub job_title: String,
+    pub job_status: String,
+    pub client_id: i64,
+    pub freelancer_id: i64,
+    pub submitted: bool,
+    pub submitted_at: String,
+    pub disputed: bool,
+    pub review_requested: bool,
+    pub cancelled: bool,
+    pub arbiter_id: Option<i64>,
+    pub freelancer_username: Option<String>,
+    pub client_username: Option<String>,
+}
+
+// Shared by every lookup so the join (and the approved-only rule) can't drift
+const DELIVERABLE_SELECT: &str = r#"
+    SELECT
+        jd.id, jd.application_id, j.id AS job_id, j.title AS job_title, j.status AS job_status,
+        j.client_id, ja.user_id AS freelancer_id,
+        jd.submitted, jd.submitted_at,
+        COALESCE(jd.disputed, 0) AS disputed,
+        COALESCE(jd.review_requested, 0) AS review_requested,
+        COALESCE(jd.cancelled, 0) AS cancelled,
+        jd.arbiter_id,
+        pf.username AS freelancer_username, pc.username AS client_username
+    FROM job_deliverables jd
+    JOIN job_applications ja ON ja.id = jd.application_id AND ja.approved = 1
+    JOIN jobs j ON j.id = ja.job_id
+    LEFT JOIN profiles pf ON pf.user_id = ja.user_id
+    LEFT JOIN profiles pc ON pc.user_id = j.client_id
+"#;
+
+#[async_trait]
+pub trait DeliverableRepo: Send + Sync {
+    async fn deliverable_for_job(&self, job_id: i64)
+        -> Result<Option<DeliverableDetail>, AppError>;
+    async fn deliverable_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<DeliverableDetail>, AppError>;
+    async fn create_deliverable(&self, application_id: i64, ipfs_hash: &str)
+        -> Result<(), AppError>;
+    // Replace the work after the client asked for changes
+    async fn resubmit_deliverable(
+        &self,
+        application_id: i64,
+        ipfs_hash: &str,
+    ) -> Result<(), AppError>;
+    // Client sends the work back for changes; returns false if there was nothing to review
+    async fn request_revision(&self, application_id: i64) -> Result<bool, AppError>;
+    async fn clear_review_request(&self, application_id: i64) -> Result<(), AppError>;
+    async fn mark_disputed(&self, deliverable_id: i64, arbiter_id: i64) -> Result<(), AppError>;
+    async fn resolve_dispute(&self, application_id: i64, resolved: bool) -> Result<(), AppError>;
+    async fn disputes_for_arbiter(&self, arbiter_id: i64)
+        -> Result<Vec<DisputedJobDetail>, AppError>;
+}
+
+#[async_trait]
+impl DeliverableRepo for SqlitePool {
+    async fn deliverable_for_job(
+        &self,
+        job_id: i64,
+    ) -> Result<Option<DeliverableDetail>, AppError> {
+        Ok(
+            sqlx::query_as::<_, DeliverableDetail>(&format!("{} WHERE j.id = ?", DELIVERABLE_SELECT))
+                .bind(job_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn deliverable_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<DeliverableDetail>, AppError> {
+        Ok(sqlx::query_as::<_, DeliverableDetail>(&format!(
+            "{} WHERE jd.application_id = ?",
+            DELIVERABLE_SELECT
+        ))
+        .bind(application_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn create_deliverable(
+        &self,
+        application_id: i64,
+        ipfs_hash: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO job_deliverables (application_id, ipfs_hash, submitted) VALUES (?, ?, 1)",
+            application_id,
+            ipfs_hash
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn resubmit_deliverable(
+        &self,
+        application_id: i64,
+        ipfs_hash: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE job_deliverables
+            SET ipfs_hash = ?, submitted = 1, submitted_at = CURRENT_TIMESTAMP,
+                review_requested = 0, review_requested_at = NULL
+            WHERE application_id = ?
+            "#,
+            ipfs_hash,
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn request_revision(&self, application_id: i64) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE job_deliverables
+            SET review_requested = 1, submitted = 0, review_requested_at = CURRENT_TIMESTAMP
+            WHERE application_id = ?
+            "#,
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn clear_review_request(&self, application_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE job_deliverables SET review_requested = 0, review_requested_at = NULL WHERE application_id = ?",
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_disputed(&self, deliverable_id: i64, arbiter_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE job_deliverables SET disputed = 1, disputed_at = CURRENT_TIMESTAMP, arbiter_id = ? WHERE id = ?",
+            arbiter_id,
+            deliverable_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn resolve_dispute(&self, application_id: i64, resolved: bool) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE job_deliverables SET resolved = ?, disputed = 0 WHERE application_id = ?",
+            resolved,
+            application_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn disputes_for_arbiter(
+        &self,
+        arbiter_id: i64,
+    ) -> Result<Vec<DisputedJobDetail>, AppError> {
+        Ok(sqlx::query_as!(
+            DisputedJobDetail,
+            r#"
+            SELECT
+                j.id AS job_id,
+                j.title AS title,
+                j.description AS description,
+                j.budget AS budget,
+                j.skills AS skills,
+                j.client_id,
+                j.posted_at,
+                j.deadline,
+                j.job_ipfs_hash,
+                jd.ipfs_hash AS work_ipfs_hash,
+                ja.user_id AS freelancer_id,
+                ja.id AS application_id,
+                jd.submitted_at,
+                j.status AS job_status,
+                jd.arbiter_id,
+                p.username AS client_username,
+                pf.username AS freelancer_username,
+                u.wallet_address AS client_wallet,
+                ua.wallet_address AS freelancer_wallet
+            FROM job_deliverables jd
+            LEFT JOIN job_applications ja ON ja.id = jd.application_id
+            LEFT JOIN jobs j ON ja.job_id = j.id
+            LEFT JOIN profiles p ON j.client_id = p.user_id
+            LEFT JOIN profiles pf ON ja.user_id = pf.user_id
+            LEFT JOIN users u ON u.id = j.client_id
+            LEFT JOIN users ua ON ua.id = ja.user_id
+            WHERE jd.disputed = 1 AND jd.arbiter_id = ?
+            "#,
+            arbiter_id
+        )
+        .fetch_all(self)
+        .await?)
+    }
+}
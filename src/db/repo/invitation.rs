@@ -0,0 +1,155 @@
+use crate::error::AppError;
+use crate::models::invitation::{InvitationResponse, InvitationStatus};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const INVITATION_SELECT: &str = r#"
+    SELECT
+        ji.id, ji.job_id, j.title AS job_title, ji.client_id, pc.username AS client_username,
+        ji.freelancer_id, pf.username AS freelancer_username,
+        ji.message, ji.status, ji.created_at, ji.responded_at
+    FROM job_invitations ji
+    JOIN jobs j ON j.id = ji.job_id
+    LEFT JOIN profiles pc ON pc.user_id = ji.client_id
+    LEFT JOIN profiles pf ON pf.user_id = ji.freelancer_id
+"#;
+
+#[async_trait]
+pub trait InvitationRepo: Send + Sync {
+    // Fails with `AppError::Conflict` when the freelancer was already invited to the job
+    async fn create_invitation(
+        &self,
+        job_id: i64,
+        client_id: i64,
+        freelancer_id: i64,
+        message: Option<&str>,
+    ) -> Result<i64, AppError>;
+    async fn find_invitation(
+        &self,
+        invitation_id: i64,
+    ) -> Result<Option<InvitationResponse>, AppError>;
+    async fn invitations_for_job(&self, job_id: i64)
+        -> Result<Vec<InvitationResponse>, AppError>;
+    async fn invitations_for_freelancer(
+        &self,
+        freelancer_id: i64,
+    ) -> Result<Vec<InvitationResponse>, AppError>;
+    // Returns false if the invitation was already answered
+    async fn respond_to_invitation(
+        &self,
+        invitation_id: i64,
+        status: InvitationStatus,
+    ) -> Result<bool, AppError>;
+    // Applying to the job counts as accepting a pending invitation
+    async fn accept_pending_invitation(&self, job_id: i64, freelancer_id: i64)
+        -> Result<(), AppError>;
+    async fn is_invited(&self, job_id: i64, freelancer_id: i64) -> Result<bool, AppError>;
+}
+
+#[async_trait]
+impl InvitationRepo for SqlitePool {
+    async fn create_invitation(
+        &self,
+        job_id: i64,
+        client_id: i64,
+        freelancer_id: i64,
+        message: Option<&str>,
+    ) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            "INSERT INTO job_invitations (job_id, client_id, freelancer_id, message) VALUES (?, ?, ?, ?)",
+            job_id,
+            client_id,
+            freelancer_id,
+            message
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn find_invitation(
+        &self,
+        invitation_id: i64,
+    ) -> Result<Option<InvitationResponse>, AppError> {
+        Ok(sqlx::query_as::<_, InvitationResponse>(&format!(
+            "{} WHERE ji.id = ?",
+            INVITATION_SELECT
+        ))
+        .bind(invitation_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn invitations_for_job(
+        &self,
+        job_id: i64,
+    ) -> Result<Vec<InvitationResponse>, AppError> {
+        Ok(sqlx::query_as::<_, InvitationResponse>(&format!(
+            "{} WHERE ji.job_id = ? ORDER BY ji.id DESC",
+            INVITATION_SELECT
+        ))
+        .bind(job_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn invitations_for_freelancer(
+        &self,
+        freelancer_id: i64,
+    ) -> Result<Vec<InvitationResponse>, AppError> {
+        Ok(sqlx::query_as::<_, InvitationResponse>(&format!(
+            "{} WHERE ji.freelancer_id = ? ORDER BY ji.id DESC",
+            INVITATION_SELECT
+        ))
+        .bind(freelancer_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn respond_to_invitation(
+        &self,
+        invitation_id: i64,
+        status: InvitationStatus,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE job_invitations SET status = ?, responded_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'pending'
+            "#,
+        )
+        .bind(status)
+        .bind(invitation_id)
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn accept_pending_invitation(
+        &self,
+        job_id: i64,
+        freelancer_id: i64,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE job_invitations SET status = 'accepted', responded_at = CURRENT_TIMESTAMP
+            WHERE job_id = ? AND freelancer_id = ? AND status = 'pending'
+            "#,
+            job_id,
+            freelancer_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn is_invited(&self, job_id: i64, freelancer_id: i64) -> Result<bool, AppError> {
+        let invited = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM job_invitations WHERE job_id = ? AND freelancer_id = ?) AS "invited!: bool""#,
+            job_id,
+            freelancer_id
+        )
+        .fetch_one(self)
+        .await?;
+        Ok(invited)
+    }
+}
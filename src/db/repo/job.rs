@@ -0,0 +1,928 @@
+use crate::db::pagination::{paginate, Cursor, Keyset};
+use crate::db::repo::{SkillRepo, UserRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::client::JobResponse as ClientJobResponse;
+use crate::models::freelancer::{JobInteractionStatus, JobResponse as FreelancerJobResponse};
+use crate::models::job::{
+    JobFilterQuery, JobRequest, JobResponse, JobRevision, JobSearchHit, JobType, JobVisibility,
+    JobsResponse, MyJobsResponse, UpdateJob,
+};
+use crate::models::location::{overlap_sql, within_radius_sql};
+use crate::models::search::{
+    closest_term, max_typos, FacetCount, JobFacet, SearchCorrection, SearchQuery,
+};
+use crate::models::skill::{parse_skill_list, SkillMatch};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+// bm25 weights per jobs_fts column: title, description, skills, category. bm25()
+// is lower for better matches, so relevance is its negation
+const RELEVANCE: &str = "-bm25(jobs_fts, 10.0, 1.0, 5.0, 2.0)";
+
+fn search_columns() -> String {
+    format!(
+        r#",
+    {} AS relevance,
+    highlight(jobs_fts, 0, '<mark>', '</mark>') AS title_highlight,
+    snippet(jobs_fts, 1, '<mark>', '</mark>', '…', 24) AS snippet"#,
+        RELEVANCE
+    )
+}
+const NO_SEARCH_COLUMNS: &str = ", NULL AS relevance, NULL AS title_highlight, NULL AS snippet";
+
+// How many values the open-ended facets (location, country, skill) list at most
+const FACET_LIMIT: i64 = 20;
+
+// The WHERE conditions of a job search, each tagged with the facet it filters on
+// so a facet's counts can leave its own filter out
+struct JobConditions {
+    from: &'static str,
+    clauses: Vec<(Option<JobFacet>, String, Vec<String>)>,
+}
+
+impl Default for JobConditions {
+    fn default() -> Self {
+        Self {
+            from: "jobs j",
+            clauses: Vec::new(),
+        }
+    }
+}
+
+impl JobConditions {
+    // Adds `clause` with its single parameter; skipped when the filter is unset
+    fn push(&mut self, facet: Option<JobFacet>, clause: &str, value: Option<String>) {
+        if let Some(value) = value {
+            self.push_all(facet, clause, vec![value]);
+        }
+    }
+
+    fn push_all(&mut self, facet: Option<JobFacet>, clause: &str, params: Vec<String>) {
+        self.clauses.push((facet, clause.to_string(), params));
+    }
+
+    fn where_clause(&self, except: Option<JobFacet>) -> (String, Vec<String>) {
+        let mut sql = String::from("1=1");
+        let mut params = Vec::new();
+        for (facet, clause, values) in &self.clauses {
+            if except.is_some() && *facet == except {
+                continue;
+            }
+            sql.push_str(" AND ");
+            sql.push_str(clause);
+            params.extend(values.iter().cloned());
+        }
+        (sql, params)
+    }
+}
+
+fn facet_sql(facet: JobFacet, from: &str, filters: &str) -> String {
+    match facet {
+        JobFacet::Category => format!(
+            "SELECT c.slug AS value, c.name AS label, COUNT(*) AS count
+             FROM {} JOIN categories c ON c.id = j.category_id WHERE {}
+             GROUP BY c.id ORDER BY count DESC, c.name",
+            from, filters
+        ),
+        JobFacet::JobType => format!(
+            "SELECT j.job_type AS value, j.job_type AS label, COUNT(*) AS count
+             FROM {} WHERE {} GROUP BY j.job_type ORDER BY count DESC, j.job_type",
+            from, filters
+        ),
+        JobFacet::Location => format!(
+            "SELECT j.location AS value, j.location AS label, COUNT(*) AS count
+             FROM {} WHERE {} GROUP BY j.location ORDER BY count DESC, j.location LIMIT {}",
+            from, filters, FACET_LIMIT
+        ),
+        JobFacet::Country => format!(
+            "SELECT j.country AS value, j.country AS label, COUNT(*) AS count
+             FROM {} WHERE {} AND j.country IS NOT NULL
+             GROUP BY j.country ORDER BY count DESC, j.country LIMIT {}",
+            from, filters, FACET_LIMIT
+        ),
+        // Buckets line up with min_budget/max_budget filters, listed low to high
+        JobFacet::Budget => format!(
+            "SELECT bucket AS value, bucket AS label, COUNT(*) AS count FROM (
+                 SELECT j.budget, CASE
+                     WHEN j.budget < 100 THEN '0-99'
+                     WHEN j.budget < 500 THEN '100-499'
+                     WHEN j.budget < 1000 THEN '500-999'
+                     WHEN j.budget < 5000 THEN '1000-4999'
+                     ELSE '5000+'
+                 END AS bucket
+                 FROM {} WHERE {}
+             )
+             GROUP BY bucket ORDER BY MIN(budget)",
+            from, filters
+        ),
+        JobFacet::Skill => format!(
+            "SELECT s.name AS value, s.name AS label, COUNT(*) AS count
+             FROM {} JOIN job_skills fs ON fs.job_id = j.id JOIN skills s ON s.id = fs.skill_id
+             WHERE {} GROUP BY s.id ORDER BY count DESC, s.name LIMIT {}",
+            from, filters, FACET_LIMIT
+        ),
+    }
+}
+
+#[async_trait]
+pub trait JobRepo: Send + Sync {
+    // `category_id` is the category `job.category` resolved to
+    async fn create_job(
+        &self,
+        client_id: i64,
+        job: &JobRequest,
+        category_id: i64,
+    ) -> Result<i64, AppError>;
+    async fn job_status(&self, job_id: i64) -> Result<Option<String>, AppError>;
+    async fn set_job_status(&self, job_id: i64, status: &str) -> Result<(), AppError>;
+    async fn job_owner(&self, job_id: i64) -> Result<Option<i64>, AppError>;
+    async fn job_visibility(&self, job_id: i64) -> Result<Option<JobVisibility>, AppError>;
+    async fn job_detail(&self, job_id: i64) -> Result<Option<JobResponse>, AppError>;
+    // Apply the edit and record `changes` (a JSON diff) as a revision, atomically
+    async fn update_job(
+        &self,
+        job_id: i64,
+        editor_id: i64,
+        update: &UpdateJob,
+        category_id: Option<i64>,
+        changes: &serde_json::Value,
+    ) -> Result<(), AppError>;
+    async fn job_revisions(&self, job_id: i64) -> Result<Vec<JobRevision>, AppError>;
+    // Copy the listing into a new open job with a fresh deadline
+    async fn repost_job(&self, job_id: i64, deadline: &str) -> Result<i64, AppError>;
+    // Keyword searches rank by relevance unless another sort is asked for; a
+    // keyword FTS5 can't make sense of fails with `AppError::BadRequest`. Without a
+    // viewer only open public jobs are listed
+    async fn search_jobs(
+        &self,
+        query: &JobFilterQuery,
+        viewer: Option<&AuthUser>,
+    ) -> Result<JobsResponse, AppError>;
+    // Words with no hits in the search index, paired with the nearest indexed term
+    async fn search_corrections(&self, words: &[String])
+        -> Result<Vec<SearchCorrection>, AppError>;
+    // A job with the deliverable of its approved application, if any (client view)
+    async fn job_with_deliverable(&self, job_id: i64)
+        -> Result<Option<ClientJobResponse>, AppError>;
+    // The job behind an application, with the client's wallet (freelancer view)
+    async fn job_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<FreelancerJobResponse>, AppError>;
+    // Jobs a user applied to, plus the client's own jobs that have an approved hire
+    async fn jobs_for_user(&self, user_id: i64) -> Result<Vec<MyJobsResponse>, AppError>;
+    async fn interaction_status(
+        &self,
+        user_id: i64,
+        job_id: i64,
+    ) -> Result<JobInteractionStatus, AppError>;
+    async fn mark_applied(&self, user_id: i64, job_id: i64) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl JobRepo for SqlitePool {
+    async fn create_job(
+        &self,
+        client_id: i64,
+        job: &JobRequest,
+        category_id: i64,
+    ) -> Result<i64, AppError> {
+        let posted_at = Utc::now().to_rfc3339();
+        let weekly_hour_cap = job
+            .weekly_hour_cap
+            .filter(|_| job.job_type == JobType::Hourly);
+        let place = &job.place;
+        let window = place.utc_window();
+        let (work_start_utc, work_minutes) = window.map(|w| (w.start, w.minutes)).unzip();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO jobs (
+                title, description, skills, budget, location, remote, country, city, latitude,
+                longitude, timezone, work_start, work_end, work_start_utc, work_minutes,
+                job_type, weekly_hour_cap, job_ipfs_hash, posted_at, deadline, client_id,
+                category, category_id, status, visibility
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            job.title,
+            job.description,
+            job.skills,
+            job.budget,
+            job.location,
+            job.remote,
+            place.country,
+            place.city,
+            place.latitude,
+            place.longitude,
+            place.timezone,
+            place.work_start,
+            place.work_end,
+            work_start_utc,
+            work_minutes,
+            job.job_type,
+            weekly_hour_cap,
+            job.job_ipfs_hash,
+            posted_at,
+            job.deadline,
+            client_id,
+            job.category,
+            category_id,
+            job.status,
+            job.visibility
+        )
+        .execute(self)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn job_status(&self, job_id: i64) -> Result<Option<String>, AppError> {
+        Ok(
+            sqlx::query_scalar!("SELECT status FROM jobs WHERE id = ?", job_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn set_job_status(&self, job_id: i64, status: &str) -> Result<(), AppError> {
+        // The first completion opens the review window
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET status = ?,
+                completed_at = CASE WHEN ? = 'completed'
+                    THEN COALESCE(completed_at, CURRENT_TIMESTAMP) ELSE completed_at END
+            WHERE id = ?
+            "#,
+            status,
+            status,
+            job_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn job_owner(&self, job_id: i64) -> Result<Option<i64>, AppError> {
+        Ok(
+            sqlx::query_scalar!("SELECT client_id FROM jobs WHERE id = ?", job_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn job_visibility(&self, job_id: i64) -> Result<Option<JobVisibility>, AppError> {
+        Ok(
+            sqlx::query_scalar::<_, JobVisibility>("SELECT visibility FROM jobs WHERE id = ?")
+                .bind(job_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn job_detail(&self, job_id: i64) -> Result<Option<JobResponse>, AppError> {
+        Ok(sqlx::query_as::<_, JobResponse>(
+            r#"
+            SELECT
+                id, title, description, skills, budget, location, remote, country, city,
+                latitude, longitude, timezone, work_start, work_end, job_type, weekly_hour_cap,
+                job_ipfs_hash, posted_at, deadline, client_id, category, status, visibility
+            FROM jobs WHERE id = ?
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn update_job(
+        &self,
+        job_id: i64,
+        editor_id: i64,
+        update: &UpdateJob,
+        category_id: Option<i64>,
+        changes: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+
+        // The jobs_update trigger keeps jobs_fts in step with the searchable columns
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET title = COALESCE(?, title),
+                description = COALESCE(?, description),
+                skills = COALESCE(?, skills),
+                budget = COALESCE(?, budget),
+                location = COALESCE(?, location),
+                remote = COALESCE(?, remote),
+                job_type = COALESCE(?, job_type),
+                weekly_hour_cap = CASE
+                    WHEN COALESCE(?, job_type) = 'hourly' THEN COALESCE(?, weekly_hour_cap)
+                END,
+                job_ipfs_hash = COALESCE(?, job_ipfs_hash),
+                deadline = COALESCE(?, deadline),
+                category = COALESCE(?, category),
+                category_id = COALESCE(?, category_id),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            update.title,
+            update.description,
+            update.skills,
+            update.budget,
+            update.location,
+            update.remote,
+            update.job_type,
+            update.job_type,
+            update.weekly_hour_cap,
+            update.job_ipfs_hash,
+            update.deadline,
+            update.category,
+            category_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(place) = &update.place {
+            let window = place.utc_window();
+            let (work_start_utc, work_minutes) = window.map(|w| (w.start, w.minutes)).unzip();
+            sqlx::query!(
+                r#"
+                UPDATE jobs
+                SET country = ?, city = ?, latitude = ?, longitude = ?, timezone = ?,
+                    work_start = ?, work_end = ?, work_start_utc = ?, work_minutes = ?
+                WHERE id = ?
+                "#,
+                place.country,
+                place.city,
+                place.latitude,
+                place.longitude,
+                place.timezone,
+                place.work_start,
+                place.work_end,
+                work_start_utc,
+                work_minutes,
+                job_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let changes = changes.to_string();
+        sqlx::query!(
+            "INSERT INTO job_revisions (job_id, editor_id, changes) VALUES (?, ?, ?)",
+            job_id,
+            editor_id,
+            changes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn job_revisions(&self, job_id: i64) -> Result<Vec<JobRevision>, AppError> {
+        Ok(sqlx::query_as::<_, JobRevision>(
+            "SELECT id, job_id, editor_id, changes, edited_at FROM job_revisions WHERE job_id = ? ORDER BY id",
+        )
+        .bind(job_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn repost_job(&self, job_id: i64, deadline: &str) -> Result<i64, AppError> {
+        let posted_at = Utc::now().to_rfc3339();
+        let mut tx = self.begin().await?;
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO jobs (
+                title, description, skills, budget, location, remote, country, city, latitude,
+                longitude, timezone, work_start, work_end, work_start_utc, work_minutes,
+                job_type, weekly_hour_cap, job_ipfs_hash, posted_at, deadline, client_id,
+                category, category_id, status, visibility, reposted_from
+            )
+            SELECT
+                title, description, skills, budget, location, remote, country, city, latitude,
+                longitude, timezone, work_start, work_end, work_start_utc, work_minutes,
+                job_type, weekly_hour_cap, job_ipfs_hash, ?, ?, client_id, category,
+                category_id, 'open', visibility, id
+            FROM jobs WHERE id = ?
+            "#,
+            posted_at,
+            deadline,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let new_job_id = result.last_insert_rowid();
+
+        sqlx::query!(
+            "INSERT INTO job_skills (job_id, skill_id) SELECT ?, skill_id FROM job_skills WHERE job_id = ?",
+            new_job_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(new_job_id)
+    }
+
+    async fn search_jobs(
+        &self,
+        query: &JobFilterQuery,
+        viewer: Option<&AuthUser>,
+    ) -> Result<JobsResponse, AppError> {
+        let facets = match &query.facets {
+            Some(raw) => JobFacet::parse_list(raw).map_err(AppError::BadRequest)?,
+            None => Vec::new(),
+        };
+        let mut conditions = JobConditions::default();
+        let mut corrections = Vec::new();
+        let keyword = query.keyword.as_deref().filter(|_| query.id.is_none());
+
+        if let Some(id) = query.id {
+            // Fetch by ID directly
+            conditions.push(None, "j.id = ?", Some(id.to_string()));
+        } else if let Some(keyword) = keyword {
+            // Keyword-based search with FTS5; the parsed query is rebuilt from quoted
+            // terms, so user input can't produce FTS5 syntax errors
+            let search = SearchQuery::parse(keyword).map_err(AppError::BadRequest)?;
+            corrections = self.search_corrections(&search.words()).await?;
+            let widened = corrections
+                .iter()
+                .map(|c| (c.term.clone(), c.suggestion.clone()))
+                .collect();
+            conditions.from = "jobs_fts JOIN jobs j ON j.id = jobs_fts.rowid";
+            conditions.push(None, "jobs_fts MATCH ?", Some(search.to_fts(&widened)));
+        }
+
+        let budget = Some(JobFacet::Budget);
+        conditions.push(
+            budget,
+            "j.budget >= ?",
+            query.min_budget.map(|v| v.to_string()),
+        );
+        conditions.push(
+            budget,
+            "j.budget <= ?",
+            query.max_budget.map(|v| v.to_string()),
+        );
+        if let Some(location) = &query.location {
+            conditions.push_all(
+                Some(JobFacet::Location),
+                "(lower(j.location) = lower(?) OR lower(j.city) = lower(?))",
+                vec![location.clone(), location.clone()],
+            );
+        }
+        conditions.push(
+            None,
+            "j.remote = ?",
+            query.remote.map(|remote| i64::from(remote).to_string()),
+        );
+        conditions.push(
+            Some(JobFacet::Country),
+            "j.country = ?",
+            query.country.as_ref().map(|c| c.to_ascii_uppercase()),
+        );
+        match (query.lat, query.lng, query.radius_km) {
+            (Some(lat), Some(lng), Some(radius_km)) => conditions.push_all(
+                None,
+                &within_radius_sql("j.latitude", "j.longitude", (lat, lng), radius_km),
+                Vec::new(),
+            ),
+            (None, None, None) => {}
+            _ => {
+                return Err(AppError::BadRequest(
+                    "lat, lng and radius_km go together".into(),
+                ))
+            }
+        }
+        if let Some(hours) = query.min_overlap_hours {
+            let viewer = viewer.ok_or(AppError::BadRequest(
+                "Sign in to filter by working hours overlap".into(),
+            ))?;
+            let window = self
+                .working_hours(viewer.id)
+                .await?
+                .ok_or(AppError::BadRequest(
+                    "Set a timezone and working hours on your profile to filter by overlap".into(),
+                ))?;
+            let overlap = overlap_sql("j.work_start_utc", "j.work_minutes", window);
+            conditions.push_all(
+                None,
+                &format!(
+                    "j.work_start_utc IS NOT NULL AND {} >= {}",
+                    overlap,
+                    (hours * 60.0).round()
+                ),
+                Vec::new(),
+            );
+        }
+        conditions.push(
+            Some(JobFacet::JobType),
+            "j.job_type = ?",
+            query.job_type.clone(),
+        );
+        conditions.push(
+            None,
+            "j.client_id = ?",
+            query.client_id.map(|v| v.to_string()),
+        );
+        // A category matches by name or slug and takes in its subcategories
+        conditions.push(
+            Some(JobFacet::Category),
+            "j.category_id IN (SELECT c.id FROM categories c LEFT JOIN categories p ON p.id = c.parent_id WHERE lower(?) IN (lower(c.name), c.slug, lower(p.name), p.slug))",
+            query.category.clone(),
+        );
+        conditions.push(None, "j.deadline >= ?", query.deadline_start.clone());
+        conditions.push(None, "j.deadline <= ?", query.deadline_end.clone());
+        conditions.push(None, "j.posted_at >= ?", query.posted_at_start.clone());
+        conditions.push(None, "j.posted_at <= ?", query.posted_at_end.clone());
+        // Anonymous listings are always of open jobs
+        if viewer.is_some() {
+            conditions.push(None, "j.status = ?", query.status.clone());
+        }
+        // Invite-only jobs show to their client and invited freelancers only
+        match viewer {
+            None => conditions.push(
+                None,
+                "j.visibility = 'public' AND j.status = ?",
+                Some("open".to_string()),
+            ),
+            Some(viewer) if viewer.role == "client" => conditions.push(
+                None,
+                "(j.visibility = 'public' OR j.client_id = ?)",
+                Some(viewer.id.to_string()),
+            ),
+            Some(viewer) => conditions.push(
+                None,
+                "(j.visibility = 'public' OR EXISTS (SELECT 1 FROM job_invitations ji WHERE ji.job_id = j.id AND ji.freelancer_id = ?))",
+                Some(viewer.id.to_string()),
+            ),
+        }
+
+        // Exact skill matching through the taxonomy; a name that is no known skill
+        // matches nothing
+        if let Some(skills) = &query.skills {
+            let ids = self.find_skill_ids(&parse_skill_list(skills)).await?;
+            let mode = query.skills_match.unwrap_or_default();
+            let mut known: Vec<i64> = ids.iter().flatten().copied().collect();
+            known.sort_unstable();
+            known.dedup();
+            let placeholders = vec!["?"; known.len()].join(", ");
+            let clause = if known.is_empty() || (mode == SkillMatch::All && ids.contains(&None)) {
+                "0".to_string()
+            } else if mode == SkillMatch::Any {
+                format!(
+                    "EXISTS (SELECT 1 FROM job_skills js WHERE js.job_id = j.id AND js.skill_id IN ({}))",
+                    placeholders
+                )
+            } else {
+                format!(
+                    "(SELECT COUNT(*) FROM job_skills js WHERE js.job_id = j.id AND js.skill_id IN ({})) = {}",
+                    placeholders,
+                    known.len()
+                )
+            };
+            conditions.push_all(
+                Some(JobFacet::Skill),
+                &clause,
+                known.iter().map(i64::to_string).collect(),
+            );
+        }
+
+        // Dynamic sorting, e.g. "budget:asc"; unknown values fall back to newest first
+        let sort_by = match query.sort_by.as_deref() {
+            None if keyword.is_some() => Some("relevance"),
+            sort_by => sort_by,
+        };
+        let (name, column, descending) = match sort_by.map(|s| s.split_once(':').unwrap_or((s, "")))
+        {
+            Some(("relevance", _)) if keyword.is_some() => ("relevance", RELEVANCE, true),
+            Some((column, direction)) if !direction.is_empty() => {
+                let descending = !direction.eq_ignore_ascii_case("asc");
+                match (column, descending) {
+                    ("budget", true) => ("budget:desc", "j.budget", true),
+                    ("budget", false) => ("budget:asc", "j.budget", false),
+                    ("deadline", true) => ("deadline:desc", "j.deadline", true),
+                    ("deadline", false) => ("deadline:asc", "j.deadline", false),
+                    (_, true) => ("posted_at:desc", "j.posted_at", true),
+                    (_, false) => ("posted_at:asc", "j.posted_at", false),
+                }
+            }
+            _ => ("posted_at:desc", "j.posted_at", true),
+        };
+        let keyset = Keyset {
+            name,
+            column,
+            descending,
+            id_column: "j.id",
+            id_descending: descending,
+            numeric: matches!(name, "relevance" | "budget:asc" | "budget:desc"),
+        };
+
+        // A cursor replaces the offset: the page starts after the row it names
+        let (mut filters, mut params) = conditions.where_clause(None);
+        let offset = match &query.cursor {
+            Some(raw) => {
+                let (after, values) = keyset.after(&Cursor::decode(raw, name)?);
+                filters.push_str(" AND ");
+                filters.push_str(&after);
+                params.extend(values);
+                0
+            }
+            None => query.offset.unwrap_or(0),
+        };
+        let limit = query.limit.unwrap_or(20);
+        let sql = format!(
+            r#"
+            SELECT
+                j.id, j.title, j.description, j.skills, j.budget, j.location, j.remote,
+                j.country, j.city, j.latitude, j.longitude, j.timezone, j.work_start,
+                j.work_end, j.job_type, j.weekly_hour_cap, j.job_ipfs_hash, j.posted_at,
+                j.deadline, j.client_id, j.category, j.status, j.visibility{}
+            FROM {} WHERE {}
+            ORDER BY {} LIMIT ? OFFSET ?
+            "#,
+            if keyword.is_some() {
+                search_columns()
+            } else {
+                NO_SEARCH_COLUMNS.to_string()
+            },
+            conditions.from,
+            filters,
+            keyset.order_by()
+        );
+        // One extra row tells whether there is a next page
+        params.push((limit + 1).to_string());
+        params.push(offset.to_string());
+        let mut rows = sqlx::query_as::<sqlx::Sqlite, JobSearchHit>(&sql);
+        for param in params {
+            rows = rows.bind(param);
+        }
+        let (jobs, next_cursor) = paginate(rows.fetch_all(self).await?, limit, name, |hit| {
+            let key = match name {
+                "relevance" => hit.relevance.unwrap_or_default().to_string(),
+                "budget:asc" | "budget:desc" => hit.job.budget.to_string(),
+                "deadline:asc" | "deadline:desc" => hit.job.deadline.clone(),
+                _ => hit.job.posted_at.clone(),
+            };
+            (key, hit.job.id)
+        });
+
+        let total = match query.include_total {
+            Some(true) => {
+                let (filters, params) = conditions.where_clause(None);
+                let sql = format!("SELECT COUNT(*) FROM {} WHERE {}", conditions.from, filters);
+                let mut count = sqlx::query_scalar::<sqlx::Sqlite, i64>(&sql);
+                for param in params {
+                    count = count.bind(param);
+                }
+                Some(count.fetch_one(self).await?)
+            }
+            _ => None,
+        };
+
+        let mut facet_counts = BTreeMap::new();
+        for facet in facets {
+            let (filters, params) = conditions.where_clause(Some(facet));
+            let sql = facet_sql(facet, conditions.from, &filters);
+            let mut counts = sqlx::query_as::<sqlx::Sqlite, FacetCount>(&sql);
+            for param in params {
+                counts = counts.bind(param);
+            }
+            facet_counts.insert(facet.name().to_string(), counts.fetch_all(self).await?);
+        }
+
+        Ok(JobsResponse {
+            jobs,
+            next_cursor,
+            corrections,
+            total,
+            facets: facet_counts,
+        })
+    }
+
+    async fn search_corrections(
+        &self,
+        words: &[String],
+    ) -> Result<Vec<SearchCorrection>, AppError> {
+        let mut corrections = Vec::new();
+        for word in words.iter().filter(|word| max_typos(word) > 0) {
+            let found: bool =
+                sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM jobs_fts WHERE jobs_fts MATCH ?)")
+                    .bind(format!("\"{}\"", word))
+                    .fetch_one(self)
+                    .await?;
+            if found {
+                continue;
+            }
+            // Candidates share the first letter and are close in length, allowing for
+            // the letters stemming strips
+            let length = word.chars().count() as i64;
+            let budget = max_typos(word) as i64;
+            let vocabulary = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                SELECT term, doc FROM jobs_fts_vocab
+                WHERE substr(term, 1, 1) = substr(?1, 1, 1)
+                  AND length(term) BETWEEN ?2 AND ?3
+                "#,
+            )
+            .bind(word)
+            .bind(length - budget - 3)
+            .bind(length + budget)
+            .fetch_all(self)
+            .await?;
+            if let Some(suggestion) = closest_term(word, &vocabulary) {
+                corrections.push(SearchCorrection {
+                    term: word.clone(),
+                    suggestion,
+                });
+            }
+        }
+        Ok(corrections)
+    }
+
+    async fn job_with_deliverable(
+        &self,
+        job_id: i64,
+    ) -> Result<Option<ClientJobResponse>, AppError> {
+        // Only the approved application has a deliverable; joining every applicant
+        // would return one row per application
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                j.id, j.title, j.description, j.skills, j.budget, j.location, j.job_type,
+                j.job_ipfs_hash, j.posted_at, j.deadline, j.client_id, j.category, j.status,
+                d.submitted, d.submitted_at, d.disputed, d.disputed_at,
+                d.review_requested, d.review_requested_at, d.cancelled, d.cancelled_at
+            FROM jobs j
+            LEFT JOIN job_applications ja ON ja.job_id = j.id AND ja.approved = 1
+            LEFT JOIN job_deliverables d ON d.application_id = ja.id
+            WHERE j.id = ?
+            "#,
+            job_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| ClientJobResponse {
+            id: row.id,
+            title: row.title,
+            description: row.description,
+            skills: row.skills,
+            budget: row.budget,
+            location: row.location,
+            job_type: row.job_type,
+            job_ipfs_hash: row.job_ipfs_hash,
+            posted_at: row.posted_at,
+            deadline: row.deadline,
+            client_id: row.client_id,
+            category: row.category,
+            status: row.status,
+            submitted: row.submitted,
+            submitted_at: row.submitted_at,
+            disputed: row.disputed,
+            disputed_at: row.disputed_at,
+            review_requested: row.review_requested,
+            review_requested_at: row.review_requested_at,
+            cancelled: row.cancelled,
+            cancelled_at: row.cancelled_at,
+        }))
+    }
+
+    async fn job_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<FreelancerJobResponse>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                j.id, j.title, j.description, j.skills, j.budget, j.location, j.job_type,
+                j.job_ipfs_hash, j.posted_at, j.deadline, j.client_id, j.category, j.status,
+                u.wallet_address,
+                d.submitted, d.submitted_at, d.disputed, d.disputed_at,
+                d.timeout_claimed, d.timeout_claimed_at
+            FROM job_applications ja
+            JOIN jobs j ON j.id = ja.job_id
+            LEFT JOIN users u ON u.id = j.client_id
+            LEFT JOIN job_deliverables d ON d.application_id = ja.id
+            WHERE ja.id = ?
+            "#,
+            application_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| FreelancerJobResponse {
+            id: row.id,
+            title: row.title,
+            description: row.description,
+            skills: row.skills,
+            budget: row.budget,
+            location: row.location,
+            job_type: row.job_type,
+            job_ipfs_hash: row.job_ipfs_hash,
+            posted_at: row.posted_at,
+            deadline: row.deadline,
+            client_id: row.client_id,
+            category: row.category,
+            status: row.status,
+            wallet_address: row.wallet_address,
+            submitted: row.submitted,
+            submitted_at: row.submitted_at,
+            disputed: row.disputed,
+            disputed_at: row.disputed_at,
+            timeout_claimed: row.timeout_claimed,
+            timeout_claimed_at: row.timeout_claimed_at,
+        }))
+    }
+
+    async fn jobs_for_user(&self, user_id: i64) -> Result<Vec<MyJobsResponse>, AppError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                j.id AS job_id, j.title, j.status, j.description, j.skills, j.budget,
+                j.location, j.posted_at, j.deadline, j.client_id,
+                ja.applied_at, ja.approved, ja.id AS application_id,
+                CASE WHEN s.job_id IS NOT NULL THEN 1 ELSE 0 END AS is_saved
+            FROM jobs j
+            JOIN job_applications ja ON ja.job_id = j.id
+            LEFT JOIN saved_jobs s ON s.job_id = j.id AND s.user_id = ja.user_id
+            WHERE ja.user_id = ? OR (j.client_id = ? AND ja.approved = 1)
+            ORDER BY ja.applied_at DESC
+            "#,
+            user_id,
+            user_id
+        )
+        .fetch_all(self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MyJobsResponse {
+                job_id: row.job_id,
+                status: row.status,
+                title: row.title,
+                description: row.description,
+                skills: row.skills,
+                budget: row.budget as u64,
+                location: row.location,
+                posted_at: row.posted_at,
+                deadline: row.deadline,
+                client_id: row.client_id,
+                applied_at: row.applied_at,
+                approved: row.approved,
+                is_saved: row.is_saved,
+                application_id: row.application_id,
+            })
+            .collect())
+    }
+
+    async fn interaction_status(
+        &self,
+        user_id: i64,
+        job_id: i64,
+    ) -> Result<JobInteractionStatus, AppError> {
+        let status = sqlx::query_as!(
+            JobInteractionStatus,
+            r#"
+            SELECT
+                COALESCE(applied, false) AS applied,
+                COALESCE(saved, false) AS saved
+            FROM job_user_interactions
+            WHERE user_id = ? AND job_id = ?
+            "#,
+            user_id,
+            job_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(status.unwrap_or(JobInteractionStatus {
+            applied: false,
+            saved: false,
+        }))
+    }
+
+    async fn mark_applied(&self, user_id: i64, job_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO job_user_interactions (user_id, job_id, applied)
+            VALUES (?, ?, TRUE)
+            ON CONFLICT(user_id, job_id) DO UPDATE SET applied = TRUE
+            "#,
+            user_id,
+            job_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+// Data access for the marketplace tables. Each repo is a trait so handlers only
+// depend on the operations they use; the SQLite implementations live on
+// `SqlitePool` itself, so handlers keep extracting `State<SqlitePool>` and call
+// e.g. `pool.find_application(id)` with the trait in scope.
+pub mod application;
+pub mod category;
+pub mod deliverable;
+pub mod invitation;
+pub mod job;
+pub mod notification;
+pub mod profile;
+pub mod recommendation;
+pub mod review;
+pub mod saved_search;
+pub mod skill;
+pub mod timesheet;
+pub mod token;
+pub mod user;
+
+pub use application::{ApplicationDetail, ApplicationRepo, NewApplication};
+pub use category::CategoryRepo;
+pub use deliverable::{DeliverableDetail, DeliverableRepo};
+pub use invitation::InvitationRepo;
+pub use job::JobRepo;
+pub use notification::{NewNotification, NotificationRepo};
+pub use profile::ProfileRepo;
+pub use recommendation::RecommendationRepo;
+pub use review::ReviewRepo;
+pub use saved_search::SavedSearchRepo;
+pub use skill::SkillRepo;
+pub use timesheet::{HourlyContract, TimesheetRepo};
+pub use token::{NonceRecord, TokenRepo};
+pub use user::{Account, LoginRecord, TotpState, UserRepo, UserSummary};
@@ -0,0 +1,177 @@
+use crate::db::pagination::{paginate, Cursor, Page, PageQuery};
+use crate::error::AppError;
+use crate::models::escrow::EscrowResponse;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: i64, // recipient
+    pub message: String,
+    pub kind: String, // stored in the `type` column, drives the frontend redirect
+    pub job_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub escrow_pda: Option<String>,
+}
+
+impl NewNotification {
+    // The usual shape: something `actor_id` did on a job, told to `user_id`
+    pub fn for_job(
+        user_id: i64,
+        kind: &str,
+        message: impl Into<String>,
+        job_id: i64,
+        actor_id: i64,
+    ) -> Self {
+        Self {
+            user_id,
+            message: message.into(),
+            kind: kind.to_string(),
+            job_id: Some(job_id),
+            actor_id: Some(actor_id),
+            escrow_pda: None,
+        }
+    }
+}
+
+// A stored notification joined with what the frontend needs to render and link it
+#[derive(Debug, Clone)]
+pub struct NotificationRecord {
+    pub id: i64,
+    pub message: String,
+    pub read: bool,
+    pub created_at: String,
+    pub kind: Option<String>,
+    pub job_id: Option<i64>,
+    pub job_title: Option<String>,
+    pub username: Option<String>, // the actor
+    pub escrow_pda: Option<String>,
+    pub application_id: Option<i64>, // the job's approved application
+}
+
+const NOTIFICATIONS_SORT: &str = "created_at:desc";
+
+#[async_trait]
+pub trait NotificationRepo: Send + Sync {
+    async fn create_notification(&self, notification: &NewNotification) -> Result<i64, AppError>;
+    // Newest first, a page at a time
+    async fn notifications_for_user(
+        &self,
+        user_id: i64,
+        page: &PageQuery,
+    ) -> Result<Page<NotificationRecord>, AppError>;
+    // Returns false when the notification doesn't exist or belongs to someone else
+    async fn mark_notification_read(&self, id: i64, user_id: i64) -> Result<bool, AppError>;
+    async fn escrow_by_pda(&self, escrow_pda: &str) -> Result<Option<EscrowResponse>, AppError>;
+}
+
+#[async_trait]
+impl NotificationRepo for SqlitePool {
+    async fn create_notification(&self, notification: &NewNotification) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO notifications (user_id, message, read, type, job_id, actor_id, escrow_pda)
+            VALUES (?, ?, 0, ?, ?, ?, ?)
+            "#,
+            notification.user_id,
+            notification.message,
+            notification.kind,
+            notification.job_id,
+            notification.actor_id,
+            notification.escrow_pda
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn notifications_for_user(
+        &self,
+        user_id: i64,
+        page: &PageQuery,
+    ) -> Result<Page<NotificationRecord>, AppError> {
+        let after = page
+            .cursor
+            .as_deref()
+            .map(|raw| Cursor::decode(raw, NOTIFICATIONS_SORT))
+            .transpose()?;
+        let (after_key, after_id) = after.map(|c| (c.key, c.id)).unzip();
+        let limit = page.limit() + 1;
+        // Joining only the approved application keeps one row per notification
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                n.id, n.message, n.read, n.created_at, n.job_id, n.type, n.escrow_pda,
+                j.title AS job_title,
+                pf.username AS username,
+                ja.id AS application_id
+            FROM notifications n
+            LEFT JOIN jobs j ON n.job_id = j.id
+            LEFT JOIN profiles pf ON n.actor_id = pf.user_id
+            LEFT JOIN job_applications ja ON ja.job_id = j.id AND ja.approved = 1
+            WHERE n.user_id = ?
+                AND (? IS NULL OR n.created_at < ? OR (n.created_at = ? AND n.id < ?))
+            ORDER BY n.created_at DESC, n.id DESC
+            LIMIT ?
+            "#,
+            user_id,
+            after_key,
+            after_key,
+            after_key,
+            after_id,
+            limit
+        )
+        .fetch_all(self)
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|row| NotificationRecord {
+                id: row.id,
+                message: row.message,
+                read: row.read,
+                created_at: row.created_at,
+                kind: row.r#type,
+                job_id: row.job_id,
+                job_title: row.job_title,
+                username: row.username,
+                escrow_pda: row.escrow_pda,
+                application_id: row.application_id,
+            })
+            .collect();
+        Ok(paginate(rows, page.limit(), NOTIFICATIONS_SORT, |row| {
+            (row.created_at.clone(), row.id)
+        }))
+    }
+
+    async fn mark_notification_read(&self, id: i64, user_id: i64) -> Result<bool, AppError> {
+        let updated = sqlx::query!(
+            "UPDATE notifications SET read = 1 WHERE id = ? AND user_id = ?",
+            id,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(updated.rows_affected() > 0)
+    }
+
+    async fn escrow_by_pda(&self, escrow_pda: &str) -> Result<Option<EscrowResponse>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT n.job_id, u.wallet_address
+            FROM notifications n
+            LEFT JOIN users u ON n.actor_id = u.id
+            WHERE n.escrow_pda = ?
+            LIMIT 1
+            "#,
+            escrow_pda
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| EscrowResponse {
+            job_id: row.job_id,
+            wallet_address: row.wallet_address,
+        }))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::error::AppError;
+use crate::models::profile::{ProfileResponseByIdOrUsername, UserProfilePayload};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+const PROFILE_SELECT: &str = r#"
+    SELECT user_id, username, role, bio, skills, certifications, work_history,
+        profile_ipfs_hash, country, city, latitude, longitude, timezone, work_start,
+        work_end, created_at, updated_at
+    FROM profiles
+"#;
+
+#[async_trait]
+pub trait ProfileRepo: Send + Sync {
+    // `except_user_id` lets a user keep their own username
+    async fn username_taken(
+        &self,
+        username: &str,
+        except_user_id: Option<i64>,
+    ) -> Result<bool, AppError>;
+    // Creates or replaces the user's profile; the skill list is kept separately
+    // through `SkillRepo::set_profile_skills`
+    async fn save_profile(
+        &self,
+        user_id: i64,
+        role: &str,
+        profile: &UserProfilePayload,
+    ) -> Result<(), AppError>;
+    // The reputation is left at its default
+    async fn profile_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<ProfileResponseByIdOrUsername>, AppError>;
+    async fn profile_by_username(
+        &self,
+        username: &str,
+    ) -> Result<Option<ProfileResponseByIdOrUsername>, AppError>;
+}
+
+#[async_trait]
+impl ProfileRepo for SqlitePool {
+    async fn username_taken(
+        &self,
+        username: &str,
+        except_user_id: Option<i64>,
+    ) -> Result<bool, AppError> {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM profiles WHERE username = ? AND (? IS NULL OR user_id != ?)
+            ) AS "taken!: bool"
+            "#,
+            username,
+            except_user_id,
+            except_user_id
+        )
+        .fetch_one(self)
+        .await?;
+        Ok(taken)
+    }
+
+    async fn save_profile(
+        &self,
+        user_id: i64,
+        role: &str,
+        profile: &UserProfilePayload,
+    ) -> Result<(), AppError> {
+        let place = &profile.place;
+        let (work_start_utc, work_minutes) =
+            place.utc_window().map(|w| (w.start, w.minutes)).unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO profiles (
+                user_id, username, role, bio, skills, certifications, work_history, profile_ipfs_hash,
+                country, city, latitude, longitude, timezone, work_start, work_end, work_start_utc,
+                work_minutes
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(user_id) DO UPDATE SET
+                username = excluded.username,
+                role = excluded.role,
+                bio = excluded.bio,
+                skills = excluded.skills,
+                certifications = excluded.certifications,
+                work_history = excluded.work_history,
+                profile_ipfs_hash = excluded.profile_ipfs_hash,
+                country = excluded.country,
+                city = excluded.city,
+                latitude = excluded.latitude,
+                longitude = excluded.longitude,
+                timezone = excluded.timezone,
+                work_start = excluded.work_start,
+                work_end = excluded.work_end,
+                work_start_utc = excluded.work_start_utc,
+                work_minutes = excluded.work_minutes,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+            user_id,
+            profile.username,
+            role,
+            profile.bio,
+            profile.skills,
+            profile.certifications,
+            profile.work_history,
+            profile.profile_ipfs_hash,
+            place.country,
+            place.city,
+            place.latitude,
+            place.longitude,
+            place.timezone,
+            place.work_start,
+            place.work_end,
+            work_start_utc,
+            work_minutes
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn profile_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<ProfileResponseByIdOrUsername>, AppError> {
+        Ok(sqlx::query_as::<_, ProfileResponseByIdOrUsername>(&format!(
+            "{} WHERE user_id = ?",
+            PROFILE_SELECT
+        ))
+        .bind(user_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn profile_by_username(
+        &self,
+        username: &str,
+    ) -> Result<Option<ProfileResponseByIdOrUsername>, AppError> {
+        Ok(sqlx::query_as::<_, ProfileResponseByIdOrUsername>(&format!(
+            "{} WHERE username = ?",
+            PROFILE_SELECT
+        ))
+        .bind(username)
+        .fetch_optional(self)
+        .await?)
+    }
+}
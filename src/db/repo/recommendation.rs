@@ -0,0 +1,157 @@
+use crate::error::AppError;
+use crate::models::recommendation::{FreelancerCandidate, FreelancerHistory, JobCandidate};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[async_trait]
+pub trait RecommendationRepo: Send + Sync {
+    async fn freelancer_history(&self, user_id: i64) -> Result<FreelancerHistory, AppError>;
+    // Open jobs the freelancer can still apply to, newest first
+    async fn job_candidates(&self, user_id: i64, limit: i64)
+        -> Result<Vec<JobCandidate>, AppError>;
+    // Freelancers sharing a skill or category history with the job who haven't
+    // applied or been invited yet
+    async fn freelancer_candidates(
+        &self,
+        job_id: i64,
+        limit: i64,
+    ) -> Result<Vec<FreelancerCandidate>, AppError>;
+}
+
+#[async_trait]
+impl RecommendationRepo for SqlitePool {
+    async fn freelancer_history(&self, user_id: i64) -> Result<FreelancerHistory, AppError> {
+        let category_counts = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT j.category_id, COUNT(*)
+            FROM jobs j
+            WHERE j.category_id IS NOT NULL AND j.id IN (
+                SELECT job_id FROM job_user_interactions
+                WHERE user_id = ?1 AND (applied OR saved)
+                UNION
+                SELECT job_id FROM saved_jobs WHERE user_id = ?1
+            )
+            GROUP BY j.category_id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self)
+        .await?
+        .into_iter()
+        .collect();
+
+        let typical_budget = sqlx::query_scalar::<_, Option<f64>>(
+            r#"
+            SELECT AVG(COALESCE(ja.agreed_amount, ja.bid_amount, j.budget))
+            FROM job_applications ja
+            JOIN jobs j ON j.id = ja.job_id
+            WHERE ja.user_id = ? AND ja.status != 'withdrawn'
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self)
+        .await?;
+
+        Ok(FreelancerHistory {
+            category_counts,
+            typical_budget,
+        })
+    }
+
+    async fn job_candidates(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> Result<Vec<JobCandidate>, AppError> {
+        Ok(sqlx::query_as::<_, JobCandidate>(
+            r#"
+            SELECT
+                j.id, j.title, j.description, j.skills, j.budget, j.location, j.remote,
+                j.country, j.city, j.latitude, j.longitude, j.timezone, j.work_start,
+                j.work_end, j.job_type, j.weekly_hour_cap, j.job_ipfs_hash, j.posted_at,
+                j.deadline, j.client_id, j.category, j.status, j.visibility, j.category_id,
+                (SELECT COUNT(*) FROM job_skills js WHERE js.job_id = j.id) AS skill_count,
+                (SELECT COUNT(*) FROM job_skills js
+                 JOIN profile_skills ps ON ps.skill_id = js.skill_id AND ps.user_id = ?1
+                 WHERE js.job_id = j.id) AS profile_matches,
+                (SELECT COUNT(*) FROM job_skills js
+                 WHERE js.job_id = j.id AND js.skill_id IN (
+                     SELECT seen.skill_id FROM job_skills seen
+                     JOIN job_user_interactions i ON i.job_id = seen.job_id
+                     WHERE i.user_id = ?1 AND (i.applied OR i.saved)
+                 )) AS history_matches,
+                (SELECT GROUP_CONCAT(s.name, ', ') FROM job_skills js
+                 JOIN profile_skills ps ON ps.skill_id = js.skill_id AND ps.user_id = ?1
+                 JOIN skills s ON s.id = js.skill_id
+                 WHERE js.job_id = j.id) AS matched_skills
+            FROM jobs j
+            WHERE j.status = 'open'
+              AND j.deadline >= date('now')
+              AND j.client_id != ?1
+              AND (j.visibility = 'public' OR EXISTS (
+                  SELECT 1 FROM job_invitations ji WHERE ji.job_id = j.id AND ji.freelancer_id = ?1
+              ))
+              AND NOT EXISTS (
+                  SELECT 1 FROM job_applications ja WHERE ja.job_id = j.id AND ja.user_id = ?1
+              )
+            ORDER BY j.posted_at DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn freelancer_candidates(
+        &self,
+        job_id: i64,
+        limit: i64,
+    ) -> Result<Vec<FreelancerCandidate>, AppError> {
+        Ok(sqlx::query_as::<_, FreelancerCandidate>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    u.id AS user_id, p.username, p.skills,
+                    (SELECT GROUP_CONCAT(s.name, ', ') FROM job_skills js
+                     JOIN profile_skills ps ON ps.skill_id = js.skill_id AND ps.user_id = u.id
+                     JOIN skills s ON s.id = js.skill_id
+                     WHERE js.job_id = ?1) AS matched_skills,
+                    (SELECT COUNT(*) FROM job_skills js
+                     JOIN profile_skills ps ON ps.skill_id = js.skill_id AND ps.user_id = u.id
+                     WHERE js.job_id = ?1) AS profile_matches,
+                    (SELECT COUNT(*) FROM job_skills WHERE job_id = ?1) AS job_skill_count,
+                    (SELECT COUNT(*) FROM job_applications ja
+                     WHERE ja.user_id = u.id) AS applications,
+                    (SELECT COUNT(*) FROM job_applications ja
+                     JOIN jobs past ON past.id = ja.job_id
+                     WHERE ja.user_id = u.id
+                       AND past.category_id = (SELECT category_id FROM jobs WHERE id = ?1)
+                    ) AS category_applications,
+                    (SELECT COUNT(*) FROM job_applications ja
+                     WHERE ja.user_id = u.id AND ja.status = 'accepted') AS hires,
+                    (SELECT AVG(COALESCE(ja.agreed_amount, ja.bid_amount, past.budget))
+                     FROM job_applications ja JOIN jobs past ON past.id = ja.job_id
+                     WHERE ja.user_id = u.id AND ja.status != 'withdrawn') AS typical_budget
+                FROM users u
+                JOIN profiles p ON p.user_id = u.id
+                WHERE u.role = 'freelancer'
+                  AND NOT EXISTS (
+                      SELECT 1 FROM job_applications ja WHERE ja.job_id = ?1 AND ja.user_id = u.id
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM job_invitations ji WHERE ji.job_id = ?1 AND ji.freelancer_id = u.id
+                  )
+            )
+            WHERE profile_matches > 0 OR category_applications > 0
+            ORDER BY profile_matches DESC, user_id DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(job_id)
+        .bind(limit)
+        .fetch_all(self)
+        .await?)
+    }
+}
@@ -0,0 +1,237 @@
+use crate::db::pagination::{paginate, Cursor, Page, PageQuery};
+use crate::error::AppError;
+use crate::models::review::{CreateReview, Reputation, Review, ReviewContract};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+const REVIEW_SELECT: &str = r#"
+    SELECT
+        r.id, r.job_id, j.title AS job_title, r.reviewer_id, p.username AS reviewer_username,
+        r.reviewee_id, r.rating, r.review, r.review_ipfs_hash, r.created_at
+    FROM reviews r
+    JOIN jobs j ON j.id = r.job_id
+    LEFT JOIN profiles p ON p.user_id = r.reviewer_id
+"#;
+
+// A review is revealed once the reviewee has reviewed back, or once the job's
+// review window closed (completed at or before the bound cutoff)
+const REVIEW_VISIBLE: &str = r#"
+    (EXISTS (SELECT 1 FROM reviews back WHERE back.job_id = r.job_id AND back.reviewer_id = r.reviewee_id)
+        OR j.completed_at <= ?)
+"#;
+
+const USER_REVIEWS_SORT: &str = "created_at:desc";
+
+#[async_trait]
+pub trait ReviewRepo: Send + Sync {
+    async fn review_contract(&self, job_id: i64) -> Result<Option<ReviewContract>, AppError>;
+    // Fails with `AppError::Conflict` when the reviewer already reviewed the job
+    async fn create_review(
+        &self,
+        job_id: i64,
+        reviewer_id: i64,
+        reviewee_id: i64,
+        review: &CreateReview,
+    ) -> Result<i64, AppError>;
+    // `cutoff` is the completion time at or before which a job's window has closed
+    // ("YYYY-MM-DD HH:MM:SS"); the viewer always sees their own review
+    async fn job_reviews(
+        &self,
+        job_id: i64,
+        viewer_id: i64,
+        cutoff: &str,
+    ) -> Result<Vec<Review>, AppError>;
+    // Visible reviews about the user, newest first
+    async fn reviews_for_user(
+        &self,
+        user_id: i64,
+        cutoff: &str,
+        page: &PageQuery,
+    ) -> Result<Page<Review>, AppError>;
+    // Users without any contracts or reviews get the default (empty) reputation
+    async fn reputations(
+        &self,
+        user_ids: &[i64],
+        cutoff: &str,
+    ) -> Result<HashMap<i64, Reputation>, AppError>;
+}
+
+fn rate(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+#[async_trait]
+impl ReviewRepo for SqlitePool {
+    async fn review_contract(&self, job_id: i64) -> Result<Option<ReviewContract>, AppError> {
+        Ok(sqlx::query_as::<_, ReviewContract>(
+            r#"
+            SELECT
+                j.id AS job_id, j.title AS job_title, j.status, j.completed_at, j.client_id,
+                (
+                    SELECT ja.user_id FROM job_applications ja
+                    WHERE ja.job_id = j.id AND ja.approved = 1
+                    ORDER BY ja.approved_at DESC, ja.id DESC
+                    LIMIT 1
+                ) AS freelancer_id
+            FROM jobs j
+            WHERE j.id = ?
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn create_review(
+        &self,
+        job_id: i64,
+        reviewer_id: i64,
+        reviewee_id: i64,
+        review: &CreateReview,
+    ) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO reviews (job_id, reviewer_id, reviewee_id, rating, review, review_ipfs_hash)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            job_id,
+            reviewer_id,
+            reviewee_id,
+            review.rating,
+            review.review,
+            review.review_ipfs_hash
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn job_reviews(
+        &self,
+        job_id: i64,
+        viewer_id: i64,
+        cutoff: &str,
+    ) -> Result<Vec<Review>, AppError> {
+        Ok(sqlx::query_as::<_, Review>(&format!(
+            "{} WHERE r.job_id = ? AND (r.reviewer_id = ? OR {}) ORDER BY r.id",
+            REVIEW_SELECT, REVIEW_VISIBLE
+        ))
+        .bind(job_id)
+        .bind(viewer_id)
+        .bind(cutoff)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn reviews_for_user(
+        &self,
+        user_id: i64,
+        cutoff: &str,
+        page: &PageQuery,
+    ) -> Result<Page<Review>, AppError> {
+        let after = page
+            .cursor
+            .as_deref()
+            .map(|raw| Cursor::decode(raw, USER_REVIEWS_SORT))
+            .transpose()?;
+        let (after_key, after_id) = after.map(|c| (c.key, c.id)).unzip();
+        let rows = sqlx::query_as::<_, Review>(&format!(
+            r#"{} WHERE r.reviewee_id = ? AND {}
+                AND (? IS NULL OR r.created_at < ? OR (r.created_at = ? AND r.id < ?))
+            ORDER BY r.created_at DESC, r.id DESC
+            LIMIT ?"#,
+            REVIEW_SELECT, REVIEW_VISIBLE
+        ))
+        .bind(user_id)
+        .bind(cutoff)
+        .bind(&after_key)
+        .bind(&after_key)
+        .bind(&after_key)
+        .bind(after_id)
+        .bind(page.limit() + 1)
+        .fetch_all(self)
+        .await?;
+        Ok(paginate(rows, page.limit(), USER_REVIEWS_SORT, |row| {
+            (row.created_at.clone(), row.id)
+        }))
+    }
+
+    async fn reputations(
+        &self,
+        user_ids: &[i64],
+        cutoff: &str,
+    ) -> Result<HashMap<i64, Reputation>, AppError> {
+        let mut reputations: HashMap<i64, Reputation> = user_ids
+            .iter()
+            .map(|&id| (id, Reputation::default()))
+            .collect();
+        if user_ids.is_empty() {
+            return Ok(reputations);
+        }
+        let placeholders = vec!["?"; user_ids.len()].join(", ");
+
+        let sql = format!(
+            r#"
+            SELECT r.reviewee_id, AVG(r.rating), COUNT(*)
+            FROM reviews r
+            JOIN jobs j ON j.id = r.job_id
+            WHERE r.reviewee_id IN ({}) AND {}
+            GROUP BY r.reviewee_id
+            "#,
+            placeholders, REVIEW_VISIBLE
+        );
+        let mut ratings = sqlx::query_as::<_, (i64, f64, i64)>(&sql);
+        for id in user_ids {
+            ratings = ratings.bind(id);
+        }
+        for (user_id, average, count) in ratings.bind(cutoff).fetch_all(self).await? {
+            let reputation = reputations.entry(user_id).or_default();
+            reputation.average_rating = Some(average);
+            reputation.review_count = count;
+        }
+
+        // Every approved application is a contract for both its freelancer and the
+        // job's client. A cancelled contract leaves its job 'rejected', and a
+        // resolved dispute clears `disputed` but keeps `disputed_at`
+        let sql = format!(
+            r#"
+            WITH contracts AS (
+                SELECT ja.user_id AS user_id, j.status, d.disputed_at
+                FROM job_applications ja
+                JOIN jobs j ON j.id = ja.job_id
+                LEFT JOIN job_deliverables d ON d.application_id = ja.id
+                WHERE ja.approved = 1
+                UNION ALL
+                SELECT j.client_id, j.status, d.disputed_at
+                FROM job_applications ja
+                JOIN jobs j ON j.id = ja.job_id
+                LEFT JOIN job_deliverables d ON d.application_id = ja.id
+                WHERE ja.approved = 1
+            )
+            SELECT
+                user_id,
+                COUNT(*),
+                SUM(status = 'completed'),
+                SUM(status IN ('completed', 'rejected')),
+                SUM(disputed_at IS NOT NULL)
+            FROM contracts
+            WHERE user_id IN ({})
+            GROUP BY user_id
+            "#,
+            placeholders
+        );
+        let mut contracts = sqlx::query_as::<_, (i64, i64, i64, i64, i64)>(&sql);
+        for id in user_ids {
+            contracts = contracts.bind(id);
+        }
+        for (user_id, total, completed, finished, disputed) in contracts.fetch_all(self).await? {
+            let reputation = reputations.entry(user_id).or_default();
+            reputation.contracts = total;
+            reputation.completion_rate = rate(completed, finished);
+            reputation.dispute_rate = rate(disputed, total);
+        }
+
+        Ok(reputations)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::AppError;
+use crate::models::saved_search::{CreateSavedSearch, SavedJob, SavedSearch, UpdateSavedSearch};
+use async_trait::async_trait;
+use sqlx::{types::Json, SqlitePool};
+
+const SAVED_SEARCH_SELECT: &str = r#"
+    SELECT id, user_id, name, query, alerts, last_job_id, created_at, updated_at
+    FROM saved_searches
+"#;
+
+#[async_trait]
+pub trait SavedSearchRepo: Send + Sync {
+    // Both return whether anything changed, so repeats are harmless
+    async fn save_job(&self, user_id: i64, job_id: i64) -> Result<bool, AppError>;
+    async fn unsave_job(&self, user_id: i64, job_id: i64) -> Result<bool, AppError>;
+    async fn saved_jobs(&self, user_id: i64) -> Result<Vec<SavedJob>, AppError>;
+    // Only jobs posted after the search was saved can trigger alerts
+    async fn create_saved_search(
+        &self,
+        user_id: i64,
+        search: &CreateSavedSearch,
+    ) -> Result<i64, AppError>;
+    async fn find_saved_search(&self, search_id: i64) -> Result<Option<SavedSearch>, AppError>;
+    async fn saved_searches(&self, user_id: i64) -> Result<Vec<SavedSearch>, AppError>;
+    async fn update_saved_search(
+        &self,
+        search_id: i64,
+        update: &UpdateSavedSearch,
+    ) -> Result<(), AppError>;
+    async fn delete_saved_search(&self, search_id: i64) -> Result<(), AppError>;
+    async fn searches_with_alerts(&self) -> Result<Vec<SavedSearch>, AppError>;
+    async fn advance_saved_search(&self, search_id: i64, last_job_id: i64) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl SavedSearchRepo for SqlitePool {
+    async fn save_job(&self, user_id: i64, job_id: i64) -> Result<bool, AppError> {
+        let mut tx = self.begin().await?;
+        let saved = sqlx::query!(
+            "INSERT INTO saved_jobs (user_id, job_id) VALUES (?, ?) ON CONFLICT DO NOTHING",
+            user_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        sqlx::query!(
+            r#"
+            INSERT INTO job_user_interactions (user_id, job_id, saved)
+            VALUES (?, ?, TRUE)
+            ON CONFLICT(user_id, job_id) DO UPDATE SET saved = TRUE
+            "#,
+            user_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    async fn unsave_job(&self, user_id: i64, job_id: i64) -> Result<bool, AppError> {
+        let mut tx = self.begin().await?;
+        let removed = sqlx::query!(
+            "DELETE FROM saved_jobs WHERE user_id = ? AND job_id = ?",
+            user_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        sqlx::query!(
+            "UPDATE job_user_interactions SET saved = FALSE WHERE user_id = ? AND job_id = ?",
+            user_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    async fn saved_jobs(&self, user_id: i64) -> Result<Vec<SavedJob>, AppError> {
+        Ok(sqlx::query_as::<_, SavedJob>(
+            r#"
+            SELECT
+                j.id, j.title, j.description, j.skills, j.budget, j.location, j.remote,
+                j.country, j.city, j.latitude, j.longitude, j.timezone, j.work_start,
+                j.work_end, j.job_type, j.weekly_hour_cap, j.job_ipfs_hash, j.posted_at,
+                j.deadline, j.client_id, j.category, j.status, j.visibility, s.saved_at
+            FROM saved_jobs s
+            JOIN jobs j ON j.id = s.job_id
+            WHERE s.user_id = ?
+            ORDER BY s.saved_at DESC, j.id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn create_saved_search(
+        &self,
+        user_id: i64,
+        search: &CreateSavedSearch,
+    ) -> Result<i64, AppError> {
+        let name = search.name.trim();
+        let query = Json(&search.query);
+        let alerts = search.alerts.unwrap_or(true);
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO saved_searches (user_id, name, query, alerts, last_job_id)
+            VALUES (?, ?, ?, ?, (SELECT COALESCE(MAX(id), 0) FROM jobs))
+            "#,
+            user_id,
+            name,
+            query,
+            alerts
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn find_saved_search(&self, search_id: i64) -> Result<Option<SavedSearch>, AppError> {
+        Ok(
+            sqlx::query_as::<_, SavedSearch>(&format!("{} WHERE id = ?", SAVED_SEARCH_SELECT))
+                .bind(search_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn saved_searches(&self, user_id: i64) -> Result<Vec<SavedSearch>, AppError> {
+        Ok(sqlx::query_as::<_, SavedSearch>(&format!(
+            "{} WHERE user_id = ? ORDER BY id DESC",
+            SAVED_SEARCH_SELECT
+        ))
+        .bind(user_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn update_saved_search(
+        &self,
+        search_id: i64,
+        update: &UpdateSavedSearch,
+    ) -> Result<(), AppError> {
+        let name = update.name.as_deref().map(str::trim);
+        sqlx::query!(
+            r#"
+            UPDATE saved_searches
+            SET name = COALESCE(?, name),
+                alerts = COALESCE(?, alerts),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            name,
+            update.alerts,
+            search_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_saved_search(&self, search_id: i64) -> Result<(), AppError> {
+        sqlx::query!("DELETE FROM saved_searches WHERE id = ?", search_id)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+
+    async fn searches_with_alerts(&self) -> Result<Vec<SavedSearch>, AppError> {
+        Ok(sqlx::query_as::<_, SavedSearch>(&format!(
+            "{} WHERE alerts ORDER BY id",
+            SAVED_SEARCH_SELECT
+        ))
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn advance_saved_search(&self, search_id: i64, last_job_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE saved_searches SET last_job_id = MAX(last_job_id, ?) WHERE id = ?",
+            last_job_id,
+            search_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::error::AppError;
+use crate::models::skill::{parse_skill_list, Skill, SkillSuggestion};
+use async_trait::async_trait;
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[async_trait]
+pub trait SkillRepo: Send + Sync {
+    // Replace the job's skills from a comma-separated list, creating skills nobody has
+    // used yet, and rewrite jobs.skills with the canonical names
+    async fn set_job_skills(&self, job_id: i64, skills: &str) -> Result<Vec<Skill>, AppError>;
+    async fn set_profile_skills(
+        &self,
+        user_id: i64,
+        skills: Option<&str>,
+    ) -> Result<Vec<Skill>, AppError>;
+    // Look names up by canonical name or alias; None where nothing matches
+    async fn find_skill_ids(&self, names: &[String]) -> Result<Vec<Option<i64>>, AppError>;
+    async fn autocomplete_skills(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<SkillSuggestion>, AppError>;
+}
+
+// Resolve each name to its skill, adding a new canonical skill for unknown names
+async fn resolve_skills(
+    conn: &mut SqliteConnection,
+    names: &[String],
+) -> Result<Vec<Skill>, AppError> {
+    let mut skills: Vec<Skill> = Vec::new();
+    for name in names {
+        let alias = name.to_lowercase();
+        let skill = sqlx::query_as::<_, Skill>(
+            r#"
+            SELECT s.id, s.name FROM skill_aliases sa
+            JOIN skills s ON s.id = sa.skill_id
+            WHERE sa.alias = ?
+            "#,
+        )
+        .bind(&alias)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let skill = match skill {
+            Some(skill) => skill,
+            None => {
+                let id = sqlx::query!("INSERT INTO skills (name) VALUES (?)", name)
+                    .execute(&mut *conn)
+                    .await?
+                    .last_insert_rowid();
+                sqlx::query!(
+                    "INSERT INTO skill_aliases (alias, skill_id) VALUES (?, ?)",
+                    alias,
+                    id
+                )
+                .execute(&mut *conn)
+                .await?;
+                Skill {
+                    id,
+                    name: name.clone(),
+                }
+            }
+        };
+        // Two aliases of one skill ("js, JavaScript") count once
+        if !skills.contains(&skill) {
+            skills.push(skill);
+        }
+    }
+    Ok(skills)
+}
+
+fn display_list(skills: &[Skill]) -> String {
+    skills
+        .iter()
+        .map(|skill| skill.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait]
+impl SkillRepo for SqlitePool {
+    async fn set_job_skills(&self, job_id: i64, skills: &str) -> Result<Vec<Skill>, AppError> {
+        let mut tx = self.begin().await?;
+        let skills = resolve_skills(&mut tx, &parse_skill_list(skills)).await?;
+
+        sqlx::query!("DELETE FROM job_skills WHERE job_id = ?", job_id)
+            .execute(&mut *tx)
+            .await?;
+        for skill in &skills {
+            sqlx::query!(
+                "INSERT INTO job_skills (job_id, skill_id) VALUES (?, ?)",
+                job_id,
+                skill.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let display = display_list(&skills);
+        sqlx::query!("UPDATE jobs SET skills = ? WHERE id = ?", display, job_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(skills)
+    }
+
+    async fn set_profile_skills(
+        &self,
+        user_id: i64,
+        skills: Option<&str>,
+    ) -> Result<Vec<Skill>, AppError> {
+        let mut tx = self.begin().await?;
+        let names = skills.map(parse_skill_list).unwrap_or_default();
+        let skills = resolve_skills(&mut tx, &names).await?;
+
+        sqlx::query!("DELETE FROM profile_skills WHERE user_id = ?", user_id)
+            .execute(&mut *tx)
+            .await?;
+        for skill in &skills {
+            sqlx::query!(
+                "INSERT INTO profile_skills (user_id, skill_id) VALUES (?, ?)",
+                user_id,
+                skill.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let display = Some(display_list(&skills)).filter(|list| !list.is_empty());
+        sqlx::query!(
+            "UPDATE profiles SET skills = ? WHERE user_id = ?",
+            display,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(skills)
+    }
+
+    async fn find_skill_ids(&self, names: &[String]) -> Result<Vec<Option<i64>>, AppError> {
+        let mut ids = Vec::with_capacity(names.len());
+        for name in names {
+            let alias = name.to_lowercase();
+            ids.push(
+                sqlx::query_scalar!("SELECT skill_id FROM skill_aliases WHERE alias = ?", alias)
+                    .fetch_optional(self)
+                    .await?,
+            );
+        }
+        Ok(ids)
+    }
+
+    async fn autocomplete_skills(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> Result<Vec<SkillSuggestion>, AppError> {
+        // Match on any alias so "gola" finds Go, but list each skill once
+        let pattern = format!(
+            "{}%",
+            prefix
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        Ok(sqlx::query_as::<_, SkillSuggestion>(
+            r#"
+            SELECT s.id, s.name,
+                (SELECT COUNT(*) FROM job_skills js WHERE js.skill_id = s.id) AS job_count
+            FROM skills s
+            WHERE s.id IN (SELECT skill_id FROM skill_aliases WHERE alias LIKE ? ESCAPE '\')
+            ORDER BY job_count DESC, s.name
+            LIMIT ?
+            "#,
+        )
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(self)
+        .await?)
+    }
+}
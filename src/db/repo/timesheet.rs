@@ -0,0 +1,294 @@
+use crate::error::AppError;
+use crate::models::timesheet::{LogTime, TimeEntry, TimesheetResponse};
+use async_trait::async_trait;
+use sqlx::{FromRow, SqlitePool};
+
+// The approved application on an hourly job; the agreed bid is the hourly rate
+#[derive(Debug, Clone, FromRow)]
+pub struct HourlyContract {
+    pub application_id: i64,
+    pub job_id: i64,
+    pub job_title: String,
+    pub job_status: String,
+    pub client_id: i64,
+    pub freelancer_id: i64,
+    pub hourly_rate: f64,
+    pub weekly_hour_cap: Option<i64>,
+}
+
+const TIMESHEET_SELECT: &str = r#"
+    SELECT
+        id, application_id, week_start, hours, hourly_rate, amount, status, review_note,
+        escrow_pda, submitted_at, reviewed_at, escrow_recorded_at, release_recorded_at
+    FROM timesheets
+"#;
+
+#[async_trait]
+pub trait TimesheetRepo: Send + Sync {
+    async fn hourly_contract(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<HourlyContract>, AppError>;
+    // Total hours logged in the week starting on `week_start` (a Monday)
+    async fn hours_logged(&self, application_id: i64, week_start: &str) -> Result<f64, AppError>;
+    // None when the entry would take the week past `weekly_hour_cap`. The cap is
+    // checked inside the INSERT, so entries logged at the same time can't both fit
+    async fn log_time(
+        &self,
+        application_id: i64,
+        week_start: &str,
+        weekly_hour_cap: Option<i64>,
+        entry: &LogTime,
+    ) -> Result<Option<TimeEntry>, AppError>;
+    async fn time_entries(
+        &self,
+        application_id: i64,
+        week_start: Option<&str>,
+    ) -> Result<Vec<TimeEntry>, AppError>;
+    async fn find_timesheet(
+        &self,
+        timesheet_id: i64,
+    ) -> Result<Option<TimesheetResponse>, AppError>;
+    async fn timesheet_for_week(
+        &self,
+        application_id: i64,
+        week_start: &str,
+    ) -> Result<Option<TimesheetResponse>, AppError>;
+    async fn timesheets_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<TimesheetResponse>, AppError>;
+    // Total the week's entries at the contract rate; a rejected week is resubmitted in place
+    async fn submit_timesheet(
+        &self,
+        contract: &HourlyContract,
+        week_start: &str,
+    ) -> Result<TimesheetResponse, AppError>;
+    // Each transition returns false if the timesheet wasn't in the expected state
+    async fn review_timesheet(
+        &self,
+        timesheet_id: i64,
+        approve: bool,
+        note: Option<&str>,
+    ) -> Result<bool, AppError>;
+    // Bookkeeping of the client's on-chain escrow steps; false when out of order
+    async fn record_timesheet_escrow(
+        &self,
+        timesheet_id: i64,
+        escrow_pda: &str,
+    ) -> Result<bool, AppError>;
+    async fn record_timesheet_release(&self, timesheet_id: i64) -> Result<bool, AppError>;
+}
+
+#[async_trait]
+impl TimesheetRepo for SqlitePool {
+    async fn hourly_contract(
+        &self,
+        application_id: i64,
+    ) -> Result<Option<HourlyContract>, AppError> {
+        Ok(sqlx::query_as::<_, HourlyContract>(
+            r#"
+            SELECT
+                ja.id AS application_id, ja.job_id, j.title AS job_title,
+                j.status AS job_status, j.client_id, ja.user_id AS freelancer_id,
+                CAST(COALESCE(ja.agreed_amount, j.budget) AS REAL) AS hourly_rate, j.weekly_hour_cap
+            FROM job_applications ja
+            JOIN jobs j ON j.id = ja.job_id
+            WHERE ja.id = ? AND ja.approved = 1 AND j.job_type = 'hourly'
+            "#,
+        )
+        .bind(application_id)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn hours_logged(&self, application_id: i64, week_start: &str) -> Result<f64, AppError> {
+        Ok(sqlx::query_scalar::<_, f64>(
+            r#"
+            SELECT COALESCE(SUM(hours), 0.0) FROM time_entries
+            WHERE application_id = ? AND work_date BETWEEN ? AND date(?, '+6 days')
+            "#,
+        )
+        .bind(application_id)
+        .bind(week_start)
+        .bind(week_start)
+        .fetch_one(self)
+        .await?)
+    }
+
+    async fn log_time(
+        &self,
+        application_id: i64,
+        week_start: &str,
+        weekly_hour_cap: Option<i64>,
+        entry: &LogTime,
+    ) -> Result<Option<TimeEntry>, AppError> {
+        Ok(sqlx::query_as::<_, TimeEntry>(
+            r#"
+            INSERT INTO time_entries (application_id, work_date, hours, description)
+            SELECT ?1, ?2, ?3, ?4
+            WHERE ?5 IS NULL OR ?3 + (
+                SELECT COALESCE(SUM(hours), 0.0) FROM time_entries
+                WHERE application_id = ?1 AND work_date BETWEEN ?6 AND date(?6, '+6 days')
+            ) <= ?5
+            RETURNING id, application_id, work_date, hours, description, created_at
+            "#,
+        )
+        .bind(application_id)
+        .bind(&entry.work_date)
+        .bind(entry.hours)
+        .bind(&entry.description)
+        .bind(weekly_hour_cap)
+        .bind(week_start)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn time_entries(
+        &self,
+        application_id: i64,
+        week_start: Option<&str>,
+    ) -> Result<Vec<TimeEntry>, AppError> {
+        Ok(sqlx::query_as::<_, TimeEntry>(
+            r#"
+            SELECT id, application_id, work_date, hours, description, created_at
+            FROM time_entries
+            WHERE application_id = ?1
+              AND (?2 IS NULL OR work_date BETWEEN ?2 AND date(?2, '+6 days'))
+            ORDER BY work_date, id
+            "#,
+        )
+        .bind(application_id)
+        .bind(week_start)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn find_timesheet(
+        &self,
+        timesheet_id: i64,
+    ) -> Result<Option<TimesheetResponse>, AppError> {
+        Ok(
+            sqlx::query_as::<_, TimesheetResponse>(&format!("{} WHERE id = ?", TIMESHEET_SELECT))
+                .bind(timesheet_id)
+                .fetch_optional(self)
+                .await?,
+        )
+    }
+
+    async fn timesheet_for_week(
+        &self,
+        application_id: i64,
+        week_start: &str,
+    ) -> Result<Option<TimesheetResponse>, AppError> {
+        Ok(sqlx::query_as::<_, TimesheetResponse>(&format!(
+            "{} WHERE application_id = ? AND week_start = ?",
+            TIMESHEET_SELECT
+        ))
+        .bind(application_id)
+        .bind(week_start)
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn timesheets_for_application(
+        &self,
+        application_id: i64,
+    ) -> Result<Vec<TimesheetResponse>, AppError> {
+        Ok(sqlx::query_as::<_, TimesheetResponse>(&format!(
+            "{} WHERE application_id = ? ORDER BY week_start",
+            TIMESHEET_SELECT
+        ))
+        .bind(application_id)
+        .fetch_all(self)
+        .await?)
+    }
+
+    async fn submit_timesheet(
+        &self,
+        contract: &HourlyContract,
+        week_start: &str,
+    ) -> Result<TimesheetResponse, AppError> {
+        let hours = self
+            .hours_logged(contract.application_id, week_start)
+            .await?;
+        let amount = hours * contract.hourly_rate;
+        sqlx::query(
+            r#"
+            INSERT INTO timesheets (application_id, week_start, hours, hourly_rate, amount)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (application_id, week_start) DO UPDATE
+            SET hours = excluded.hours, hourly_rate = excluded.hourly_rate,
+                amount = excluded.amount, status = 'submitted', review_note = NULL,
+                submitted_at = CURRENT_TIMESTAMP, reviewed_at = NULL
+            WHERE timesheets.status = 'rejected'
+            "#,
+        )
+        .bind(contract.application_id)
+        .bind(week_start)
+        .bind(hours)
+        .bind(contract.hourly_rate)
+        .bind(amount)
+        .execute(self)
+        .await?;
+
+        self.timesheet_for_week(contract.application_id, week_start)
+            .await?
+            .ok_or(AppError::NotFound("Timesheet not found".into()))
+    }
+
+    async fn review_timesheet(
+        &self,
+        timesheet_id: i64,
+        approve: bool,
+        note: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let status = if approve { "approved" } else { "rejected" };
+        let result = sqlx::query!(
+            r#"
+            UPDATE timesheets
+            SET status = ?, review_note = ?, reviewed_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'submitted'
+            "#,
+            status,
+            note,
+            timesheet_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_timesheet_escrow(
+        &self,
+        timesheet_id: i64,
+        escrow_pda: &str,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE timesheets
+            SET status = 'escrow_recorded', escrow_pda = ?, escrow_recorded_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'approved'
+            "#,
+            escrow_pda,
+            timesheet_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_timesheet_release(&self, timesheet_id: i64) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE timesheets
+            SET status = 'release_recorded', release_recorded_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'escrow_recorded'
+            "#,
+            timesheet_id
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::AppError;
+use crate::models::account::EmailTokenPurpose;
+use crate::wallet::WalletChain;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+// A wallet sign-in challenge; the timestamps are RFC 3339 and part of the signed message
+#[derive(Debug, Clone)]
+pub struct NonceRecord {
+    pub nonce: String,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+// Single-use email links, wallet sign-in nonces and revoked JWTs. Only hashes of
+// email tokens are stored
+#[async_trait]
+pub trait TokenRepo: Send + Sync {
+    // Replaces any unused token of the same purpose, so only the latest link works
+    async fn store_email_token(
+        &self,
+        user_id: i64,
+        purpose: EmailTokenPurpose,
+        token_hash: &str,
+        created_at: i64,
+        expires_at: i64,
+    ) -> Result<(), AppError>;
+    // Marks the token used and returns its user; None if it is unknown, used or expired
+    async fn consume_email_token(
+        &self,
+        token_hash: &str,
+        purpose: EmailTokenPurpose,
+        now: i64,
+    ) -> Result<Option<i64>, AppError>;
+    // Replaces the wallet's outstanding nonce, so repeated requests can't grow the table
+    async fn store_nonce(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        nonce: &NonceRecord,
+    ) -> Result<(), AppError>;
+    async fn find_nonce(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        nonce: &str,
+    ) -> Result<Option<NonceRecord>, AppError>;
+    // False if the nonce was already deleted, i.e. used by a concurrent request
+    async fn delete_nonce(&self, wallet_address: &str, nonce: &str) -> Result<bool, AppError>;
+    // `now` is RFC 3339, like the stored expiry
+    async fn purge_expired_nonces(&self, now: &str) -> Result<(), AppError>;
+    // Kept until `expires_at` (the JWT's exp), after which the token is dead anyway
+    async fn blacklist_token(&self, token: &str, expires_at: i64) -> Result<(), AppError>;
+    async fn is_blacklisted(&self, token: &str, now: i64) -> Result<bool, AppError>;
+    async fn purge_blacklisted_tokens(&self, now: i64) -> Result<(), AppError>;
+}
+
+#[async_trait]
+impl TokenRepo for SqlitePool {
+    async fn store_email_token(
+        &self,
+        user_id: i64,
+        purpose: EmailTokenPurpose,
+        token_hash: &str,
+        created_at: i64,
+        expires_at: i64,
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        sqlx::query!(
+            "DELETE FROM email_tokens WHERE user_id = ? AND purpose = ? AND used_at IS NULL",
+            user_id,
+            purpose
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO email_tokens (user_id, token_hash, purpose, created_at, expires_at) VALUES (?, ?, ?, ?, ?)",
+            user_id,
+            token_hash,
+            purpose,
+            created_at,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn consume_email_token(
+        &self,
+        token_hash: &str,
+        purpose: EmailTokenPurpose,
+        now: i64,
+    ) -> Result<Option<i64>, AppError> {
+        // Checked and marked in one statement so a token can't be used twice concurrently
+        Ok(sqlx::query_scalar!(
+            r#"
+            UPDATE email_tokens SET used_at = ?
+            WHERE token_hash = ? AND purpose = ? AND used_at IS NULL AND expires_at >= ?
+            RETURNING user_id
+            "#,
+            now,
+            token_hash,
+            purpose,
+            now
+        )
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn store_nonce(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        nonce: &NonceRecord,
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        sqlx::query!(
+            "DELETE FROM nonces WHERE wallet_address = ?",
+            wallet_address
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO nonces (wallet_address, chain, nonce, created_at, expires_at) VALUES (?,?,?,?,?)",
+            wallet_address,
+            chain,
+            nonce.nonce,
+            nonce.created_at,
+            nonce.expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_nonce(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        nonce: &str,
+    ) -> Result<Option<NonceRecord>, AppError> {
+        let row = sqlx::query!(
+            "SELECT nonce, created_at, expires_at FROM nonces WHERE wallet_address = ? AND nonce = ? AND chain = ?",
+            wallet_address,
+            nonce,
+            chain
+        )
+        .fetch_optional(self)
+        .await?;
+        Ok(row.map(|row| NonceRecord {
+            nonce: row.nonce,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }))
+    }
+
+    async fn delete_nonce(&self, wallet_address: &str, nonce: &str) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM nonces WHERE wallet_address = ? AND nonce = ?",
+            wallet_address,
+            nonce
+        )
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn purge_expired_nonces(&self, now: &str) -> Result<(), AppError> {
+        sqlx::query!("DELETE FROM nonces WHERE expires_at < ?", now)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+
+    async fn blacklist_token(&self, token: &str, expires_at: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO blacklisted_tokens (token, expires_at) VALUES (?, ?)",
+            token,
+            expires_at
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn is_blacklisted(&self, token: &str, now: i64) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM blacklisted_tokens WHERE token = ? AND expires_at > ?",
+            token,
+            now
+        )
+        .fetch_one(self)
+        .await?;
+        Ok(count > 0)
+    }
+
+    async fn purge_blacklisted_tokens(&self, now: i64) -> Result<(), AppError> {
+        sqlx::query!("DELETE FROM blacklisted_tokens WHERE expires_at < ?", now)
+            .execute(self)
+            .await?;
+        Ok(())
+    }
+}
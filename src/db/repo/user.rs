@@ -0,0 +1,571 @@
+use crate::error::AppError;
+use crate::models::location::UtcWindow;
+use crate::wallet::WalletChain;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+// What an email/password login needs to decide on the outcome
+#[derive(Debug, Clone)]
+pub struct LoginRecord {
+    pub id: i64,
+    pub password: Option<String>, // None for wallet-only accounts
+    pub role: String,
+    pub wallet_user: bool,
+    pub verified_wallet: bool,
+    pub email_verified: bool,
+    pub totp_enabled: bool,
+    pub failed_login_attempts: i64,
+    pub locked_until: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserSummary {
+    pub id: i64,
+    pub role: String,
+    pub wallet_address: Option<String>,
+    pub wallet_user: bool,
+    pub verified_wallet: bool,
+    pub username: Option<String>, // from the profile, if one was created
+}
+
+// Account state behind an authenticated request
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub id: i64,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub wallet_address: Option<String>,
+    pub wallet_chain: Option<WalletChain>,
+    pub wallet_user: bool,
+    pub verified_wallet: bool,
+    pub admin: bool,
+    pub password_changed_at: Option<i64>, // JWTs issued before this are revoked
+    pub totp_enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TotpState {
+    pub secret: Option<String>, // set at enrolment, before 2FA is enabled
+    pub enabled: bool,
+    pub failed_login_attempts: i64,
+    pub locked_until: Option<i64>, // wrong codes count towards the login lockout
+}
+
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    async fn create_email_user(
+        &self,
+        email: &str,
+        password_hash: &str,
+        role: &str,
+    ) -> Result<i64, AppError>;
+    async fn create_wallet_user(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        role: &str,
+        email: Option<&str>,
+        verified_wallet: bool,
+    ) -> Result<i64, AppError>;
+    async fn login_record(&self, email: &str) -> Result<Option<LoginRecord>, AppError>;
+    async fn account(&self, user_id: i64) -> Result<Option<Account>, AppError>;
+    // Only email/password accounts have one
+    async fn password_user_id(&self, email: &str) -> Result<Option<i64>, AppError>;
+    async fn user_summary(&self, user_id: i64) -> Result<Option<UserSummary>, AppError>;
+    async fn user_by_wallet(&self, wallet_address: &str) -> Result<Option<UserSummary>, AppError>;
+    async fn user_by_username(&self, username: &str) -> Result<Option<UserSummary>, AppError>;
+    async fn connect_wallet(
+        &self,
+        user_id: i64,
+        wallet_address: &str,
+        chain: WalletChain,
+    ) -> Result<(), AppError>;
+    async fn set_wallet_verified(&self, user_id: i64) -> Result<(), AppError>;
+    async fn set_login_failures(
+        &self,
+        user_id: i64,
+        failures: i64,
+        locked_until: Option<i64>,
+    ) -> Result<(), AppError>;
+    // Count one failed password or second factor; the `max_failures`th locks the
+    // account until `lock_until` and starts the count over
+    async fn record_login_failure(
+        &self,
+        user_id: i64,
+        max_failures: i64,
+        lock_until: i64,
+    ) -> Result<(), AppError>;
+    async fn set_email_verified(&self, user_id: i64) -> Result<(), AppError>;
+    // Also verifies the email, revokes every earlier session (via
+    // `password_changed_at`) and invalidates outstanding email links
+    async fn reset_password(
+        &self,
+        user_id: i64,
+        password_hash: &str,
+        now: i64,
+    ) -> Result<(), AppError>;
+    async fn totp_state(&self, user_id: i64) -> Result<Option<TotpState>, AppError>;
+    // Store a new secret, inactive until `enable_totp`
+    async fn start_totp_enrolment(&self, user_id: i64, secret: &str) -> Result<(), AppError>;
+    async fn enable_totp(&self, user_id: i64, step: i64) -> Result<(), AppError>;
+    // Also drops the recovery codes
+    async fn disable_totp(&self, user_id: i64) -> Result<(), AppError>;
+    // Records `step` as used; false if it (or a later step) already was
+    async fn use_totp_step(&self, user_id: i64, step: i64) -> Result<bool, AppError>;
+    async fn replace_recovery_codes(
+        &self,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> Result<(), AppError>;
+    // False if the code is unknown or already used
+    async fn use_recovery_code(
+        &self,
+        user_id: i64,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, AppError>;
+    // The first admin, who is assigned as arbiter on new disputes
+    async fn find_arbiter(&self) -> Result<Option<UserSummary>, AppError>;
+    // The profile's working hours in UTC, once it has a timezone and hours
+    async fn working_hours(&self, user_id: i64) -> Result<Option<UtcWindow>, AppError>;
+}
+
+#[async_trait]
+impl UserRepo for SqlitePool {
+    async fn create_email_user(
+        &self,
+        email: &str,
+        password_hash: &str,
+        role: &str,
+    ) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            "INSERT INTO users (email, password, role, verified_wallet) VALUES (?, ?, ?, ?)",
+            email,
+            password_hash,
+            role,
+            false
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn create_wallet_user(
+        &self,
+        wallet_address: &str,
+        chain: WalletChain,
+        role: &str,
+        email: Option<&str>,
+        verified_wallet: bool,
+    ) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            "INSERT INTO users (email, wallet_address, wallet_chain, role, wallet_user, verified_wallet) VALUES (?, ?, ?, ?, ?, ?)",
+            email,
+            wallet_address,
+            chain,
+            role,
+            true,
+            verified_wallet
+        )
+        .execute(self)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn login_record(&self, email: &str) -> Result<Option<LoginRecord>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id AS "id!: i64", password, role, wallet_user, verified_wallet, email_verified,
+                totp_enabled, failed_login_attempts, locked_until
+            FROM users
+            WHERE email = ?
+            "#,
+            email
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| LoginRecord {
+            id: row.id,
+            password: row.password,
+            role: row.role,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            email_verified: row.email_verified,
+            totp_enabled: row.totp_enabled,
+            failed_login_attempts: row.failed_login_attempts,
+            locked_until: row.locked_until,
+        }))
+    }
+
+    async fn account(&self, user_id: i64) -> Result<Option<Account>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id AS "id!: i64", email, email_verified, wallet_address,
+                wallet_chain AS "wallet_chain: WalletChain", wallet_user, verified_wallet, admin,
+                password_changed_at, totp_enabled
+            FROM users
+            WHERE id = ?
+            "#,
+            user_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| Account {
+            id: row.id,
+            email: row.email,
+            email_verified: row.email_verified,
+            wallet_address: row.wallet_address,
+            wallet_chain: row.wallet_chain,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            admin: row.admin,
+            password_changed_at: row.password_changed_at,
+            totp_enabled: row.totp_enabled,
+        }))
+    }
+
+    async fn password_user_id(&self, email: &str) -> Result<Option<i64>, AppError> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT id AS "id!: i64" FROM users WHERE email = ? AND password IS NOT NULL"#,
+            email
+        )
+        .fetch_optional(self)
+        .await?)
+    }
+
+    async fn user_summary(&self, user_id: i64) -> Result<Option<UserSummary>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT u.id AS "id!: i64", u.role, u.wallet_address, u.wallet_user, u.verified_wallet,
+                p.username AS "username?"
+            FROM users u
+            LEFT JOIN profiles p ON p.user_id = u.id
+            WHERE u.id = ?
+            "#,
+            user_id
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| UserSummary {
+            id: row.id,
+            role: row.role,
+            wallet_address: row.wallet_address,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            username: row.username,
+        }))
+    }
+
+    async fn user_by_wallet(&self, wallet_address: &str) -> Result<Option<UserSummary>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT u.id AS "id!: i64", u.role, u.wallet_address, u.wallet_user, u.verified_wallet,
+                p.username AS "username?"
+            FROM users u
+            LEFT JOIN profiles p ON p.user_id = u.id
+            WHERE u.wallet_address = ?
+            "#,
+            wallet_address
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| UserSummary {
+            id: row.id,
+            role: row.role,
+            wallet_address: row.wallet_address,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            username: row.username,
+        }))
+    }
+
+    async fn user_by_username(&self, username: &str) -> Result<Option<UserSummary>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT u.id AS "id!: i64", u.role, u.wallet_address, u.wallet_user, u.verified_wallet,
+                p.username
+            FROM users u
+            JOIN profiles p ON p.user_id = u.id
+            WHERE p.username = ?
+            "#,
+            username
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| UserSummary {
+            id: row.id,
+            role: row.role,
+            wallet_address: row.wallet_address,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            username: Some(row.username),
+        }))
+    }
+
+    async fn connect_wallet(
+        &self,
+        user_id: i64,
+        wallet_address: &str,
+        chain: WalletChain,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET wallet_address = ?, wallet_chain = ?, wallet_user = ? WHERE id = ?",
+            wallet_address,
+            chain,
+            true,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_wallet_verified(&self, user_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET verified_wallet = ? WHERE id = ?",
+            true,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_login_failures(
+        &self,
+        user_id: i64,
+        failures: i64,
+        locked_until: Option<i64>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET failed_login_attempts = ?, locked_until = ? WHERE id = ?",
+            failures,
+            locked_until,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_email_verified(&self, user_id: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET email_verified = ? WHERE id = ?",
+            true,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn reset_password(
+        &self,
+        user_id: i64,
+        password_hash: &str,
+        now: i64,
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        // Receiving the reset mail proves ownership of the address
+        sqlx::query!(
+            "UPDATE users SET password = ?, password_changed_at = ?, email_verified = ? WHERE id = ?",
+            password_hash,
+            now,
+            true,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE email_tokens SET used_at = ? WHERE user_id = ? AND used_at IS NULL",
+            now,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn totp_state(&self, user_id: i64) -> Result<Option<TotpState>, AppError> {
+        let row = sqlx::query!(
+            "SELECT totp_secret, totp_enabled, failed_login_attempts, locked_until FROM users WHERE id = ?",
+            user_id
+        )
+        .fetch_optional(self)
+        .await?;
+        Ok(row.map(|row| TotpState {
+            secret: row.totp_secret,
+            enabled: row.totp_enabled,
+            failed_login_attempts: row.failed_login_attempts,
+            locked_until: row.locked_until,
+        }))
+    }
+
+    async fn start_totp_enrolment(&self, user_id: i64, secret: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET totp_secret = ?, totp_last_step = NULL WHERE id = ?",
+            secret,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn enable_totp(&self, user_id: i64, step: i64) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE users SET totp_enabled = ?, totp_last_step = ? WHERE id = ?",
+            true,
+            step,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn disable_totp(&self, user_id: i64) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        sqlx::query!(
+            "UPDATE users SET totp_enabled = ?, totp_secret = NULL, totp_last_step = NULL WHERE id = ?",
+            false,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM two_factor_recovery_codes WHERE user_id = ?",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn use_totp_step(&self, user_id: i64, step: i64) -> Result<bool, AppError> {
+        // Checked in the update itself so two concurrent logins can't both use a code
+        let used = sqlx::query!(
+            "UPDATE users SET totp_last_step = ? WHERE id = ? AND (totp_last_step IS NULL OR totp_last_step < ?)",
+            step,
+            user_id,
+            step
+        )
+        .execute(self)
+        .await?;
+        Ok(used.rows_affected() > 0)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> Result<(), AppError> {
+        let mut tx = self.begin().await?;
+        sqlx::query!(
+            "DELETE FROM two_factor_recovery_codes WHERE user_id = ?",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for code_hash in code_hashes {
+            sqlx::query!(
+                "INSERT INTO two_factor_recovery_codes (user_id, code_hash) VALUES (?, ?)",
+                user_id,
+                code_hash
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn use_recovery_code(
+        &self,
+        user_id: i64,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, AppError> {
+        let used = sqlx::query!(
+            "UPDATE two_factor_recovery_codes SET used_at = ? WHERE user_id = ? AND code_hash = ? AND used_at IS NULL",
+            now,
+            user_id,
+            code_hash
+        )
+        .execute(self)
+        .await?;
+        Ok(used.rows_affected() > 0)
+    }
+
+    async fn record_login_failure(
+        &self,
+        user_id: i64,
+        max_failures: i64,
+        lock_until: i64,
+    ) -> Result<(), AppError> {
+        // Incremented in place so concurrent guesses can't overwrite each other's count
+        sqlx::query!(
+            r#"
+            UPDATE users SET
+                locked_until = CASE WHEN failed_login_attempts + 1 >= ? THEN ? ELSE locked_until END,
+                failed_login_attempts = CASE WHEN failed_login_attempts + 1 >= ? THEN 0
+                    ELSE failed_login_attempts + 1 END
+            WHERE id = ?
+            "#,
+            max_failures,
+            lock_until,
+            max_failures,
+            user_id
+        )
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_arbiter(&self) -> Result<Option<UserSummary>, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT u.id AS "id!: i64", u.role, u.wallet_address, u.wallet_user, u.verified_wallet,
+                p.username AS "username?"
+            FROM users u
+            LEFT JOIN profiles p ON p.user_id = u.id
+            WHERE u.admin = 1
+            ORDER BY u.id
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(self)
+        .await?;
+
+        Ok(row.map(|row| UserSummary {
+            id: row.id,
+            role: row.role,
+            wallet_address: row.wallet_address,
+            wallet_user: row.wallet_user,
+            verified_wallet: row.verified_wallet,
+            username: row.username,
+        }))
+    }
+
+    async fn working_hours(&self, user_id: i64) -> Result<Option<UtcWindow>, AppError> {
+        let row = sqlx::query!(
+            "SELECT work_start_utc, work_minutes FROM profiles WHERE user_id = ?",
+            user_id
+        )
+        .fetch_optional(self)
+        .await?;
+        Ok(row.and_then(|row| {
+            Some(UtcWindow {
+                start: row.work_start_utc?,
+                minutes: row.work_minutes?,
+            })
+        }))
+    }
+}
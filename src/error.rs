@@ -1,57 +1,123 @@
+use crate::middleware::request_id::current_request_id;
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde_json::{json, Map, Value};
 use validator::ValidationErrors;
 
 #[derive(Debug)]
 pub enum AppError {
     Validation(ValidationErrors),
     Database(String),
-    Unauthorized(String),
+    Unauthorized(String), // missing or invalid credentials
+    Forbidden(String),    // authenticated, but not allowed to do this
     Server(String),
     BadRequest(String),
     NotFound(String),
     Conflict(String),
+    TooManyRequests(String, u64), // message, seconds until the client may retry
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Database(_) | AppError::Server(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::TooManyRequests(..) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    // Stable, machine-readable code; clients should branch on this rather than the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Validation(_) => "validation_failed",
+            AppError::Database(_) | AppError::Server(_) => "internal_error",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::TooManyRequests(..) => "rate_limited",
+        }
+    }
+}
+
+// Unique-constraint violations are the client's doing; everything else stays internal
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                AppError::Conflict("Resource already exists".to_string())
+            }
+            _ => AppError::Database(e.to_string()),
+        }
+    }
+}
+
+// Field name -> list of { code, message } for each failed rule
+fn validation_fields(errors: &ValidationErrors) -> Value {
+    let fields: Map<String, Value> = errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let errors = errors
+                .iter()
+                .map(|e| {
+                    json!({
+                        "code": e.code,
+                        "message": e.message.clone().unwrap_or_else(|| e.code.clone()),
+                    })
+                })
+                .collect();
+            (field.to_string(), Value::Array(errors))
+        })
+        .collect();
+    Value::Object(fields)
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, body) = match self {
-            AppError::Validation(errors) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": errors.to_string() })),
-            ),
-            AppError::Database(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": msg })),
-            ),
-            AppError::Unauthorized(msg) => (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({ "error": msg })),
-            ),
-            AppError::Server(msg) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": msg })),
-            ),
-            AppError::BadRequest(msg) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": "BadRequest", "message": msg })),
-            ),
-                AppError::NotFound(msg) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"message": msg })),
-            ),
-
-             AppError::Conflict(msg) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"message": msg })),
-            ),
+        let status = self.status();
+        let request_id = current_request_id();
+        let mut retry_after_header = None;
+
+        let mut error = json!({ "code": self.code(), "request_id": request_id });
+        let message = match self {
+            AppError::Database(msg) | AppError::Server(msg) => {
+                // Internal details stay in the logs; the request id ties them to the response
+                eprintln!("[{}] {}: {}", request_id, status, msg);
+                "Internal server error".to_string()
+            }
+            AppError::Validation(errors) => {
+                error["fields"] = validation_fields(&errors);
+                "Validation failed".to_string()
+            }
+            AppError::TooManyRequests(msg, retry_after) => {
+                error["retry_after"] = json!(retry_after);
+                retry_after_header = Some(retry_after);
+                msg
+            }
+            AppError::Unauthorized(msg)
+            | AppError::Forbidden(msg)
+            | AppError::BadRequest(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg) => msg,
         };
-         
+        error["message"] = json!(message);
 
-        (status, body).into_response()
+        let mut response = (status, Json(json!({ "error": error }))).into_response();
+        if let Some(retry_after) = retry_after_header {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, retry_after.into());
+        }
+        response
     }
-}
\ No newline at end of file
+}
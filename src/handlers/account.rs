@@ -0,0 +1,172 @@
+use crate::config::Config;
+use crate::db::repo::{TokenRepo, UserRepo};
+use crate::error::AppError;
+use crate::mail::{password_reset_mail, verification_mail, Mailer};
+use crate::models::{account::*, auth::AuthUser};
+use crate::utils::*;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+// Create a fresh single-use token for the user and return the raw value to be emailed.
+// Any earlier unused token with the same purpose is discarded so only the latest link works.
+pub async fn issue_email_token(
+    pool: &SqlitePool,
+    config: &Config,
+    user_id: i64,
+    purpose: EmailTokenPurpose,
+) -> Result<String, AppError> {
+    let ttl = match purpose {
+        EmailTokenPurpose::VerifyEmail => config.verify_email_ttl,
+        EmailTokenPurpose::PasswordReset => config.password_reset_ttl,
+    };
+
+    let token = generate_email_token();
+    let now = Utc::now();
+    pool.store_email_token(
+        user_id,
+        purpose,
+        &hash_token(&token),
+        now.timestamp(),
+        (now + ttl).timestamp(),
+    )
+    .await?;
+
+    Ok(token)
+}
+
+// Mark a token as used and return the user it belongs to
+async fn consume_email_token(
+    pool: &SqlitePool,
+    token: &str,
+    purpose: EmailTokenPurpose,
+) -> Result<i64, AppError> {
+    pool.consume_email_token(&hash_token(token), purpose, Utc::now().timestamp())
+        .await?
+        // Edge case: Unknown, already used or expired token
+        .ok_or(AppError::BadRequest("Invalid or expired token".to_string()))
+}
+
+// Issue a verification token and email it; used by signup and the resend endpoint
+pub async fn send_verification_email(
+    pool: &SqlitePool,
+    config: &Config,
+    mailer: &Mailer,
+    user_id: i64,
+    email: &str,
+) -> Result<(), AppError> {
+    let token = issue_email_token(pool, config, user_id, EmailTokenPurpose::VerifyEmail).await?;
+    mailer
+        .send(verification_mail(
+            email,
+            &config.frontend_url,
+            &token,
+            config.verify_email_ttl,
+        ))
+        .await
+}
+
+pub async fn resend_verification_email(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    State(mailer): State<Mailer>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = pool
+        .account(auth_user.id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
+
+    if user.email_verified {
+        return Err(AppError::Conflict("Email already verified".to_string()));
+    }
+
+    let email = user
+        .email
+        .ok_or(AppError::BadRequest("No email on this account".to_string()))?;
+
+    send_verification_email(&pool, &config, &mailer, auth_user.id, &email).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountMessageResponse {
+            message: "Verification email sent".to_string(),
+        }),
+    ))
+}
+
+pub async fn verify_email(
+    State(pool): State<SqlitePool>,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let user_id =
+        consume_email_token(&pool, &payload.token, EmailTokenPurpose::VerifyEmail).await?;
+
+    pool.set_email_verified(user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountMessageResponse {
+            message: "Email verified".to_string(),
+        }),
+    ))
+}
+
+pub async fn forgot_password(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    State(mailer): State<Mailer>,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    // Only email/password accounts can reset a password
+    if let Some(user_id) = pool.password_user_id(&payload.email).await? {
+        let token =
+            issue_email_token(&pool, &config, user_id, EmailTokenPurpose::PasswordReset).await?;
+        mailer
+            .send(password_reset_mail(
+                &payload.email,
+                &config.frontend_url,
+                &token,
+                config.password_reset_ttl,
+            ))
+            .await?;
+    }
+
+    // Same response whether or not the account exists, so emails can't be enumerated
+    Ok((
+        StatusCode::OK,
+        Json(AccountMessageResponse {
+            message: "If an account exists for this email, a reset link has been sent".to_string(),
+        }),
+    ))
+}
+
+pub async fn reset_password(
+    State(pool): State<SqlitePool>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let user_id =
+        consume_email_token(&pool, &payload.token, EmailTokenPurpose::PasswordReset).await?;
+
+    let hashed_password = hash_password(&payload.password)
+        .map_err(|_| AppError::Server("Failed to hash password".to_string()))?;
+    // Bumping password_changed_at makes auth_middleware reject every JWT issued before now,
+    // and every outstanding verification/reset link for the account is invalidated
+    pool.reset_password(user_id, &hashed_password, Utc::now().timestamp())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(AccountMessageResponse {
+            message: "Password has been reset".to_string(),
+        }),
+    ))
+}
@@ -1,24 +1,38 @@
-use crate::config::jwt_secret;
+use crate::config::Config;
+use crate::db::repo::{NonceRecord, TokenRepo, UserRepo};
 use crate::error::AppError;
-use crate::models::{auth::*, jwt::*};
+use crate::handlers::account::send_verification_email;
+use crate::mail::Mailer;
+use crate::models::{auth::*, two_factor::TwoFactorPendingResponse};
 use crate::utils::*;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, DecodingKey, TokenData, Validation};
+use crate::wallet::{WalletChain, WalletChallenge};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::Utc;
 use serde_json::json;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use validator::Validate;
 
+// Consecutive wrong passwords before an account is locked, and for how long
+const MAX_FAILED_LOGINS: i64 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+
 pub async fn signup(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    State(mailer): State<Mailer>,
     Json(payload): Json<SignupRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate the payload structure and constraints
     payload.validate().map_err(AppError::Validation)?;
 
     // Validate role against allowed values
-    let valid_roles = vec!["client", "freelancer"];
+    let valid_roles = ["client", "freelancer"];
     if !valid_roles.contains(&payload.role.as_str()) {
         // Edge case: Invalid role provided
         return Err(AppError::Validation(validator::ValidationErrors::new()));
@@ -52,24 +66,23 @@ pub async fn signup(
     let hashed_password = hash_password(&payload.password)
         .map_err(|_| AppError::Server("Failed to hash password".to_string()))?;
 
-    // Insert user into database with verified_wallet set to false
-    let result = sqlx::query!(
-        "INSERT INTO users (email, password, role, verified_wallet) VALUES (?, ?, ?, ?)",
-        payload.email,
-        hashed_password,
-        payload.role,
-        false
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| {
-        // Edge case: Database constraint violation (e.g., duplicate email or wallet_address)
-        AppError::Database(e.to_string())
-    })?;
+    // Insert user into database with verified_wallet set to false.
+    // Edge case: Duplicate email surfaces as a 409 via the unique-violation mapping
+    let user_id = pool
+        .create_email_user(&payload.email, &hashed_password, &payload.role)
+        .await?;
+
+    // Send the email verification link; a delivery failure shouldn't undo the signup,
+    // the user can request a new link from /email/resend-verification
+    if let Err(e) =
+        send_verification_email(&pool, &config, &mailer, user_id, &payload.email).await
+    {
+        eprintln!("Failed to send verification email: {:?}", e);
+    }
 
     let role = payload.role.clone();
     // Generate JWT for authenticated user
-    let token = generate_jwt(result.last_insert_rowid(), payload.role)
+    let token = generate_jwt(&config, user_id, payload.role)
         .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
 
     // Return success response with user_id
@@ -78,8 +91,8 @@ pub async fn signup(
         Json(SignupResponse {
             message: "Logged in".to_string(),
             token,
-            user_id: result.last_insert_rowid(),
-            role: role,
+            user_id,
+            role,
             wallet_user: false,
             verified_wallet: false,
         }),
@@ -88,43 +101,28 @@ pub async fn signup(
 
 pub async fn wallet_signup(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Json(payload): Json<WalletSignupRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate input
     payload.validate().map_err(AppError::Validation)?;
+    let wallet_address = payload.chain.normalize_address(&payload.wallet_address)?;
 
     // Check if wallet already exists
-    let existing_user = sqlx::query!(
-        "SELECT id FROM users WHERE wallet_address = ?",
-        payload.wallet_address
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if existing_user.is_some() {
-        return Err(AppError::BadRequest(
+    if pool.user_by_wallet(&wallet_address).await?.is_some() {
+        return Err(AppError::Conflict(
             "Wallet already registered".to_string(),
         ));
     }
 
-    // Use placeholder email
-
     // Insert new wallet-based user
-    let result = sqlx::query!(
-        "INSERT INTO users ( wallet_address, role,wallet_user ,verified_wallet) VALUES (?,?,?, ?)",
-        payload.wallet_address,
-        payload.role,
-        true,
-        false,
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let user_id = pool
+        .create_wallet_user(&wallet_address, payload.chain, &payload.role, None, false)
+        .await?;
 
     let role = payload.role.clone();
     // Generate JWT
-    let token = generate_jwt(result.last_insert_rowid(), payload.role)
+    let token = generate_jwt(&config, user_id, payload.role)
         .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
 
     // Respond with token
@@ -132,9 +130,9 @@ pub async fn wallet_signup(
         StatusCode::OK,
         Json(WalletSignupResponse {
             message: "Wallet login successful".to_string(),
-            token: token,
-            user_id: result.last_insert_rowid(),
-            role: role,
+            token,
+            user_id,
+            role,
             wallet_user: true,
             verified_wallet: false,
         }),
@@ -148,36 +146,17 @@ pub async fn wallet_connect(
 ) -> Result<impl IntoResponse, AppError> {
     // Validate input
     payload.validate().map_err(AppError::Validation)?;
+    let wallet_address = payload.chain.normalize_address(&payload.wallet_address)?;
 
     // Check if wallet already exists
-    let existing_user = sqlx::query!(
-        "SELECT id FROM users WHERE wallet_address = ?",
-        payload.wallet_address
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if existing_user.is_some() {
-        return Err(AppError::BadRequest(
+    if pool.user_by_wallet(&wallet_address).await?.is_some() {
+        return Err(AppError::Conflict(
             "Wallet already registered".to_string(),
         ));
     }
 
-    // Use placeholder email
-
-    // Insert new wallet-based user
-    sqlx::query!(
-        "UPDATE users
-     SET wallet_address = ?, wallet_user = ?
-     WHERE id = ?",
-        payload.wallet_address,
-        true,
-        auth_user.id
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    pool.connect_wallet(auth_user.id, &wallet_address, payload.chain)
+        .await?;
 
     // Respond with token
     Ok((
@@ -191,8 +170,9 @@ pub async fn wallet_connect(
 
 pub async fn login(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Json(payload): Json<LoginRequest>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     // Extract password from payload
     let password = payload.password.as_ref().ok_or(AppError::Validation({
         // Edge case: Password field is missing
@@ -205,37 +185,49 @@ pub async fn login(
     }))?;
 
     // Fetch user by email
-    let user = sqlx::query!(
-        r#"
-        SELECT id AS "id!: i64", password, role, wallet_user, verified_wallet 
-        FROM users
-        WHERE email = ?
-        "#,
-        payload.email
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
+    let email = payload
+        .email
+        .as_deref()
+        .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
+    let user = pool
+        .login_record(email)
+        .await?
+        .ok_or(AppError::Unauthorized("Invalid credentials".to_string()))?;
 
     // Verify password
-    let user_password = user.password.as_ref().ok_or(AppError::Server(
-        // Edge case: User exists but password is null in database
-        "User password not found".to_string(),
+    let user_password = user.password.as_ref().ok_or(AppError::Unauthorized(
+        // Edge case: Wallet-only account has no password to log in with
+        "Invalid credentials".to_string(),
     ))?;
 
+    // Refuse while locked, before touching the password, so guesses during a lockout are free to ignore
+    let now = Utc::now().timestamp();
+    ensure_not_locked(user.locked_until, now)?;
+
     if !verify_password(password, user_password)
         .map_err(|_| AppError::Server("Password verification failed".to_string()))?
     {
+        record_failed_login(&pool, user.id, now).await?;
         // Edge case: Password does not match
         return Err(AppError::Unauthorized("Invalid credentials".to_string()));
     }
 
+    // Accounts with 2FA only get a short-lived pending token, exchanged at /login/2fa.
+    // Their failure count carries over, so wrong codes there still lead to a lockout
+    if user.totp_enabled {
+        return two_factor_pending(&config, user.id, user.role);
+    }
+
+    if user.failed_login_attempts > 0 || user.locked_until.is_some() {
+        pool.set_login_failures(user.id, 0, None).await?;
+    }
+
     let role = user.role.clone();
     let verified_wallet = user.verified_wallet;
     let wallet_user = user.wallet_user;
+    let email_verified = user.email_verified;
     // Generate JWT for authenticated user
-    let token = generate_jwt(user.id, user.role)
+    let token = generate_jwt(&config, user.id, user.role)
         .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
 
     // Return success response with token
@@ -248,33 +240,103 @@ pub async fn login(
             role,
             wallet_user,
             verified_wallet,
+            email_verified,
         }),
-    ))
+    )
+        .into_response())
+}
+
+// The response for a correct first factor on an account with 2FA: a short-lived
+// pending token instead of a session
+fn two_factor_pending(config: &Config, user_id: i64, role: String) -> Result<Response, AppError> {
+    let pending_token = generate_two_factor_pending_jwt(config, user_id, role)
+        .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFactorPendingResponse {
+            message: "Two-factor code required".to_string(),
+            two_factor_required: true,
+            pending_token,
+        }),
+    )
+        .into_response())
+}
+
+pub(crate) fn ensure_not_locked(locked_until: Option<i64>, now: i64) -> Result<(), AppError> {
+    if let Some(locked_until) = locked_until.filter(|until| *until > now) {
+        // Edge case: Account is temporarily locked after repeated failures
+        return Err(AppError::TooManyRequests(
+            "Account temporarily locked due to repeated failed logins".to_string(),
+            (locked_until - now) as u64,
+        ));
+    }
+    Ok(())
+}
+
+// Count a wrong password or second factor and lock the account once
+// MAX_FAILED_LOGINS is reached
+pub(crate) async fn record_failed_login(
+    pool: &SqlitePool,
+    user_id: i64,
+    now: i64,
+) -> Result<(), AppError> {
+    pool.record_login_failure(user_id, MAX_FAILED_LOGINS, now + LOCKOUT_MINUTES * 60)
+        .await
 }
 
 pub async fn wallet_login(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Json(payload): Json<WalletLoginRequest>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     // Validate input
     payload.validate().map_err(AppError::Validation)?;
 
+    let wallet_address = payload.chain.normalize_address(&payload.wallet_address)?;
+
     // Look up user by wallet address
-    let user = sqlx::query!(
-        "SELECT id AS 'id!: i64', role, wallet_user, verified_wallet FROM users WHERE wallet_address = ?",
-        payload.wallet_address
+    let user = pool
+        .user_by_wallet(&wallet_address)
+        .await?
+        .ok_or(AppError::Unauthorized("Wallet not registered".to_string()))?;
+    let totp_state = pool
+        .totp_state(user.id)
+        .await?
+        .ok_or(AppError::Unauthorized("Wallet not registered".to_string()))?;
+
+    // Accounts locked by failed passwords or second factors can't use their wallet meanwhile
+    ensure_not_locked(totp_state.locked_until, Utc::now().timestamp())?;
+
+    // The wallet has to prove ownership by signing the nonce it was issued
+    check_wallet_signature(
+        &pool,
+        &config,
+        payload.chain,
+        &wallet_address,
+        &payload.nonce,
+        &payload.signature,
     )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .ok_or(AppError::Unauthorized("Wallet not registered".to_string()))?;
+    .await?;
+
+    // Edge case: The same signed nonce is replayed concurrently
+    if !pool.delete_nonce(&wallet_address, &payload.nonce).await? {
+        return Err(AppError::Unauthorized(
+            "Invalid or expired nonce".to_string(),
+        ));
+    }
+
+    // The wallet stands in for the password only; 2FA accounts continue at /login/2fa
+    if totp_state.enabled {
+        return two_factor_pending(&config, user.id, user.role);
+    }
 
     let role = user.role.clone();
     let verified_wallet = user.verified_wallet;
     let wallet_user = user.wallet_user;
 
     // Generate JWT
-    let token = generate_jwt(user.id, user.role)
+    let token = generate_jwt(&config, user.id, user.role)
         .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
 
     // Respond with token
@@ -288,35 +350,25 @@ pub async fn wallet_login(
             wallet_user,
             verified_wallet,
         }),
-    ))
+    )
+        .into_response())
 }
 
 pub async fn logout(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Extension(_auth_user): Extension<Arc<AuthUser>>,
     Extension(token): Extension<String>, //raw jwt token from middleware
 ) -> Result<impl IntoResponse, AppError> {
     //Decode token to extract expiration time
-    let secret = jwt_secret();
-    let token_data: TokenData<Claims> = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::new(jsonwebtoken::Algorithm::HS256),
-    )
-    .map_err(|e| AppError::Unauthorized(e.to_string()))?;
+    let claims =
+        decode_jwt(&config, &token).map_err(|e| AppError::Unauthorized(e.to_string()))?;
 
     // Insert token into blacklisted_tokens
     // Note: Blacklisting is required because we cannot modify the token's exp
     // (which would create a new token) or force the client to stop using the original.
     // Storing in blacklisted_tokens ensures the token is rejected until its exp.
-    sqlx::query!(
-        "INSERT INTO blacklisted_tokens (token, expires_at) VALUES (?, ?)",
-        token,
-        token_data.claims.exp
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    pool.blacklist_token(&token, claims.exp).await?;
 
     //return success response
     Ok((
@@ -330,20 +382,10 @@ pub async fn profile_basic(
     Extension(auth_user): Extension<Arc<AuthUser>>,
 ) -> Result<impl IntoResponse, AppError> {
     // Fetch user email by ID
-    let user = sqlx::query!(
-        r#"
-        SELECT email
-        FROM users
-        WHERE id = ?
-        "#,
-        auth_user.id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| {
+    let user = pool.account(auth_user.id).await?.ok_or(
         // Edge case: User not found (inconsistent auth_user data)
-        AppError::Database(e.to_string())
-    })?;
+        AppError::NotFound("User not found".to_string()),
+    )?;
 
     // Return user profile
     Ok((
@@ -351,6 +393,7 @@ pub async fn profile_basic(
         Json(ProfileResponse {
             email: user.email.expect("Not provided"),
             wallet_address: auth_user.wallet_address.clone(),
+            wallet_chain: user.wallet_chain,
             role: auth_user.role.clone(),
             wallet_user: false,
             verified_wallet: auth_user.verified_wallet,
@@ -363,25 +406,15 @@ pub async fn profile_verified(
     Extension(auth_user): Extension<Arc<AuthUser>>,
 ) -> Result<impl IntoResponse, AppError> {
     // Fetch user email by ID
-    let user = sqlx::query!(
-        r#"
-        SELECT email
-        FROM users
-        WHERE id = ?
-        "#,
-        auth_user.id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| {
+    let user = pool.account(auth_user.id).await?.ok_or(
         // Edge case: User not found (inconsistent auth_user data)
-        AppError::Database(e.to_string())
-    })?;
+        AppError::NotFound("User not found".to_string()),
+    )?;
 
     // Restrict access to wallet_user or client roles
     if auth_user.role != "client" {
         // Edge case: User attempts access with unauthorized role
-        return Err(AppError::Unauthorized(
+        return Err(AppError::Forbidden(
             "Wallet user or client role required".to_string(),
         ));
     }
@@ -392,6 +425,7 @@ pub async fn profile_verified(
         Json(ProfileResponse {
             email: user.email.expect("Not provided"),
             wallet_address: auth_user.wallet_address.clone(),
+            wallet_chain: user.wallet_chain,
             role: auth_user.role.clone(),
             wallet_user: true,
             verified_wallet: auth_user.verified_wallet,
@@ -401,163 +435,107 @@ pub async fn profile_verified(
 
 // Helper function to check if wallet is verified
 async fn check_wallet_verified(pool: &SqlitePool, wallet_address: &str) -> Result<bool, AppError> {
-    // Query verified_wallet status for wallet_address
-    let user = sqlx::query!(
-        "SELECT verified_wallet FROM users WHERE wallet_address = ?",
-        wallet_address
-    )
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
     // Return false if user not found, otherwise return verified_wallet status
-    Ok(user.map_or(false, |u| u.verified_wallet))
+    Ok(pool
+        .user_by_wallet(wallet_address)
+        .await?
+        .is_some_and(|u| u.verified_wallet))
 }
 
 pub async fn request_nonce(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Json(payload): Json<NonceRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate payload structure
     payload.validate().map_err(AppError::Validation)?;
+    let wallet_address = payload.chain.normalize_address(&payload.wallet_address)?;
 
-    // Prevent nonce generation for verified wallets
-    if check_wallet_verified(&pool, &payload.wallet_address).await? {
-        // Edge case: Wallet already verified
-        return Err(AppError::BadRequest("Wallet already verified".to_string()));
-    }
-
-    // Generate nonce and timestamps
+    // Generate nonce and timestamps. Verified wallets get one too, to sign in
+    // through /wallet/login
     let nonce = generate_nonce();
     let created_at = Utc::now().to_rfc3339();
     let expires_at = Utc::now()
-        .checked_add_signed(Duration::minutes(15))
+        .checked_add_signed(config.nonce_ttl)
         .expect("valid timestamp")
         .to_rfc3339();
 
-    // Store nonce in database
-    sqlx::query!(
-        "INSERT OR REPLACE INTO nonces (wallet_address, nonce, created_at, expires_at) VALUES (?,?,?,?)",
-        payload.wallet_address,
+    // Store nonce in database, replacing any earlier one for the wallet
+    let record = NonceRecord {
         nonce,
         created_at,
-        expires_at
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| {
-        // Edge case: Database error (e.g., constraint violation)
-        AppError::Database(e.to_string())
-    })?;
-
-    // Return nonce
-    Ok((StatusCode::OK, Json(NonceResponse { nonce })))
+        expires_at,
+    };
+    pool.store_nonce(&wallet_address, payload.chain, &record)
+        .await?;
+
+    let message = WalletChallenge {
+        chain: payload.chain,
+        wallet_address: &wallet_address,
+        nonce: &record.nonce,
+        issued_at: &record.created_at,
+        expires_at: &record.expires_at,
+    }
+    .message(&config);
+
+    // Return nonce with the message to sign
+    Ok((
+        StatusCode::OK,
+        Json(NonceResponse {
+            nonce: record.nonce,
+            message,
+        }),
+    ))
 }
 
 pub async fn verify(
     State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     Json(payload): Json<VerifyRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate payload structure
     payload.validate().map_err(AppError::Validation)?;
 
+    let wallet_address = payload.chain.normalize_address(&payload.wallet_address)?;
+
     // Prevent verification for already verified wallets
-    if check_wallet_verified(&pool, &payload.wallet_address).await? {
+    if check_wallet_verified(&pool, &wallet_address).await? {
         // Edge case: Wallet already verified
-        return Err(AppError::BadRequest("Wallet already verified".to_string()));
+        return Err(AppError::Conflict("Wallet already verified".to_string()));
     }
 
-    // Fetch and validate nonce
-    let nonce_record = sqlx::query!(
-        "SELECT nonce, expires_at FROM nonces WHERE wallet_address = ? AND nonce = ?",
-        payload.wallet_address,
-        payload.nonce
+    check_wallet_signature(
+        &pool,
+        &config,
+        payload.chain,
+        &wallet_address,
+        &payload.nonce,
+        &payload.signature,
     )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?
-    .ok_or(AppError::Unauthorized(
-        "Invalid or expired nonce".to_string(),
-    ))?;
-
-    // Parse and check nonce expiration
-    let expires_at =
-        chrono::DateTime::parse_from_rfc3339(&nonce_record.expires_at).map_err(|_| {
-            // Edge case: Invalid expires_at format in database
-            AppError::Server("Invalid expiration time".to_string())
-        })?;
-
-    if Utc::now() > expires_at.with_timezone(&Utc) {
-        // Edge case: Nonce has expired
-        return Err(AppError::Unauthorized("Nonce Expired".to_string()));
-    }
-
-    // Verify signature
-
-    let message = create_solana_sign_message(&payload.nonce, &payload.wallet_address);
-    if !verify_solana_signature(&message, &payload.signature, &payload.wallet_address)? {
-        return Err(AppError::Unauthorized("Invalid signature".to_string()));
-    }
+    .await?;
 
     // Fetch or create user
-    let user = sqlx::query!(
-        r#"
-        SELECT id AS "id!: i64", role
-        FROM users
-        WHERE wallet_address = ?
-        "#,
-        payload.wallet_address
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let (user_id, role) = match user {
+    let (user_id, role) = match pool.user_by_wallet(&wallet_address).await? {
         Some(user) => {
-            // Update existing user
-            sqlx::query!(
-                "UPDATE users SET verified_wallet = ? WHERE id = ?",
-                true,
-                user.id
-            )
-            .execute(&pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+            pool.set_wallet_verified(user.id).await?;
             (user.id, user.role)
         }
         None => {
             // Auto-register user if not exists
-            let email = format!("{}@freelancia.wallet", payload.wallet_address);
-            let result = sqlx::query!(
-                "INSERT INTO users (email, wallet_address, role, verified_wallet) VALUES (?,?,?,?)",
-                email,
-                payload.wallet_address,
-                "wallet_user",
-                true
-            )
-            .execute(&pool)
-            .await
-            .map_err(|e| {
-                // Edge case: Database constraint violation (e.g., duplicate wallet_address)
-                AppError::Database(e.to_string())
-            })?;
-
-            (result.last_insert_rowid(), "freelancer".to_string())
+            // Auto-registered wallets start as freelancers (role is limited to freelancer/client)
+            let email = format!("{}@freelancia.wallet", wallet_address);
+            let user_id = pool
+                .create_wallet_user(&wallet_address, payload.chain, "freelancer", Some(&email), true)
+                .await?;
+            (user_id, "freelancer".to_string())
         }
     };
 
     // Delete used nonce
-    sqlx::query!(
-        "DELETE FROM nonces WHERE wallet_address = ? AND nonce = ?",
-        payload.wallet_address,
-        payload.nonce
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    pool.delete_nonce(&wallet_address, &payload.nonce).await?;
 
     // Generate JWT
-    let token = generate_jwt(user_id, role)
+    let token = generate_jwt(&config, user_id, role)
         .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
 
     // Return success response
@@ -570,10 +548,52 @@ pub async fn verify(
     ))
 }
 
-pub async fn cleanup_blacklisted_tokens(pool: &SqlitePool) -> Result<(), sqlx::Error> {
-    let now = chrono::Utc::now().timestamp();
-    sqlx::query!("DELETE FROM blacklisted_tokens WHERE expires_at < ?", now)
-        .execute(pool)
-        .await?;
+// Check a signature over the message issued with a stored, unexpired nonce.
+// The caller deletes the nonce once it has acted on the signature
+async fn check_wallet_signature(
+    pool: &SqlitePool,
+    config: &Config,
+    chain: WalletChain,
+    wallet_address: &str,
+    nonce: &str,
+    signature: &str,
+) -> Result<(), AppError> {
+    // Fetch and validate nonce
+    let nonce_record = pool.find_nonce(wallet_address, chain, nonce).await?.ok_or(
+        // Edge case: Unknown nonce, or one already used
+        AppError::Unauthorized("Invalid or expired nonce".to_string()),
+    )?;
+
+    // Parse and check nonce expiration
+    let expires_at =
+        chrono::DateTime::parse_from_rfc3339(&nonce_record.expires_at).map_err(|_| {
+            // Edge case: Invalid expires_at format in database
+            AppError::Server("Invalid expiration time".to_string())
+        })?;
+
+    if Utc::now() > expires_at.with_timezone(&Utc) {
+        // Edge case: Nonce has expired
+        return Err(AppError::Unauthorized("Nonce Expired".to_string()));
+    }
+
+    // Verify signature against the message issued with this nonce
+    let challenge = WalletChallenge {
+        chain,
+        wallet_address,
+        nonce: &nonce_record.nonce,
+        issued_at: &nonce_record.created_at,
+        expires_at: &nonce_record.expires_at,
+    };
+    if !challenge.verify(config, signature)? {
+        return Err(AppError::Unauthorized("Invalid signature".to_string()));
+    }
     Ok(())
 }
+
+pub async fn cleanup_blacklisted_tokens(pool: &SqlitePool) -> Result<(), AppError> {
+    pool.purge_blacklisted_tokens(Utc::now().timestamp()).await
+}
+
+pub async fn cleanup_expired_nonces(pool: &SqlitePool) -> Result<(), AppError> {
+    pool.purge_expired_nonces(&Utc::now().to_rfc3339()).await
+}
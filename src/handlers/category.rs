@@ -0,0 +1,136 @@
+use crate::cache::Cache;
+use crate::db::repo::CategoryRepo;
+use crate::error::AppError;
+use crate::models::category::*;
+use crate::models::job::Categories;
+use axum::{
+    extract::{Json, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use validator::Validate;
+
+const CATEGORIES_CACHE_KEY: &str = "categories";
+// Job counts may lag by this much; admin edits clear the cache right away
+const CATEGORIES_TTL: Duration = Duration::from_secs(60);
+
+// Public, so browsers and CDNs may keep it for as long as this cache does
+pub async fn get_categories(
+    State(pool): State<SqlitePool>,
+    State(cache): State<Cache>,
+) -> Result<impl IntoResponse, AppError> {
+    let categories = match cache.get::<Categories>(CATEGORIES_CACHE_KEY) {
+        Some(categories) => categories,
+        None => {
+            let categories = Categories {
+                categories: category_tree(pool.list_categories().await?),
+            };
+            cache.insert(CATEGORIES_CACHE_KEY, &categories, CATEGORIES_TTL);
+            categories
+        }
+    };
+
+    Ok((
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=60")],
+        Json(categories),
+    ))
+}
+
+pub async fn create_category(
+    State(pool): State<SqlitePool>,
+    State(cache): State<Cache>,
+    Json(payload): Json<CreateCategory>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    // Only one level of nesting: a subcategory's parent must be top-level
+    if let Some(parent_id) = payload.parent_id {
+        let parent = pool
+            .find_category(parent_id)
+            .await?
+            .ok_or(AppError::BadRequest("Parent category not found".into()))?;
+        if parent.parent_id.is_some() {
+            return Err(AppError::BadRequest(
+                "Subcategories can't have subcategories".into(),
+            ));
+        }
+    }
+
+    let slug = payload
+        .slug
+        .clone()
+        .unwrap_or_else(|| slugify(&payload.name));
+    if slug.is_empty() {
+        return Err(AppError::BadRequest(
+            "Provide a slug for this category name".into(),
+        ));
+    }
+    let category_id = match pool.create_category(&payload, &slug).await {
+        Err(AppError::Conflict(_)) => {
+            return Err(AppError::Conflict(
+                "A category with this name or slug already exists".into(),
+            ))
+        }
+        result => result?,
+    };
+    cache.invalidate_prefix(CATEGORIES_CACHE_KEY);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "category": pool.find_category(category_id).await? })),
+    ))
+}
+
+pub async fn update_category(
+    State(pool): State<SqlitePool>,
+    State(cache): State<Cache>,
+    Path(category_id): Path<i64>,
+    Json(payload): Json<UpdateCategory>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    pool.find_category(category_id)
+        .await?
+        .ok_or(AppError::NotFound("Category not found".into()))?;
+
+    match pool.update_category(category_id, &payload).await {
+        Err(AppError::Conflict(_)) => {
+            return Err(AppError::Conflict(
+                "A category with this name or slug already exists".into(),
+            ))
+        }
+        result => result?,
+    }
+    cache.invalidate_prefix(CATEGORIES_CACHE_KEY);
+
+    Ok(Json(
+        json!({ "category": pool.find_category(category_id).await? }),
+    ))
+}
+
+// Categories still in use stay; move their jobs and subcategories first
+pub async fn delete_category(
+    State(pool): State<SqlitePool>,
+    State(cache): State<Cache>,
+    Path(category_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    pool.find_category(category_id)
+        .await?
+        .ok_or(AppError::NotFound("Category not found".into()))?;
+
+    let (jobs, subcategories) = pool.category_usage(category_id).await?;
+    if jobs > 0 || subcategories > 0 {
+        return Err(AppError::Conflict(format!(
+            "Category is used by {} jobs and {} subcategories",
+            jobs, subcategories
+        )));
+    }
+
+    pool.delete_category(category_id).await?;
+    cache.invalidate_prefix(CATEGORIES_CACHE_KEY);
+
+    Ok(Json(json!({ "message": "Category deleted" })))
+}
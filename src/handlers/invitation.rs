@@ -0,0 +1,154 @@
+use crate::db::repo::{InvitationRepo, JobRepo, NewNotification, UserRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::invitation::*;
+use crate::notifications::NotificationBroadcaster;
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+// Invite a freelancer to apply; this is also how freelancers get into invite-only jobs
+pub async fn invite_freelancer(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+    Json(payload): Json<CreateInvitation>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".into()));
+    }
+    if pool.job_status(job_id).await?.as_deref() != Some("open") {
+        return Err(AppError::BadRequest(
+            "Job is not open for applications".into(),
+        ));
+    }
+
+    let freelancer = match (payload.freelancer_id, payload.username.as_deref()) {
+        (Some(id), _) => pool.user_summary(id).await?,
+        (None, Some(username)) => pool.user_by_username(username).await?,
+        (None, None) => {
+            return Err(AppError::BadRequest(
+                "Provide a freelancer_id or username to invite".into(),
+            ))
+        }
+    }
+    .filter(|user| user.role == "freelancer")
+    .ok_or(AppError::NotFound("Freelancer not found".into()))?;
+
+    let invitation_id = match pool
+        .create_invitation(job_id, auth_user.id, freelancer.id, payload.message.as_deref())
+        .await
+    {
+        Err(AppError::Conflict(_)) => {
+            return Err(AppError::Conflict(
+                "This freelancer has already been invited to the job".into(),
+            ))
+        }
+        result => result?,
+    };
+    let invitation = pool
+        .find_invitation(invitation_id)
+        .await?
+        .ok_or(AppError::Server("Invitation vanished after insert".into()))?;
+
+    let message = format!(
+        "{} invited you to apply to '{}'.",
+        invitation
+            .client_username
+            .clone()
+            .unwrap_or_else(|| "A client".to_string()),
+        invitation.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(freelancer.id, "invited", message, job_id, auth_user.id),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "invitation": invitation })),
+    ))
+}
+
+pub async fn get_job_invitations(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".into()));
+    }
+
+    Ok(Json(json!({
+        "invitations": pool.invitations_for_job(job_id).await?
+    })))
+}
+
+pub async fn get_my_invitations(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(json!({
+        "invitations": pool.invitations_for_freelancer(auth_user.id).await?
+    })))
+}
+
+pub async fn respond_to_invitation(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(invitation_id): Path<i64>,
+    Json(payload): Json<RespondInvitation>,
+) -> Result<impl IntoResponse, AppError> {
+    let invitation = pool
+        .find_invitation(invitation_id)
+        .await?
+        .filter(|invitation| invitation.freelancer_id == auth_user.id)
+        .ok_or(AppError::NotFound("Invitation not found".into()))?;
+
+    let (status, verb) = if payload.accept {
+        (InvitationStatus::Accepted, "accepted")
+    } else {
+        (InvitationStatus::Declined, "declined")
+    };
+    if !pool.respond_to_invitation(invitation.id, status).await? {
+        return Err(AppError::Conflict(
+            "Invitation has already been answered".into(),
+        ));
+    }
+
+    let message = format!(
+        "{} {} your invitation to '{}'.",
+        invitation
+            .freelancer_username
+            .unwrap_or_else(|| "A freelancer".to_string()),
+        verb,
+        invitation.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                invitation.client_id,
+                "invitation",
+                message,
+                invitation.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    Ok(Json(json!({ "message": format!("Invitation {}", verb) })))
+}
@@ -1,60 +1,45 @@
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::db::pagination::PageQuery;
+use crate::db::repo::{
+    ApplicationDetail, ApplicationRepo, CategoryRepo, InvitationRepo, JobRepo, NewApplication,
+    NewNotification, NotificationRepo, ReviewRepo, SkillRepo, UserRepo,
+};
 use crate::error::AppError;
+use crate::handlers::proposal::expire_job_proposals;
+use crate::handlers::review::review_cutoff;
 use crate::models::auth::AuthUser;
+use crate::models::category::Category;
 use crate::models::job::*;
+use crate::models::proposal::ProposalStatus;
+use crate::notifications::NotificationBroadcaster;
 use axum::{
     extract::{Json, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Extension,
 };
 use chrono::Utc;
 use serde_json::json;
 use sqlx::SqlitePool;
 use std::sync::Arc;
+use std::time::Duration;
 use validator::Validate;
 
 pub async fn create_job(
     State(pool): State<SqlitePool>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
-    Json(payload): Json<JobRequest>,
+    Json(mut payload): Json<JobRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate the payload structure and constraints (e.g., required fields, string lengths)
     payload.validate().map_err(AppError::Validation)?;
+    require_hour_cap(payload.job_type, payload.weekly_hour_cap)?;
+    let category = known_category(&pool, &payload.category).await?;
+    payload.category = category.name;
+    payload.place.normalize();
 
-    // Record the current timestamp for when the job is posted
-    let posted_at = Utc::now().to_rfc3339();
-
-    // Insert job into the jobs table with provided details and authenticated user's ID as client_id
-    let result = sqlx::query!(
-        r#"
-        INSERT INTO jobs (
-        title, description, skills, budget, location, job_type, job_ipfs_hash,
-        posted_at, deadline, client_id, category, status
-    )
-    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-    "#,
-        payload.title,
-        payload.description,
-        payload.skills,
-        payload.budget,
-        payload.location,
-        payload.job_type,
-        payload.job_ipfs_hash,
-        posted_at,
-        payload.deadline,
-        auth_user.id,
-        payload.category,
-        payload.status
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| {
-        // Edge case: Database constraint violation (e.g., invalid foreign key, null in non-nullable field)
-        AppError::Database(e.to_string())
-    })?;
-
-    // Retrieve the ID of the newly inserted job
-    let job_id = result.last_insert_rowid();
+    let job_id = pool.create_job(auth_user.id, &payload, category.id).await?;
+    pool.set_job_skills(job_id, &payload.skills).await?;
 
     // Return success response with job_id
     Ok((
@@ -63,6 +48,258 @@ pub async fn create_job(
     ))
 }
 
+// Jobs are filed under a category from the admin-managed list, by name or slug
+async fn known_category(pool: &SqlitePool, name_or_slug: &str) -> Result<Category, AppError> {
+    pool.resolve_category(name_or_slug)
+        .await?
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown category '{}'", name_or_slug)))
+}
+
+// Hourly jobs bill against a weekly cap, so one has to be set
+fn require_hour_cap(job_type: JobType, weekly_hour_cap: Option<i64>) -> Result<(), AppError> {
+    if job_type == JobType::Hourly && weekly_hour_cap.is_none() {
+        return Err(AppError::BadRequest(
+            "Hourly jobs need a weekly hour cap".into(),
+        ));
+    }
+    Ok(())
+}
+
+const PUBLIC_JOBS_CACHE_PREFIX: &str = "public_jobs:";
+// New and edited jobs reach the public listing within this long
+const PUBLIC_JOBS_TTL: Duration = Duration::from_secs(60);
+const PUBLIC_JOBS_CACHE_CONTROL: &str = "public, max-age=60";
+
+// Changes to these fields are worth telling applicants about
+const MATERIAL_FIELDS: [&str; 8] = [
+    "description",
+    "skills",
+    "budget",
+    "remote",
+    "place",
+    "job_type",
+    "weekly_hour_cap",
+    "deadline",
+];
+
+// The client's job that nobody has been hired for yet
+async fn unhired_job(
+    pool: &SqlitePool,
+    job_id: i64,
+    client_id: i64,
+) -> Result<JobResponse, AppError> {
+    let job = pool
+        .job_detail(job_id)
+        .await?
+        .filter(|job| job.client_id == client_id)
+        .ok_or(AppError::NotFound("Job not found".into()))?;
+
+    if !pool.approved_freelancers(job_id).await?.is_empty() {
+        return Err(AppError::Conflict(
+            "A freelancer has already been hired for this job".into(),
+        ));
+    }
+    Ok(job)
+}
+
+pub async fn update_job(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+    Json(mut payload): Json<UpdateJob>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    if let Some(place) = payload.place.as_mut() {
+        place.normalize();
+    }
+    let job = unhired_job(&pool, job_id, auth_user.id).await?;
+    let category_id = match &payload.category {
+        Some(name_or_slug) => {
+            let category = known_category(&pool, name_or_slug).await?;
+            payload.category = Some(category.name);
+            Some(category.id)
+        }
+        None => None,
+    };
+
+    // The cap only applies to hourly jobs and is dropped when switching to fixed-price
+    let job_type = payload.job_type.unwrap_or(job.job_type);
+    let weekly_hour_cap = match job_type {
+        JobType::Hourly => payload.weekly_hour_cap.or(job.weekly_hour_cap),
+        JobType::Fixed => None,
+    };
+    require_hour_cap(job_type, weekly_hour_cap)?;
+
+    // Record only the fields whose value actually changes
+    let mut changes = serde_json::Map::new();
+    let mut diff = |field: &str, from: serde_json::Value, to: Option<serde_json::Value>| {
+        if let Some(to) = to.filter(|to| *to != from) {
+            changes.insert(field.to_string(), json!({ "from": from, "to": to }));
+        }
+    };
+    diff(
+        "title",
+        json!(job.title),
+        payload.title.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "description",
+        json!(job.description),
+        payload.description.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "skills",
+        json!(job.skills),
+        payload.skills.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "budget",
+        json!(job.budget),
+        payload.budget.map(|v| json!(v)),
+    );
+    diff(
+        "location",
+        json!(job.location),
+        payload.location.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "remote",
+        json!(job.remote),
+        payload.remote.map(|v| json!(v)),
+    );
+    diff(
+        "place",
+        json!(job.place),
+        payload.place.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "job_type",
+        json!(job.job_type),
+        payload.job_type.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "weekly_hour_cap",
+        json!(job.weekly_hour_cap),
+        Some(json!(weekly_hour_cap)),
+    );
+    diff(
+        "job_ipfs_hash",
+        json!(job.job_ipfs_hash),
+        payload.job_ipfs_hash.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "deadline",
+        json!(job.deadline),
+        payload.deadline.as_ref().map(|v| json!(v)),
+    );
+    diff(
+        "category",
+        json!(job.category),
+        payload.category.as_ref().map(|v| json!(v)),
+    );
+
+    if changes.is_empty() {
+        return Err(AppError::BadRequest("No changes to apply".into()));
+    }
+    let material: Vec<&str> = MATERIAL_FIELDS
+        .into_iter()
+        .filter(|field| changes.contains_key(*field))
+        .collect();
+    let changes = serde_json::Value::Object(changes);
+    pool.update_job(job_id, auth_user.id, &payload, category_id, &changes)
+        .await?;
+    if let (Some(skills), true) = (&payload.skills, changes.get("skills").is_some()) {
+        pool.set_job_skills(job_id, skills).await?;
+    }
+
+    if !material.is_empty() {
+        let message = format!(
+            "The job '{}' you applied to was updated ({}).",
+            job.title,
+            material.join(", ")
+        );
+        for proposal in pool.proposals_for_job(job_id).await? {
+            if proposal.status != ProposalStatus::Submitted {
+                continue;
+            }
+            notifications
+                .notify(
+                    &pool,
+                    NewNotification::for_job(
+                        proposal.freelancer_id,
+                        "job_updated",
+                        message.clone(),
+                        job_id,
+                        auth_user.id,
+                    ),
+                )
+                .await?;
+        }
+    }
+
+    Ok(Json(
+        json!({ "message": "Job updated", "changes": changes }),
+    ))
+}
+
+// Close a job without hiring; its pending proposals expire
+pub async fn close_job(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = unhired_job(&pool, job_id, auth_user.id).await?;
+    if job.status != "open" {
+        return Err(AppError::Conflict("Job is not open".into()));
+    }
+
+    pool.set_job_status(job_id, "closed").await?;
+    expire_job_proposals(&pool, &notifications, job_id).await?;
+
+    Ok(Json(json!({ "message": "Job closed" })))
+}
+
+// Relist a closed or past-deadline job that nobody was hired for
+pub async fn repost_job(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+    Json(payload): Json<RepostJob>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    let job = unhired_job(&pool, job_id, auth_user.id).await?;
+
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    if job.status == "open" && job.deadline.as_str() >= today.as_str() {
+        return Err(AppError::Conflict(
+            "Only closed or expired jobs can be reposted".into(),
+        ));
+    }
+
+    let new_job_id = pool.repost_job(job_id, &payload.deadline).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "message": "job reposted", "job_id": new_job_id })),
+    ))
+}
+
+pub async fn get_job_revisions(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".into()));
+    }
+
+    Ok(Json(
+        json!({ "revisions": pool.job_revisions(job_id).await? }),
+    ))
+}
+
 //reducdant code but commenting for future references
 
 // pub async fn view_jobs(
@@ -121,223 +358,85 @@ pub async fn create_job(
 //     Ok((StatusCode::OK, Json(jobs_response)))
 // }
 
+// Signed-in users get the full listing; anonymous visitors get open public jobs
+// with the redacted field set, cached briefly here and by browsers and CDNs
 pub async fn get_filtered_jobs(
     State(pool): State<SqlitePool>,
-    Extension(auth_user): Extension<Arc<AuthUser>>,
+    State(cache): State<Cache>,
+    auth_user: Option<Extension<Arc<AuthUser>>>,
     Query(query): Query<JobFilterQuery>,
-) -> Result<impl IntoResponse, AppError> {
+) -> Result<Response, AppError> {
     //validate query parameters
     query.validate().map_err(AppError::Validation)?;
 
-    //build dynamic SQL query
-    // Initialize SQL query to select job fields from jobs table
-    let mut sql = String::from(
-        r#"
-        SELECT
-            j.id,
-            j.title,
-            j.description,
-            j.skills,
-            j.budget,
-            j.location,
-            j.job_type,
-            j.job_ipfs_hash,
-            j.posted_at,
-            j.deadline,
-            j.client_id,
-            j.category,
-            j.status
-        FROM jobs j
-        "#,
-    );
-    let mut params: Vec<String> = Vec::new();
-
-    //keyword search in title or description
-    // if let Some(keyword) = &query.keyword {
-    //     sql.push_str(" AND (title LIKE ? OR description LIKE ?)");
-    //     let wildcard = format!("%{}%", keyword);
-    //     params.push(wildcard.clone());
-    //     params.push(wildcard);
-    // }
-
-    //handle keyword search with fts5
-
-    if let Some(id) = query.id {
-        // Fetch by ID directly
-        sql.push_str(" WHERE j.id = ?");
-        params.push(id.to_string());
-    } else {
-        // Keyword-based search with FTS5
-        if let Some(keyword) = &query.keyword {
-            sql.push_str(" INNER JOIN jobs_fts ON j.id = jobs_fts.job_id");
-            sql.push_str(" WHERE jobs_fts MATCH ?");
-            let escaped_keyword = keyword.replace("'", ""); // sanitize input
-            params.push(escaped_keyword);
-        } else {
-            sql.push_str(" WHERE 1=1");
-        }
-
-        // Add other filters like budget, skills, etc.
-        if let Some(category) = &query.category {
-            sql.push_str(" AND j.category = ?");
-            params.push(category.clone());
-        }
-
-        // Add more filters here as needed...
-    }
-    //Min budget filter
-    if let Some(min_budget) = query.min_budget {
-        sql.push_str(" AND j.budget >= ?");
-        params.push(min_budget.to_string());
-    }
-
-    // Max budget filter
-    if let Some(max_budget) = query.max_budget {
-        sql.push_str(" AND j.budget <= ?");
-        params.push(max_budget.to_string());
-    }
-
-    //skills filter
-    if let Some(skills) = &query.skills {
-        sql.push_str(" AND j.skills LIKE ?");
-        params.push(format!("%{}%", skills));
-    }
-
-    //location filter
-    if let Some(job_type) = &query.job_type {
-        sql.push_str(" AND j.job_type = ?");
-        params.push(job_type.clone());
-    }
-
-    //client ID filter
-    if let Some(client_id) = query.client_id {
-        sql.push_str(" AND j.client_id = ?");
-        params.push(client_id.to_string());
-    }
-
-    //Category filter
-    if let Some(category) = &query.category {
-        sql.push_str(" AND j.category = ?");
-        params.push(category.clone());
-    }
-
-    //deadline start filter
-    if let Some(deadline_start) = &query.deadline_start {
-        sql.push_str(" AND j.deadline >= ?");
-        params.push(deadline_start.clone());
-    }
-
-    //deadline end filter
-    if let Some(deadline_end) = &query.deadline_end {
-        sql.push_str(" AND j.deadline <= ?");
-        params.push(deadline_end.clone());
-    }
-
-    //posted_at start filter
-    if let Some(posted_at_start) = &query.posted_at_start {
-        sql.push_str(" AND j.posted_at >= ?");
-        params.push(posted_at_start.clone());
-    }
-
-    //posted_at end filter
-    if let Some(posted_at_end) = &query.posted_at_end {
-        sql.push_str(" AND j.posted_at <= ?");
-        params.push(posted_at_end.clone());
+    if let Some(Extension(auth_user)) = auth_user {
+        let jobs = pool.search_jobs(&query, Some(&auth_user)).await?;
+        return Ok((
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "private, no-store")],
+            Json(jobs),
+        )
+            .into_response());
     }
 
-    //filter by status
-    if let Some(status) = &query.status {
-        sql.push_str(" AND j.status = ?");
-        params.push(status.clone());
-    }
-
-    //role-based access: freelancers see all jobs, clients see only their jobs
-    if auth_user.role == "client" {
-        sql.push_str(" AND j.client_id =?");
-        params.push(auth_user.id.to_string());
-    }
-
-    //sort by posted_at (latest first)
-    // sql.push_str(" ORDER BY posted_at DESC");
-
-    //Dynamic sorting
-    if let Some(sort_by) = &query.sort_by {
-        let parts: Vec<&str> = sort_by.split(':').collect();
-        if parts.len() == 2 {
-            let column = match parts[0] {
-                "budget" => "j.budget",
-                "posted_at" => "j.posted_at",
-                "deadline" => "j.deadline",
-                _ => "j.posted_at", //default
-            };
-            let direction = match parts[1].to_uppercase().as_str() {
-                "ASC" => "ASC",
-                "DESC" => "DESC",
-                _ => "DESC", //default
-            };
-            sql.push_str(&format!(" ORDER BY {} {}", column, direction));
-        } else {
-            sql.push_str(" ORDER BY j.posted_at DESC");
+    let key = format!(
+        "{}{}",
+        PUBLIC_JOBS_CACHE_PREFIX,
+        serde_json::to_string(&query).unwrap_or_default()
+    );
+    let jobs = match cache.get::<JobsResponse<PublicJob>>(&key) {
+        Some(jobs) => jobs,
+        None => {
+            let jobs = pool.search_jobs(&query, None).await?.redacted();
+            cache.insert(key, &jobs, PUBLIC_JOBS_TTL);
+            jobs
         }
-    } else {
-        sql.push_str(" ORDER BY j.posted_at DESC");
-    }
-
-    //pagination
-    let limit = query.limit.unwrap_or(20);
-    let offset = query.offset.unwrap_or(0);
-    sql.push_str(" LIMIT ? OFFSET ?");
-    params.push(limit.to_string());
-    params.push(offset.to_string());
-
-    // Execute dynamic query with individual bind calls
-    let mut query = sqlx::query_as::<sqlx::Sqlite, JobResponse>(&sql);
-    for param in params {
-        query = query.bind(param);
-    }
-    let jobs = query.fetch_all(&pool).await.map_err(|e| {
-        // Edge case: SQL syntax error or database connection failure
-        AppError::Database(e.to_string())
-    })?;
-
-    //return filtered jobs
-    let jobs_response = JobsResponse { jobs };
-    Ok((StatusCode::OK, Json(jobs_response)))
+    };
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CACHE_CONTROL, PUBLIC_JOBS_CACHE_CONTROL),
+            (header::VARY, "Authorization"),
+        ],
+        Json(jobs),
+    )
+        .into_response())
 }
 
-pub async fn get_categories(
-    State(_pool): State<SqlitePool>,
-) -> Result<(StatusCode, Json<Categories>), AppError> {
-    // Mock categories (replace with DB query if needed)
-    let categories = vec![
-        "Web Development",
-        "Graphic Design",
-        "Writing",
-        "Digital Marketing",
-        "Video Editing",
-        "Music Production",
-    ];
-
-    let categories = Categories {
-        categories: categories.into_iter().map(String::from).collect(),
-    };
+// The client's own jobs, invite-only and closed ones included, with the usual filters
+pub async fn get_posted_jobs(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Query(mut query): Query<JobFilterQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate().map_err(AppError::Validation)?;
+    query.client_id = Some(auth_user.id);
 
-    Ok((StatusCode::OK, Json(categories)))
+    let jobs = pool.search_jobs(&query, Some(&auth_user)).await?;
+    Ok((StatusCode::OK, Json(jobs)))
 }
 
 pub async fn apply_for_job(
     State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
     Json(payload): Json<ApplyJobPayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Check if job exists and is open
-    let job_status: Option<String> =
-        sqlx::query_scalar!("SELECT status FROM jobs WHERE id = ?", payload.job_id)
-            .fetch_optional(&pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+    payload.validate().map_err(AppError::Validation)?;
+    submit_application(&pool, &notifications, auth_user.id, payload).await?;
+
+    Ok(Json("Application submitted"))
+}
 
-    match job_status {
+// Shared by /jobs/apply and /proposals, which both create a job application
+pub(crate) async fn submit_application(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+    user_id: i64,
+    payload: ApplyJobPayload,
+) -> Result<i64, AppError> {
+    // Check if job exists and is open
+    match pool.job_status(payload.job_id).await? {
         None => return Err(AppError::NotFound("Job not found".into())),
         Some(status) if status != "open" => {
             return Err(AppError::BadRequest(
@@ -346,340 +445,319 @@ pub async fn apply_for_job(
         }
         _ => {}
     }
+    // Invite-only jobs don't exist for freelancers who weren't invited
+    if pool.job_visibility(payload.job_id).await? == Some(JobVisibility::InviteOnly)
+        && !pool.is_invited(payload.job_id, user_id).await?
+    {
+        return Err(AppError::NotFound("Job not found".into()));
+    }
 
-    // Fetch the job to get creator's ID
-    let job_creator = sqlx::query!(
-        "SELECT j.client_id, p.username, u.wallet_address
-            FROM jobs j
-            JOIN profiles p ON p.user_id = ?
-            JOIN users u ON u.id = ?
-            WHERE j.id = ?",
-        auth_user.id,
-        auth_user.id,
-        payload.job_id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    // Try inserting application
-    let result = sqlx::query!(
-        "INSERT INTO job_applications (user_id, job_id, freelancer_wallet) VALUES (?, ?, ?)",
-        auth_user.id,
-        payload.job_id,
-        job_creator.wallet_address
-    )
-    .execute(&pool)
-    .await;
-
-    match result {
-        Ok(_) => {
-            // ✅ Only insert notification after successful application
-            let message = format!("{} has applied to your job post.", job_creator.username);
-            sqlx::query!(
-                "INSERT INTO notifications (user_id, message, read,type,  job_id, actor_id) VALUES (?, ?, 0, ?, ?, ?)",
-                job_creator.client_id,
-                message,
+    let applicant = pool
+        .user_summary(user_id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".into()))?;
+    // Payment is released to this wallet, so it has to exist before applying
+    let wallet = payload
+        .payout_wallet
+        .or(applicant.wallet_address)
+        .ok_or(AppError::BadRequest(
+            "Connect a wallet before applying".into(),
+        ))?;
+
+    let application = NewApplication {
+        cover_letter: payload.cover_letter,
+        bid_amount: payload.bid_amount,
+        timeline_days: payload.timeline_days,
+        ..NewApplication::new(user_id, payload.job_id, wallet)
+    };
+    let application_id = match pool.create_application(&application).await {
+        Err(AppError::Conflict(_)) => {
+            return Err(AppError::Conflict(
+                "You have already applied to this job".into(),
+            ))
+        }
+        result => result?,
+    };
+
+    let application = pool
+        .find_application(application_id)
+        .await?
+        .ok_or(AppError::Server("Application vanished after insert".into()))?;
+
+    let message = format!(
+        "{} has applied to your job post.",
+        application
+            .freelancer_username
+            .unwrap_or_else(|| "A freelancer".to_string())
+    );
+    notifications
+        .notify(
+            pool,
+            NewNotification::for_job(
+                application.client_id,
                 "applied",
-                payload.job_id,
-                auth_user.id
-            )
-            .execute(&pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
-
-            sqlx::query!(
-                "
-                INSERT INTO job_user_interactions (user_id, job_id, applied)
-                VALUES (?, ?, TRUE)
-                ON CONFLICT(user_id, job_id) DO UPDATE SET applied = TRUE",
-                auth_user.id,
-                payload.job_id
-            )
-            .execute(&pool)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+                message,
+                application.job_id,
+                user_id,
+            ),
+        )
+        .await?;
 
-            Ok(Json("Application submitted"))
-        }
-        Err(e) => {
-            if e.to_string().contains("UNIQUE constraint failed") {
-                Err(AppError::BadRequest(
-                    "You have already applied to this job".into(),
-                ))
-            } else {
-                Err(AppError::Database(e.to_string()))
-            }
-        }
-    }
+    pool.mark_applied(user_id, application.job_id).await?;
+    pool.accept_pending_invitation(application.job_id, user_id)
+        .await?;
+
+    Ok(application_id)
 }
+
 pub async fn approve_application(
     Extension(auth_user): Extension<Arc<AuthUser>>,
     State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
     Json(payload): Json<ApproveApplicationPayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Step 1: Validate client owns the job related to this application
-    let record = sqlx::query!(
-        r#"
-        SELECT ja.user_id as freelancer_id, j.client_id, j.id as job_id, u.wallet_address, p.username
-        FROM job_applications ja
-        JOIN jobs j ON ja.job_id = j.id
-        JOIN users u ON ja.user_id = u.id
-        JOIN profiles p ON p.user_id = j.client_id 
-        WHERE ja.id = ?
-        "#,
-        payload.application_id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| AppError::NotFound("Application not found".into()))?;
+    // Validate client owns the job related to this application
+    let application = pool
+        .find_application(payload.application_id)
+        .await?
+        .ok_or(AppError::NotFound("Application not found".into()))?;
+
+    if application.client_id != auth_user.id {
+        return Err(AppError::Forbidden("User doesn't match.".to_string()));
+    }
 
-    if record.client_id != auth_user.id {
-        return Err(AppError::Unauthorized("User doesn't match.".to_string()));
+    accept_application(&pool, &notifications, application, auth_user.id).await?;
+
+    Ok(Json(json!({ "message": "Application approved"})))
+}
+
+// Hire the freelancer behind an application on its standing terms. `accepted_by`
+// is the party agreeing; the caller has checked they're on the application.
+pub(crate) async fn accept_application(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+    application: ApplicationDetail,
+    accepted_by: i64,
+) -> Result<(), AppError> {
+    if application.status != ProposalStatus::Submitted {
+        return Err(AppError::Conflict(format!(
+            "Application has already been {}",
+            application.status.as_str()
+        )));
     }
 
-    // Step 2: Update approval status
-    sqlx::query!(
-        "UPDATE job_applications SET approved = 1, approved_at =  CURRENT_TIMESTAMP  WHERE id = ?",
-        payload.application_id
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    // Nobody can accept their own offer; without any offer only the client can hire at the job budget
+    let (agreed_amount, agreed_timeline_days) = match pool.latest_offer(application.id).await? {
+        Some(offer) if offer.author_id == accepted_by => {
+            return Err(AppError::Conflict(
+                "Waiting for the other party to respond to your offer".into(),
+            ))
+        }
+        Some(offer) => (offer.amount, offer.timeline_days),
+        None if accepted_by == application.client_id => (application.job_budget as f64, None),
+        None => return Err(AppError::Conflict("There is no offer to accept".into())),
+    };
 
-    sqlx::query!(
-        "UPDATE jobs SET status = 'closed'  WHERE id = ?",
-        record.job_id
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let msg = format!("{} has approved you application.", record.username);
-
-    // Step 3: Create notification for freelancer
-    sqlx::query!(
-        "INSERT INTO notifications (user_id, message, read, type, job_id, actor_id) VALUES (?, ?, 0, ?, ?, ?)",
-        record.freelancer_id,
-        msg,
-        "approved",
-        record.job_id,
-        auth_user.id
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    if !pool.approved_freelancers(application.job_id).await?.is_empty() {
+        return Err(AppError::Conflict(
+            "A freelancer has already been hired for this job".into(),
+        ));
+    }
 
-    Ok(Json(json!({ "message": "Application approved"})))
+    // Migrated proposals may predate the freelancer connecting a wallet
+    if application.freelancer_wallet.is_none() {
+        return Err(AppError::BadRequest(
+            "The freelancer has no payout wallet on this application".into(),
+        ));
+    }
+
+    let rejected = pool
+        .approve_application(
+            application.id,
+            application.job_id,
+            agreed_amount,
+            agreed_timeline_days,
+        )
+        .await?;
+
+    // The hire is done; letting the other applicants know is best effort
+    let rejected_msg = format!(
+        "Your proposal for the job '{}' was not accepted.",
+        application.job_title
+    );
+    for freelancer_id in rejected {
+        notifications
+            .notify(
+                pool,
+                NewNotification::for_job(
+                    freelancer_id,
+                    "rejected",
+                    rejected_msg.clone(),
+                    application.job_id,
+                    accepted_by,
+                ),
+            )
+            .await
+            .ok();
+    }
+
+    let notification = if accepted_by == application.client_id {
+        let msg = format!(
+            "{} has approved you application.",
+            application.client_username.unwrap_or_default()
+        );
+        NewNotification::for_job(
+            application.freelancer_id,
+            "approved",
+            msg,
+            application.job_id,
+            accepted_by,
+        )
+    } else {
+        let msg = format!(
+            "{} accepted your offer of {} for '{}'.",
+            application
+                .freelancer_username
+                .unwrap_or_else(|| "The freelancer".to_string()),
+            agreed_amount,
+            application.job_title
+        );
+        NewNotification::for_job(
+            application.client_id,
+            "approved",
+            msg,
+            application.job_id,
+            accepted_by,
+        )
+    };
+    notifications.notify(pool, notification).await?;
+
+    Ok(())
 }
 
 pub async fn create_escrow_notification(
     Extension(auth_user): Extension<Arc<AuthUser>>,
     State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
     Json(payload): Json<CreateEscrowPayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Step 1: Validate client owns the job related to this application
-    let record = sqlx::query!(
-        r#"
-        SELECT ja.user_id as freelancer_id, j.client_id , j.id as job_id, j.title as job_title
-        FROM job_applications ja
-        JOIN jobs j ON ja.job_id = j.id
-        JOIN users u ON ja.user_id = u.id
-        WHERE ja.id = ?
-        "#,
-        payload.application_id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| AppError::NotFound("Application not found".into()))?;
+    // Validate client owns the job related to this application
+    let application = pool
+        .find_application(payload.application_id)
+        .await?
+        .ok_or(AppError::NotFound("Application not found".into()))?;
+
+    if application.client_id != auth_user.id {
+        return Err(AppError::Forbidden("User doesn't match.".to_string()));
+    }
 
-    if record.client_id != auth_user.id {
-        return Err(AppError::Unauthorized("User doesn't match.".to_string()));
+    // Escrow funds the hired freelancer's agreed bid
+    if !application.approved {
+        return Err(AppError::BadRequest(
+            "Escrow can only be created for an approved application".into(),
+        ));
+    }
+    if application.job_type == JobType::Hourly {
+        return Err(AppError::BadRequest(
+            "Hourly contracts are funded per approved timesheet".into(),
+        ));
     }
 
     let msg_client = format!(
         "Escrow {} has be created for job '{}'.",
-        payload.escrow_pda, record.job_title
+        payload.escrow_pda, application.job_title
     );
-
-    // Step 3: Create notification for client
-    sqlx::query!(
-        "INSERT INTO notifications (user_id, message, read, type, job_id, actor_id, escrow_pda) VALUES (?, ?, 0, ?, ?, ?, ?)",
-        record.client_id,
-        msg_client,
-        "escrow",
-        record.job_id,
-        auth_user.id,
-        payload.escrow_pda
-
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
     let msg_freelancer = format!(
         "Escrow {} has be created for the job '{}' you applied.",
-        payload.escrow_pda, record.job_title
+        payload.escrow_pda, application.job_title
     );
 
-    sqlx::query!(
-        "INSERT INTO notifications (user_id, message, read, type, job_id, actor_id, escrow_pda) VALUES (?, ?, 0, ?, ?, ?, ?)",
-        record.freelancer_id,
-        msg_freelancer,
-        "escrow",
-        record.job_id,
-        auth_user.id,
-        payload.escrow_pda
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    for (recipient, message) in [
+        (application.client_id, msg_client),
+        (application.freelancer_id, msg_freelancer),
+    ] {
+        notifications
+            .notify(
+                &pool,
+                NewNotification {
+                    escrow_pda: Some(payload.escrow_pda.clone()),
+                    ..NewNotification::for_job(
+                        recipient,
+                        "escrow",
+                        message,
+                        application.job_id,
+                        auth_user.id,
+                    )
+                },
+            )
+            .await?;
+    }
 
     Ok(Json(json!({ "message": "Added Notification"})))
 }
 
+// Each applicant comes with their reputation, so the client can compare track records
 pub async fn get_job_applicants(
+    Extension(auth_user): Extension<Arc<AuthUser>>,
     Path(job_id): Path<i64>,
     State(pool): State<SqlitePool>,
-) -> Result<Json<Vec<ApplicantResponse>>, AppError> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            ja.id AS application_id,
-            ja.user_id AS freelancer_id,
-            p.username AS freelancer_username,
-            p.skills,
-            p.profile_ipfs_hash,
-            ja.applied_at,
-            ja.approved,
-            ja.approved_at,
-            ja.freelancer_wallet
-        FROM job_applications ja
-        JOIN users u ON ja.user_id = u.id
-        JOIN profiles p ON p.user_id = u.id
-        WHERE ja.job_id = ?
-        ORDER BY ja.applied_at DESC
-        "#,
-        job_id
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let applicants = rows
-        .into_iter()
-        .map(|row| ApplicantResponse {
-            application_id: row.application_id,
-            freelancer_id: row.freelancer_id,
-            freelancer_username: row.freelancer_username,
-            skills: row.skills,
-            profile_ipfs_hash: row.profile_ipfs_hash,
-            applied_at: row.applied_at,
-            approved: row.approved,
-            approved_at: row.approved_at,
-            freelancer_wallet: row.freelancer_wallet,
-        })
-        .collect();
-
-    Ok(Json(applicants))
+    State(config): State<Arc<Config>>,
+    Query(page): Query<PageQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    page.validate().map_err(AppError::Validation)?;
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".to_string()));
+    }
+    let (mut applicants, next_cursor) = pool.applicants_for_job(job_id, &page).await?;
+    let freelancer_ids: Vec<i64> = applicants.iter().map(|a| a.freelancer_id).collect();
+    let mut reputations = pool
+        .reputations(&freelancer_ids, &review_cutoff(&config))
+        .await?;
+    for applicant in &mut applicants {
+        applicant.reputation = reputations
+            .remove(&applicant.freelancer_id)
+            .unwrap_or_default();
+    }
+    Ok(Json(
+        json!({ "applicants": applicants, "next_cursor": next_cursor }),
+    ))
 }
+
 pub async fn get_user_jobs(
     Extension(auth_user): Extension<Arc<AuthUser>>,
     State(pool): State<SqlitePool>,
 ) -> Result<Json<Vec<MyJobsResponse>>, AppError> {
-    let rows = sqlx::query!(
-        r#"
-SELECT 
-    j.id AS job_id,
-    j.title,
-    j.status,
-    j.description,
-    j.skills,
-    j.budget,
-    j.location,
-    j.posted_at,
-    j.deadline,
-    j.client_id,
-    ja.applied_at,
-    ja.approved,
-    ja.id as application_id,
-    CASE WHEN s.job_id IS NOT NULL THEN 1 ELSE 0 END AS is_saved
-FROM jobs j
-JOIN job_applications ja ON ja.job_id = j.id 
-LEFT JOIN saved_jobs s ON s.job_id = j.id AND s.user_id = ja.user_id
-WHERE ja.user_id = ? OR ( j.client_id = ? AND ja.approved = 1)
-ORDER BY ja.applied_at DESC;
-        "#,
-        auth_user.id,
-        auth_user.id
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let applicants = rows
-        .into_iter()
-        .map(|row| MyJobsResponse {
-            job_id: row.job_id,
-            status: row.status,
-            title: row.title,
-            description: row.description,
-            skills: row.skills,
-            budget: row.budget as u64,
-            location: row.location,
-            posted_at: row.posted_at,
-            deadline: row.deadline,
-            client_id: row.client_id,
-            applied_at: row.applied_at,
-            approved: row.approved,
-            is_saved: row.is_saved,
-            application_id: row.application_id,
-        })
-        .collect();
-
-    Ok(Json(applicants))
+    Ok(Json(pool.jobs_for_user(auth_user.id).await?))
 }
 
 pub async fn get_notifications(
     Extension(auth_user): Extension<Arc<AuthUser>>,
     State(pool): State<SqlitePool>,
+    Query(page): Query<PageQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            n.id,
-            n.message,
-            n.read,
-            n.created_at,
-            n.job_id,
-            n.type,
-            n.escrow_pda,
-            j.title AS job_title,
-            pf.username AS username,
-            ja.id as application_id
-        FROM notifications n
-        LEFT JOIN jobs j ON n.job_id = j.id
-        LEFT JOIN profiles pf ON n.actor_id = pf.user_id
-        LEFT JOIN job_applications ja ON ja.job_id = j.id
-        WHERE n.user_id = ?
-        ORDER BY n.created_at DESC
-        "#,
-        auth_user.id
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
+    page.validate().map_err(AppError::Validation)?;
+    let (rows, next_cursor) = pool.notifications_for_user(auth_user.id, &page).await?;
     let notifications: Vec<Notification> = rows
         .into_iter()
         .map(|row| {
-            let redirect_url = match row.r#type.as_deref() {
+            let redirect_url = match row.kind.as_deref() {
                 Some("applied") => format!("/jobs/{}/applicants", row.job_id.unwrap_or_default()),
                 Some("approved") => format!("/jobs/{}", row.job_id.unwrap_or_default()),
                 Some("submitted") => {
                     format!("/my-workspace/{}", row.application_id.unwrap_or_default())
                 }
-                Some("review") => format!("/my-jobs/{}", row.job_id.unwrap_or_default()),
+                Some("review") | Some("timesheet") => {
+                    format!("/my-jobs/{}", row.job_id.unwrap_or_default())
+                }
+                Some("timesheet_reviewed") | Some("payment_released") => {
+                    format!("/my-workspace/{}", row.application_id.unwrap_or_default())
+                }
+                Some("invited") | Some("job_updated") | Some("saved_search") | Some("reviewed") => {
+                    format!("/jobs/{}", row.job_id.unwrap_or_default())
+                }
+                Some("proposal_updated") | Some("withdrawn") | Some("invitation") => {
+                    format!("/jobs/{}/applicants", row.job_id.unwrap_or_default())
+                }
                 Some("escrow") => match &row.escrow_pda {
                     Some(pda) => format!("/escrow/{}", pda),
                     None => "/escrow".to_string(),
@@ -700,7 +778,9 @@ pub async fn get_notifications(
         })
         .collect();
 
-    Ok(Json(notifications))
+    Ok(Json(
+        json!({ "notifications": notifications, "next_cursor": next_cursor }),
+    ))
 }
 
 pub async fn mark_notification_as_read(
@@ -708,16 +788,7 @@ pub async fn mark_notification_as_read(
     State(pool): State<SqlitePool>,
     Json(payload): Json<MarkReadPayload>,
 ) -> Result<Json<&'static str>, AppError> {
-    let updated = sqlx::query!(
-        "UPDATE notifications SET read = 1 WHERE id = ? AND user_id = ?",
-        payload.id,
-        auth_user.id
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if updated.rows_affected() == 0 {
+    if !pool.mark_notification_read(payload.id, auth_user.id).await? {
         return Err(AppError::NotFound("Notification not found".into()));
     }
 
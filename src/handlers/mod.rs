@@ -2,14 +2,23 @@ use axum::response::IntoResponse;
 use axum::Json;
 use serde_json::json;
 
+pub mod account;
 pub mod auth;
+pub mod category;
 pub mod client;
 pub mod escrow;
 pub mod freelancer;
+pub mod invitation;
 pub mod job;
 pub mod profile;
 pub mod proposal;
 pub mod protected;
+pub mod recommendation;
+pub mod review;
+pub mod saved_search;
+pub mod skill;
+pub mod timesheet;
+pub mod two_factor;
 pub async fn check_health() -> impl IntoResponse {
     Json(json!({"status" : "ok"}))
 }
@@ -1,132 +1,98 @@
+use crate::config::Config;
+use crate::db::repo::{ProfileRepo, ReviewRepo, SkillRepo};
 use crate::error::AppError;
+use crate::handlers::review::review_cutoff;
 use crate::models::auth::*;
 use crate::models::profile::*;
 use axum::{
-    extract::{Extension, Json, Path, Query},
+    extract::{Extension, Json, Path, Query, State},
     response::IntoResponse,
 };
 use serde_json::json;
-use sqlx::{Sqlite, SqlitePool};
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
+use validator::Validate;
 
 pub async fn create_or_update_profile(
-    Extension(pool): Extension<SqlitePool>,
+    State(pool): State<SqlitePool>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
-    Json(payload): Json<UserProfilePayload>,
+    Json(mut payload): Json<UserProfilePayload>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Check if username is already taken by another user
-    let existing = sqlx::query_scalar!(
-        "SELECT user_id FROM profiles WHERE username = ? AND user_id != ?",
-        payload.username,
-        auth_user.id
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    payload.validate().map_err(AppError::Validation)?;
+    payload.place.normalize();
 
-    if existing.is_some() {
+    // Check if username is already taken by another user
+    if pool
+        .username_taken(&payload.username, Some(auth_user.id))
+        .await?
+    {
         return Err(AppError::Conflict("Username is already taken".into()));
     }
 
     // Insert or update profile
-    sqlx::query!(
-        r#"
-        INSERT INTO profiles (
-            user_id, username, role, bio, skills, certifications, work_history, profile_ipfs_hash
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-        ON CONFLICT(user_id) DO UPDATE SET
-            username = excluded.username,
-            role = excluded.role,
-            bio = excluded.bio,
-            skills = excluded.skills,
-            certifications = excluded.certifications,
-            work_history = excluded.work_history,
-            profile_ipfs_hash = excluded.profile_ipfs_hash,
-            updated_at = CURRENT_TIMESTAMP
-        "#,
-        auth_user.id,
-        payload.username,
-        auth_user.role,
-        payload.bio,
-        payload.skills,
-        payload.certifications,
-        payload.work_history,
-        payload.profile_ipfs_hash
-    )
-    .execute(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    pool.save_profile(auth_user.id, &auth_user.role, &payload)
+        .await?;
+    pool.set_profile_skills(auth_user.id, payload.skills.as_deref())
+        .await?;
 
     Ok(Json(UserProfileResponse {
         message: "Profile saved successfully.".into(),
     }))
 }
 
+async fn with_reputation(
+    pool: &SqlitePool,
+    config: &Config,
+    mut profile: ProfileResponseByIdOrUsername,
+) -> Result<ProfileResponseByIdOrUsername, AppError> {
+    let user_id: i64 = profile
+        .user_id
+        .parse()
+        .map_err(|_| AppError::Server("Profile has a malformed user id".into()))?;
+    profile.reputation = pool
+        .reputations(&[user_id], &review_cutoff(config))
+        .await?
+        .remove(&user_id)
+        .unwrap_or_default();
+    Ok(profile)
+}
+
 pub async fn get_profile_by_user_id(
     Path(user_id): Path<String>,
-    Extension(pool): Extension<SqlitePool>,
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
 ) -> Result<Json<ProfileResponseByIdOrUsername>, AppError> {
-    let profile = sqlx::query_as::<Sqlite, ProfileResponseByIdOrUsername>(
-        r#"
-        SELECT user_id, username, role, bio, skills, certifications, work_history,
-            profile_ipfs_hash, created_at, updated_at
-        FROM profiles
-        WHERE user_id = ?
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let profile = pool.profile_by_user_id(&user_id).await?;
 
     match profile {
-        Some(profile_data) => Ok(Json(profile_data)),
+        Some(profile_data) => Ok(Json(with_reputation(&pool, &config, profile_data).await?)),
         None => Err(AppError::NotFound("Profile not found".into())),
     }
 }
 pub async fn get_profile_by_username(
     Path(username): Path<String>,
-    Extension(pool): Extension<SqlitePool>,
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
 ) -> Result<Json<ProfileResponseByIdOrUsername>, AppError> {
-    let profile = sqlx::query_as::<Sqlite, ProfileResponseByIdOrUsername>(
-        r#"
-        SELECT user_id, username, role, bio, skills, certifications, work_history,
-            profile_ipfs_hash, created_at, updated_at
-        FROM profiles
-        WHERE username = ?
-        "#,
-    )
-    .bind(username)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let profile = pool.profile_by_username(&username).await?;
 
     match profile {
-        Some(profile_data) => Ok(Json(profile_data)),
+        Some(profile_data) => Ok(Json(with_reputation(&pool, &config, profile_data).await?)),
         None => Err(AppError::NotFound("Profile not found".into())),
     }
 }
 
 pub async fn check_username_availability(
     Query(params): Query<HashMap<String, String>>,
-    Extension(pool): Extension<SqlitePool>,
+    State(pool): State<SqlitePool>,
 ) -> Result<impl IntoResponse, AppError> {
     let username = match params.get("username") {
         Some(u) => u,
         None => return Err(AppError::Validation(validator::ValidationErrors::new())),
     };
 
-    let exists = sqlx::query_scalar!(
-        "SELECT EXISTS (SELECT 1 FROM profiles WHERE username = ?)",
-        username
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let available = exists.unwrap_or(0) == 0;
+    let available = !pool.username_taken(username, None).await?;
 
     Ok(Json(json!({ "available": available })))
 }
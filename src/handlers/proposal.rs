@@ -9,72 +9,47 @@ use serde_json::json;
 use validator::Validate;
 use std::sync::Arc;
 use crate::{
+    db::{
+        pagination::PageQuery,
+        repo::{ApplicationDetail, ApplicationRepo, JobRepo, NewNotification},
+    },
     error::AppError,
+    handlers::job::{accept_application, submit_application},
     models::{
-        proposal::{CreateProposal, ProposalResponse, ProposalStatus, UpdateProposal, ProposalFilter},
+        proposal::{
+            CreateOffer, CreateProposal, EditProposal, ProposalResponse, ProposalStatus,
+            UpdateProposal, ProposalFilter,
+        },
         auth::AuthUser,
     },
-    // blockchain::escrow::call_create_escrow,
+    notifications::NotificationBroadcaster,
 };
 
+// Proposals are job applications with a cover letter and bid, so accepting one
+// hires the freelancer exactly like /applications/approve
+
 //Create a new proposal
 //only authenticated freelancers can submit proposals
 pub async fn create_proposal(
     State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
     Json(payload): Json<CreateProposal>,
 ) -> Result<impl axum::response::IntoResponse, AppError>{
     //Ensure user is a freelancer
     if auth_user.role != "freelancer" {
-        return Err(AppError::Unauthorized("Only freelancers can submit proposals".to_string()));
+        return Err(AppError::Forbidden("Only freelancers can submit proposals".to_string()));
     }
 
     //validate input (job_id > 0, cover_letter 10-1000 chars, bid_amount > 1.0)
-    payload.validate().map_err(|e| AppError::BadRequest(e.to_string()))?;
-
-    //check if job exits
-    let job_exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM jobs WHERE id = ?")
-                        .bind(payload.job_id)
-                        .fetch_one(&pool)
-                        .await
-                        .map_err(|e| AppError::Database(e.to_string()))?;
-    
-    if job_exists == 0 {
-        return Err(AppError::BadRequest("Job does not exist".to_string()));
-    }
+    payload.validate().map_err(AppError::Validation)?;
 
-    //Check if freelancer already applied
-    let already_applied = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM proposals WHERE job_id = ? AND freelancer_id = ?"
-    )
-    .bind(payload.job_id)
-    .bind(auth_user.id)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if already_applied > 0 {
-        return Err(AppError::BadRequest("You have already applied for this job".to_string()));
-    }
-
-    //Insert proposal
-    let now = chrono::Utc::now().timestamp();
-    let proposal = sqlx::query_as::<_, ProposalResponse>(
-        r#"
-        INSERT INTO proposals (job_id, freelancer_id, cover_letter, bid_amount, status, created_at)
-        VALUES (?,?,?,?,?,?)
-        RETURNING id, job_id, freelancer_id, cover_letter, bid_amount, status, created_at
-        "#
-    )
-    .bind(payload.job_id)
-    .bind(auth_user.id)
-    .bind(payload.cover_letter)
-    .bind(payload.bid_amount)
-    .bind(ProposalStatus::Submitted)
-    .bind(now)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let application_id =
+        submit_application(&pool, &notifications, auth_user.id, payload.into()).await?;
+    let proposal = pool
+        .proposal(application_id)
+        .await?
+        .ok_or(AppError::Server("Proposal vanished after insert".into()))?;
 
     Ok((
         StatusCode::CREATED,
@@ -89,28 +64,11 @@ pub async fn get_proposals_by_job(
     Path(job_id): Path<i64>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     //check if the user is the job owner
-    let is_owner = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM jobs WHERE id = ? AND client_id = ?"
-    )
-    .bind(job_id)
-    .bind(auth_user.id)
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if is_owner == 0 {
-        return Err(AppError::Unauthorized("You are not the job owner".to_string()));
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".to_string()));
     }
 
-    //Fetch proposals
-    let proposals = sqlx::query_as::<_, ProposalResponse>(
-        "SELECT id, job_id, freelancer_id, cover_letter, bid_amount, status, created_at
-        FROM proposals WHERE job_id = ?"
-    )
-    .bind(job_id)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
+    let proposals = pool.proposals_for_job(job_id).await?;
 
     Ok((
         StatusCode::OK,
@@ -122,71 +80,67 @@ pub async fn get_proposals_by_job(
 //Update proposal status (accept/reject), restricted to job owner
 pub async fn update_proposal(
     State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
     Path(proposal_id): Path<i64>,
     Json(payload): Json<UpdateProposal>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
     //Restricts to clients
     if auth_user.role != "client" {
-        return Err(AppError::Unauthorized("Only clients can update proposals".to_string()));
+        return Err(AppError::Forbidden("Only clients can update proposals".to_string()));
     }
 
     //Validate input (status must be Accepted or Rejected)
-    payload.validate().map_err(|e| AppError::BadRequest(e.to_string()))?;
-
-    if payload.status == ProposalStatus::Submitted {
-        return Err(AppError::BadRequest("Cannot set status to submitted".to_string()));
-    }
+    payload.validate().map_err(AppError::Validation)?;
 
-    //Atomically update proposal if user is job owner and proposal is submitted 
-    let result = sqlx::query_as::<_, ProposalResponse>(
-        r#"
-        UPDATE proposals
-        SET status = ?
-        WHERE id = ? AND job_id IN (
-            SELECT id FROM jobs WHERE client_id = ?
-        ) AND status = ?
-         RETURNING id, job_id, freelancer_id, cover_letter, bid_amount, status, created_at
-         "#
-    )
-    .bind(&payload.status)
-    .bind(proposal_id)
-    .bind(auth_user.id)
-    .bind(ProposalStatus::Submitted)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    let proposal = match result {
-        Some(proposal) => proposal,
-        None => return Err(AppError::BadRequest(
-            "Proposal not found, not owned, or not in submitted status".to_string()
-        )),
-    };
+    let application = pool
+        .find_application(proposal_id)
+        .await?
+        .filter(|application| application.client_id == auth_user.id)
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))?;
 
-    // If accepted, create escrow on Solana
-    // if payload.status == ProposalStatus::Accepted {
-    //     // Fetch freelancer's wallet address
-    //     let freelancer_wallet = sqlx::query_scalar::<_, String>(
-    //         "SELECT wallet_address FROM users WHERE id = ?"
-    //     )
-    //     .bind(proposal.freelancer_id)
-    //     .fetch_one(&pool)
-    //     .await
-    //     .map_err(|e| AppError::Database(e.to_string()))?;
-
-        // Call create escrow
-    //     call_create_escrow(
-    //         proposal_id as u64,
-    //         proposal.job_id as u64, 
-    //         &freelancer_wallet, 
-    //         (proposal.bid_amount * 1_000_000_000.0) as u64, // Convert bid_amount to lamports (assuming bid_amount in SOL)
-    //         )
-    //         .await
-    //         .map_err(|e| AppError::Server(format!("Failed to create escrow: {}", e)))?;
-    // }
+    match payload.status {
+        // Withdrawal is the freelancer's call and expiry happens on its own
+        status @ (ProposalStatus::Submitted | ProposalStatus::Withdrawn | ProposalStatus::Expired) => {
+            return Err(AppError::BadRequest(format!(
+                "Cannot set status to {}",
+                status.as_str()
+            )))
+        }
+        ProposalStatus::Accepted => {
+            accept_application(&pool, &notifications, application, auth_user.id).await?
+        }
+        ProposalStatus::Rejected => {
+            if !pool.reject_application(application.id).await? {
+                return Err(AppError::Conflict(
+                    "Proposal is no longer awaiting a decision".to_string(),
+                ));
+            }
+            let message = format!(
+                "Your proposal for the job '{}' was not accepted.",
+                application.job_title
+            );
+            notifications
+                .notify(
+                    &pool,
+                    NewNotification::for_job(
+                        application.freelancer_id,
+                        "rejected",
+                        message,
+                        application.job_id,
+                        auth_user.id,
+                    ),
+                )
+                .await?;
+        }
+    }
 
     //Return updated proposal
+    let proposal = pool
+        .proposal(proposal_id)
+        .await?
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))?;
+
     Ok((
         StatusCode::OK,
         Json(json!({ "proposal": proposal})),
@@ -198,56 +152,283 @@ pub async fn get_my_proposals(
     State(pool): State<SqlitePool>,
     Extension(auth_user): Extension<Arc<AuthUser>>,
     Query(filter): Query<ProposalFilter>,
+    Query(page): Query<PageQuery>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
        // Restrict to freelancers
        if auth_user.role != "freelancer" {
-        return Err(AppError::Unauthorized("Only freelancers can view their proposals".to_string()));
+        return Err(AppError::Forbidden("Only freelancers can view their proposals".to_string()));
     }
 
-    // Build complete query string
-    let mut query = String::from(
-        "SELECT p.id, p.job_id, p.freelancer_id, p.cover_letter, p.bid_amount, p.status, p.created_at
-         FROM proposals p
-         JOIN jobs j ON p.job_id = j.id
-         WHERE p.freelancer_id = ?"
-    );
+    page.validate().map_err(AppError::Validation)?;
+
+    let (proposals, next_cursor) = pool
+        .proposals_for_freelancer(auth_user.id, &filter, &page)
+        .await?;
+
+    // Return proposals
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "proposals": proposals, "next_cursor": next_cursor })),
+    ))
+}
+
+// The application behind a proposal, if the user is its client or freelancer
+async fn proposal_for_party(
+    pool: &SqlitePool,
+    proposal_id: i64,
+    user_id: i64,
+) -> Result<ApplicationDetail, AppError> {
+    pool.find_application(proposal_id)
+        .await?
+        .filter(|application| {
+            application.client_id == user_id || application.freelancer_id == user_id
+        })
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))
+}
+
+// Offer history for a proposal, visible to both parties
+pub async fn get_proposal_offers(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let application = proposal_for_party(&pool, proposal_id, auth_user.id).await?;
+    let offers = pool.offers_for_application(application.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "offers": offers })),
+    ))
+}
+
+// Counter the standing offer; the parties take turns until one accepts
+pub async fn counter_proposal(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+    Json(payload): Json<CreateOffer>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let application = proposal_for_party(&pool, proposal_id, auth_user.id).await?;
+    if application.status != ProposalStatus::Submitted {
+        return Err(AppError::Conflict(
+            "Proposal is no longer open for negotiation".to_string(),
+        ));
+    }
 
-    // Add status filter if provided
-    if filter.status.is_some() {
-        query.push_str(" AND p.status = ?");
+    if let Some(standing) = pool.latest_offer(application.id).await? {
+        if standing.author_id == auth_user.id {
+            return Err(AppError::Conflict(
+                "Waiting for the other party to respond to your offer".to_string(),
+            ));
+        }
     }
 
-    // Add sorting
-    let sort_column = match filter.sort_by.as_str() {
-        "job.title" => "j.title",
-        _ => "p.status", // Default to status
+    let offer = pool
+        .create_offer(application.id, auth_user.id, &payload)
+        .await?;
+
+    let (recipient, sender) = if auth_user.id == application.client_id {
+        (application.freelancer_id, application.client_username)
+    } else {
+        (application.client_id, application.freelancer_username)
     };
-    query.push_str(&format!(" ORDER BY {} ASC", sort_column));
-
-    // Create query builder with complete query
-    let mut query_builder = sqlx::query_as::<_, ProposalResponse>(&query)
-        .bind(auth_user.id);
-
-    // Bind status if provided
-    if let Some(status) = &filter.status {
-        // Convert ProposalStatus to lowercase string for TEXT column
-        let status_str = match status {
-            ProposalStatus::Submitted => "submitted",
-            ProposalStatus::Accepted => "accepted",
-            ProposalStatus::Rejected => "rejected",
-        };
-        query_builder = query_builder.bind(status_str);
+    let message = format!(
+        "{} sent a counter-offer of {} for '{}'.",
+        sender.unwrap_or_else(|| "The other party".to_string()),
+        offer.amount,
+        application.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(recipient, "offer", message, application.job_id, auth_user.id),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "offer": offer })),
+    ))
+}
+
+// Accept the other party's standing offer, which hires the freelancer on those terms
+pub async fn accept_proposal_offer(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let application = proposal_for_party(&pool, proposal_id, auth_user.id).await?;
+    accept_application(&pool, &notifications, application, auth_user.id).await?;
+
+    let proposal = pool
+        .proposal(proposal_id)
+        .await?
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "proposal": proposal })),
+    ))
+}
+
+// The application behind a proposal, if the user is the freelancer who sent it
+async fn own_proposal(
+    pool: &SqlitePool,
+    proposal_id: i64,
+    user_id: i64,
+) -> Result<ApplicationDetail, AppError> {
+    pool.find_application(proposal_id)
+        .await?
+        .filter(|application| application.freelancer_id == user_id)
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))
+}
+
+// Edit a proposal that is still awaiting a decision; earlier terms are kept as revisions
+pub async fn edit_proposal(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+    Json(payload): Json<EditProposal>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let application = own_proposal(&pool, proposal_id, auth_user.id).await?;
+    if !pool.edit_application(application.id, &payload).await? {
+        return Err(AppError::Conflict(
+            "Only submitted proposals can be edited".to_string(),
+        ));
     }
 
-    // Fetch proposals
-    let proposals = query_builder
-        .fetch_all(&pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+    let message = format!(
+        "{} updated their proposal for '{}'.",
+        application
+            .freelancer_username
+            .unwrap_or_else(|| "A freelancer".to_string()),
+        application.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                application.client_id,
+                "proposal_updated",
+                message,
+                application.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    let proposal = pool
+        .proposal(proposal_id)
+        .await?
+        .ok_or(AppError::NotFound("Proposal not found".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "proposal": proposal })),
+    ))
+}
+
+pub async fn withdraw_proposal(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let application = own_proposal(&pool, proposal_id, auth_user.id).await?;
+    if !pool.withdraw_application(application.id).await? {
+        return Err(AppError::Conflict(
+            "Only submitted proposals can be withdrawn".to_string(),
+        ));
+    }
+
+    let message = format!(
+        "{} withdrew their proposal for '{}'.",
+        application
+            .freelancer_username
+            .unwrap_or_else(|| "A freelancer".to_string()),
+        application.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                application.client_id,
+                "withdrawn",
+                message,
+                application.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "message": "Proposal withdrawn" })),
+    ))
+}
+
+// Revision history for a proposal, visible to both parties
+pub async fn get_proposal_revisions(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(proposal_id): Path<i64>,
+) -> Result<impl axum::response::IntoResponse, AppError> {
+    let application = proposal_for_party(&pool, proposal_id, auth_user.id).await?;
+    let revisions = pool.revisions_for_application(application.id).await?;
 
-    // Return proposals
     Ok((
         StatusCode::OK,
-        Json(json!({ "proposals": proposals })),
+        Json(json!({ "revisions": revisions })),
     ))
 }
+
+// Periodic sweep: expire proposals on closed or past-deadline jobs and tell the freelancers
+pub async fn expire_stale_proposals(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+) -> Result<usize, AppError> {
+    let expired = pool.expire_stale_applications().await?;
+    notify_expired(pool, notifications, &expired).await?;
+    Ok(expired.len())
+}
+
+// The same for a single job its client just closed; other jobs wait for the sweep
+pub async fn expire_job_proposals(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+    job_id: i64,
+) -> Result<usize, AppError> {
+    let expired = pool.expire_job_applications(job_id).await?;
+    notify_expired(pool, notifications, &expired).await?;
+    Ok(expired.len())
+}
+
+async fn notify_expired(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+    expired: &[ProposalResponse],
+) -> Result<(), AppError> {
+    for proposal in expired {
+        notifications
+            .notify(
+                pool,
+                NewNotification {
+                    user_id: proposal.freelancer_id,
+                    message: "Your proposal expired because the job is no longer open.".to_string(),
+                    kind: "expired".to_string(),
+                    job_id: Some(proposal.job_id),
+                    actor_id: None,
+                    escrow_pda: None,
+                },
+            )
+            .await?;
+    }
+    Ok(())
+}
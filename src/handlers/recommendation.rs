@@ -0,0 +1,70 @@
+use crate::db::repo::{JobRepo, RecommendationRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::recommendation::*;
+use axum::{
+    extract::{Json, Path, Query, State},
+    response::IntoResponse,
+    Extension,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+// How many of the newest candidates get scored before ranking
+const CANDIDATE_POOL: i64 = 500;
+const DEFAULT_LIMIT: i64 = 10;
+
+// Open jobs ranked for the freelancer by skill overlap, category preference,
+// budget history and the skills of jobs they applied to or saved
+pub async fn recommend_jobs(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Query(query): Query<RecommendationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate().map_err(AppError::Validation)?;
+
+    let history = pool.freelancer_history(auth_user.id).await?;
+    let candidates = pool.job_candidates(auth_user.id, CANDIDATE_POOL).await?;
+    let jobs = rank(
+        candidates
+            .into_iter()
+            .map(|candidate| score_job(candidate, &history))
+            .collect(),
+        query.limit.unwrap_or(DEFAULT_LIMIT) as usize,
+        |job| (job.score, job.job.id),
+    );
+
+    Ok(Json(json!({ "jobs": jobs })))
+}
+
+// Freelancers worth inviting to the client's job, ranked the same way
+pub async fn suggest_freelancers(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+    Query(query): Query<RecommendationQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate().map_err(AppError::Validation)?;
+
+    if pool.job_owner(job_id).await? != Some(auth_user.id) {
+        return Err(AppError::Forbidden("You are not the job owner".into()));
+    }
+    let job = pool
+        .job_detail(job_id)
+        .await?
+        .ok_or(AppError::NotFound("Job not found".into()))?;
+
+    let candidates = pool.freelancer_candidates(job_id, CANDIDATE_POOL).await?;
+    let freelancers = rank(
+        candidates
+            .into_iter()
+            .map(|candidate| score_freelancer(candidate, job.budget as f64))
+            .collect(),
+        query.limit.unwrap_or(DEFAULT_LIMIT) as usize,
+        |freelancer| (freelancer.score, freelancer.user_id),
+    );
+
+    Ok(Json(json!({ "freelancers": freelancers })))
+}
@@ -0,0 +1,148 @@
+use crate::config::Config;
+use crate::db::pagination::PageQuery;
+use crate::db::repo::{NewNotification, ReviewRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::review::*;
+use crate::notifications::NotificationBroadcaster;
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use chrono::{NaiveDateTime, Utc};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Jobs completed at or before this have a closed review window, so their reviews
+// are public whether or not both sides wrote one
+pub fn review_cutoff(config: &Config) -> String {
+    (Utc::now().naive_utc() - config.review_window)
+        .format(TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+// Review the other side of a completed job; the review stays hidden until they
+// review back or the window closes
+pub async fn create_review(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+    Json(payload): Json<CreateReview>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let contract = pool
+        .review_contract(job_id)
+        .await?
+        .ok_or(AppError::NotFound("Job not found".into()))?;
+    let reviewee_id = match contract.freelancer_id {
+        Some(freelancer_id) if auth_user.id == contract.client_id => freelancer_id,
+        Some(freelancer_id) if auth_user.id == freelancer_id => contract.client_id,
+        _ => {
+            return Err(AppError::Forbidden(
+                "Only the job's client and hired freelancer can review it".into(),
+            ))
+        }
+    };
+    if contract.status != "completed" {
+        return Err(AppError::BadRequest(
+            "Reviews open once the job is completed".into(),
+        ));
+    }
+    let completed_at = contract
+        .completed_at
+        .as_deref()
+        .and_then(|at| NaiveDateTime::parse_from_str(at, TIMESTAMP_FORMAT).ok())
+        .ok_or(AppError::Server(
+            "Completed job has no completion time".into(),
+        ))?;
+    if Utc::now().naive_utc() - completed_at > config.review_window {
+        return Err(AppError::BadRequest(format!(
+            "The review period ({} days) has ended",
+            config.review_window.num_days()
+        )));
+    }
+
+    let review_id = match pool
+        .create_review(job_id, auth_user.id, reviewee_id, &payload)
+        .await
+    {
+        Err(AppError::Conflict(_)) => {
+            return Err(AppError::Conflict(
+                "You have already reviewed this job".into(),
+            ))
+        }
+        result => result?,
+    };
+    let review = pool
+        .job_reviews(job_id, auth_user.id, &review_cutoff(&config))
+        .await?
+        .into_iter()
+        .find(|review| review.id == review_id)
+        .ok_or(AppError::Server("Review vanished after insert".into()))?;
+
+    // The rating stays out of the message until the review is revealed
+    let message = format!(
+        "{} left you a review on '{}'. Review them back to see it.",
+        review
+            .reviewer_username
+            .clone()
+            .unwrap_or_else(|| "The other party".to_string()),
+        contract.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(reviewee_id, "reviewed", message, job_id, auth_user.id),
+        )
+        .await
+        .ok();
+
+    Ok((StatusCode::CREATED, Json(json!({ "review": review }))))
+}
+
+// Revealed reviews on the job, plus the caller's own
+pub async fn get_job_reviews(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    pool.review_contract(job_id)
+        .await?
+        .ok_or(AppError::NotFound("Job not found".into()))?;
+
+    Ok(Json(json!({
+        "reviews": pool.job_reviews(job_id, auth_user.id, &review_cutoff(&config)).await?
+    })))
+}
+
+pub async fn get_user_reviews(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    Path(user_id): Path<i64>,
+    Query(page): Query<PageQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    page.validate().map_err(AppError::Validation)?;
+    let cutoff = review_cutoff(&config);
+    let (reviews, next_cursor) = pool.reviews_for_user(user_id, &cutoff, &page).await?;
+    let reputation = pool
+        .reputations(&[user_id], &cutoff)
+        .await?
+        .remove(&user_id)
+        .unwrap_or_default();
+
+    Ok(Json(json!({
+        "reputation": reputation,
+        "reviews": reviews,
+        "next_cursor": next_cursor
+    })))
+}
@@ -0,0 +1,195 @@
+use crate::db::repo::{InvitationRepo, JobRepo, NewNotification, SavedSearchRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::job::JobVisibility;
+use crate::models::saved_search::*;
+use crate::notifications::NotificationBroadcaster;
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+// How many of the newest matches one alert run looks at per search
+const ALERT_BATCH: i64 = 100;
+
+pub async fn save_job(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    // Invite-only jobs don't exist for freelancers who weren't invited
+    match pool.job_visibility(job_id).await? {
+        None => return Err(AppError::NotFound("Job not found".into())),
+        Some(JobVisibility::InviteOnly) if !pool.is_invited(job_id, auth_user.id).await? => {
+            return Err(AppError::NotFound("Job not found".into()))
+        }
+        _ => {}
+    }
+
+    let saved = pool.save_job(auth_user.id, job_id).await?;
+    let status = if saved {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((
+        status,
+        Json(json!({ "message": "Job saved", "job_id": job_id })),
+    ))
+}
+
+pub async fn unsave_job(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(job_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    if !pool.unsave_job(auth_user.id, job_id).await? {
+        return Err(AppError::NotFound("Job is not saved".into()));
+    }
+    Ok(Json(json!({ "message": "Job removed from saved jobs" })))
+}
+
+pub async fn get_saved_jobs(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(
+        json!({ "jobs": pool.saved_jobs(auth_user.id).await? }),
+    ))
+}
+
+pub async fn create_saved_search(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Json(payload): Json<CreateSavedSearch>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let search_id = pool.create_saved_search(auth_user.id, &payload).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "saved_search": pool.find_saved_search(search_id).await? })),
+    ))
+}
+
+pub async fn get_saved_searches(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(json!({
+        "saved_searches": pool.saved_searches(auth_user.id).await?
+    })))
+}
+
+// Another user's search is reported as missing rather than forbidden
+async fn own_saved_search(
+    pool: &SqlitePool,
+    search_id: i64,
+    user_id: i64,
+) -> Result<SavedSearch, AppError> {
+    pool.find_saved_search(search_id)
+        .await?
+        .filter(|search| search.user_id == user_id)
+        .ok_or(AppError::NotFound("Saved search not found".into()))
+}
+
+pub async fn update_saved_search(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(search_id): Path<i64>,
+    Json(payload): Json<UpdateSavedSearch>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    own_saved_search(&pool, search_id, auth_user.id).await?;
+
+    pool.update_saved_search(search_id, &payload).await?;
+    Ok(Json(json!({
+        "saved_search": pool.find_saved_search(search_id).await?
+    })))
+}
+
+pub async fn delete_saved_search(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(search_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    own_saved_search(&pool, search_id, auth_user.id).await?;
+
+    pool.delete_saved_search(search_id).await?;
+    Ok(Json(json!({ "message": "Saved search deleted" })))
+}
+
+// Run every saved search with alerts on against jobs posted since its last run and
+// notify the owner once per search about the new open matches
+pub async fn alert_saved_searches(
+    pool: &SqlitePool,
+    notifications: &NotificationBroadcaster,
+) -> Result<usize, AppError> {
+    let mut alerted = 0;
+    for search in pool.searches_with_alerts().await? {
+        // Saved searches belong to freelancers, so they see what freelancers see
+        let viewer = AuthUser {
+            id: search.user_id,
+            wallet_address: None,
+            role: "freelancer".to_string(),
+            verified_wallet: false,
+            admin: None,
+            two_factor_enabled: false,
+            two_factor_at: None,
+        };
+        let mut query = search.query.0.clone();
+        query.status = Some("open".to_string());
+        query.sort_by = None;
+        query.limit = Some(ALERT_BATCH);
+        query.offset = None;
+
+        // A search can stop applying, e.g. overlap filtering once the profile drops
+        // its working hours; it is skipped until it does again
+        let response = match pool.search_jobs(&query, Some(&viewer)).await {
+            Err(AppError::BadRequest(_)) => continue,
+            response => response?,
+        };
+        let matches: Vec<_> = response
+            .jobs
+            .into_iter()
+            .map(|hit| hit.job)
+            .filter(|job| job.id > search.last_job_id)
+            .collect();
+        let Some(newest) = matches.iter().max_by_key(|job| job.id) else {
+            continue;
+        };
+
+        let message = match matches.len() {
+            1 => format!(
+                "New job matching your saved search \"{}\": {}",
+                search.name, newest.title
+            ),
+            count => format!(
+                "{} new jobs match your saved search \"{}\"",
+                count, search.name
+            ),
+        };
+        notifications
+            .notify(
+                pool,
+                NewNotification {
+                    user_id: search.user_id,
+                    message,
+                    kind: "saved_search".to_string(),
+                    job_id: Some(newest.id),
+                    actor_id: None,
+                    escrow_pda: None,
+                },
+            )
+            .await?;
+        pool.advance_saved_search(search.id, newest.id).await?;
+        alerted += 1;
+    }
+    Ok(alerted)
+}
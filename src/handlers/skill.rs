@@ -0,0 +1,24 @@
+use crate::db::repo::SkillRepo;
+use crate::error::AppError;
+use crate::models::skill::SkillQuery;
+use axum::{
+    extract::{Json, Query, State},
+    response::IntoResponse,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use validator::Validate;
+
+// Autocomplete for skill inputs; matches canonical names and aliases by prefix
+pub async fn search_skills(
+    State(pool): State<SqlitePool>,
+    Query(query): Query<SkillQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate().map_err(AppError::Validation)?;
+
+    let skills = pool
+        .autocomplete_skills(query.q.trim(), query.limit.unwrap_or(10))
+        .await?;
+
+    Ok(Json(json!({ "skills": skills })))
+}
@@ -0,0 +1,314 @@
+use crate::db::repo::{HourlyContract, NewNotification, TimesheetRepo};
+use crate::error::AppError;
+use crate::models::auth::AuthUser;
+use crate::models::timesheet::*;
+use crate::notifications::NotificationBroadcaster;
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+// The hourly contract behind an application, visible only to its client and freelancer
+async fn contract_for_party(
+    pool: &SqlitePool,
+    application_id: i64,
+    user_id: i64,
+) -> Result<HourlyContract, AppError> {
+    pool.hourly_contract(application_id)
+        .await?
+        .filter(|contract| contract.client_id == user_id || contract.freelancer_id == user_id)
+        .ok_or(AppError::NotFound("Hourly contract not found".into()))
+}
+
+// A timesheet together with its contract, for the client who pays it
+async fn timesheet_for_client(
+    pool: &SqlitePool,
+    timesheet_id: i64,
+    client_id: i64,
+) -> Result<(TimesheetResponse, HourlyContract), AppError> {
+    let not_found = || AppError::NotFound("Timesheet not found".into());
+    let timesheet = pool
+        .find_timesheet(timesheet_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let contract = pool
+        .hourly_contract(timesheet.application_id)
+        .await?
+        .filter(|contract| contract.client_id == client_id)
+        .ok_or_else(not_found)?;
+    Ok((timesheet, contract))
+}
+
+pub async fn log_time(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(application_id): Path<i64>,
+    Json(payload): Json<LogTime>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    let contract = contract_for_party(&pool, application_id, auth_user.id).await?;
+    if contract.freelancer_id != auth_user.id {
+        return Err(AppError::Forbidden(
+            "Only the hired freelancer can log time".into(),
+        ));
+    }
+    if contract.job_status == "completed" {
+        return Err(AppError::Conflict("The contract has ended".into()));
+    }
+
+    let week_start = week_of(&payload.work_date)
+        .ok_or(AppError::BadRequest("Invalid work date".into()))?
+        .to_string();
+    // Once a week is submitted its hours are fixed unless the client sends it back
+    if let Some(timesheet) = pool.timesheet_for_week(application_id, &week_start).await? {
+        if timesheet.status != TimesheetStatus::Rejected {
+            return Err(AppError::Conflict(
+                "The timesheet for that week has already been submitted".into(),
+            ));
+        }
+    }
+    let Some(entry) = pool
+        .log_time(
+            application_id,
+            &week_start,
+            contract.weekly_hour_cap,
+            &payload,
+        )
+        .await?
+    else {
+        let logged = pool.hours_logged(application_id, &week_start).await?;
+        return Err(AppError::BadRequest(format!(
+            "This would exceed the weekly cap of {} hours ({} already logged)",
+            contract.weekly_hour_cap.unwrap_or_default(),
+            logged
+        )));
+    };
+
+    Ok((StatusCode::CREATED, Json(json!({ "time_entry": entry }))))
+}
+
+pub async fn get_time_entries(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(application_id): Path<i64>,
+    Query(query): Query<TimeEntryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate().map_err(AppError::Validation)?;
+    contract_for_party(&pool, application_id, auth_user.id).await?;
+
+    let entries = pool
+        .time_entries(application_id, query.week_start.as_deref())
+        .await?;
+
+    Ok(Json(json!({ "time_entries": entries })))
+}
+
+// Freelancer submits a finished week for the client's approval
+pub async fn submit_timesheet(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(application_id): Path<i64>,
+    Json(payload): Json<SubmitTimesheet>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    let contract = contract_for_party(&pool, application_id, auth_user.id).await?;
+    if contract.freelancer_id != auth_user.id {
+        return Err(AppError::Forbidden(
+            "Only the hired freelancer can submit timesheets".into(),
+        ));
+    }
+
+    let week_start =
+        week_of(&payload.week_start).ok_or(AppError::BadRequest("Invalid week start".into()))?;
+    if week_start + Duration::days(7) > Utc::now().date_naive() {
+        return Err(AppError::BadRequest("The week hasn't ended yet".into()));
+    }
+    let week_start = week_start.to_string();
+
+    if let Some(timesheet) = pool.timesheet_for_week(application_id, &week_start).await? {
+        if timesheet.status != TimesheetStatus::Rejected {
+            return Err(AppError::Conflict(
+                "The timesheet for that week has already been submitted".into(),
+            ));
+        }
+    }
+    if pool.hours_logged(application_id, &week_start).await? == 0.0 {
+        return Err(AppError::BadRequest("No time was logged that week".into()));
+    }
+
+    let timesheet = pool.submit_timesheet(&contract, &week_start).await?;
+
+    let message = format!(
+        "{} hours for the week of {} on '{}' are waiting for your approval.",
+        timesheet.hours, timesheet.week_start, contract.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                contract.client_id,
+                "timesheet",
+                message,
+                contract.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(json!({ "timesheet": timesheet }))))
+}
+
+pub async fn get_timesheets(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(application_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    contract_for_party(&pool, application_id, auth_user.id).await?;
+
+    Ok(Json(json!({
+        "timesheets": pool.timesheets_for_application(application_id).await?
+    })))
+}
+
+pub async fn review_timesheet(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(timesheet_id): Path<i64>,
+    Json(payload): Json<ReviewTimesheet>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    let (timesheet, contract) = timesheet_for_client(&pool, timesheet_id, auth_user.id).await?;
+
+    if !pool
+        .review_timesheet(timesheet_id, payload.approve, payload.note.as_deref())
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "Timesheet is not awaiting review".into(),
+        ));
+    }
+
+    let message = if payload.approve {
+        format!(
+            "Your timesheet for the week of {} on '{}' was approved.",
+            timesheet.week_start, contract.job_title
+        )
+    } else {
+        format!(
+            "Your timesheet for the week of {} on '{}' was sent back: {}",
+            timesheet.week_start,
+            contract.job_title,
+            payload.note.as_deref().unwrap_or("no reason given")
+        )
+    };
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                contract.freelancer_id,
+                "timesheet_reviewed",
+                message,
+                contract.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    Ok(Json(json!({
+        "timesheet": pool.find_timesheet(timesheet_id).await?
+    })))
+}
+
+// Client records the escrow PDA they funded for an approved week, like a job escrow.
+// Bookkeeping only: the PDA is stored as given, not looked up on-chain
+pub async fn record_timesheet_escrow(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(timesheet_id): Path<i64>,
+    Json(payload): Json<RecordTimesheetEscrow>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+    let (timesheet, contract) = timesheet_for_client(&pool, timesheet_id, auth_user.id).await?;
+
+    if !pool
+        .record_timesheet_escrow(timesheet_id, &payload.escrow_pda)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "Escrow can only be recorded for approved timesheets".into(),
+        ));
+    }
+
+    let message = format!(
+        "The client recorded escrow {} for {} for the week of {} on '{}'.",
+        payload.escrow_pda, timesheet.amount, timesheet.week_start, contract.job_title
+    );
+    for recipient in [contract.client_id, contract.freelancer_id] {
+        notifications
+            .notify(
+                &pool,
+                NewNotification {
+                    escrow_pda: Some(payload.escrow_pda.clone()),
+                    ..NewNotification::for_job(
+                        recipient,
+                        "escrow",
+                        message.clone(),
+                        contract.job_id,
+                        auth_user.id,
+                    )
+                },
+            )
+            .await?;
+    }
+
+    Ok(Json(json!({
+        "timesheet": pool.find_timesheet(timesheet_id).await?
+    })))
+}
+
+// Client records that they released a week's escrow on-chain; also bookkeeping only
+pub async fn record_timesheet_release(
+    State(pool): State<SqlitePool>,
+    State(notifications): State<NotificationBroadcaster>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Path(timesheet_id): Path<i64>,
+) -> Result<impl IntoResponse, AppError> {
+    let (timesheet, contract) = timesheet_for_client(&pool, timesheet_id, auth_user.id).await?;
+
+    if !pool.record_timesheet_release(timesheet_id).await? {
+        return Err(AppError::Conflict(
+            "Record the escrow before its release".into(),
+        ));
+    }
+
+    let message = format!(
+        "The client released {} for the week of {} on '{}'.",
+        timesheet.amount, timesheet.week_start, contract.job_title
+    );
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(
+                contract.freelancer_id,
+                "payment_released",
+                message,
+                contract.job_id,
+                auth_user.id,
+            ),
+        )
+        .await?;
+
+    Ok(Json(json!({
+        "timesheet": pool.find_timesheet(timesheet_id).await?
+    })))
+}
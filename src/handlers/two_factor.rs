@@ -0,0 +1,298 @@
+use crate::config::Config;
+use crate::db::repo::UserRepo;
+use crate::error::AppError;
+use crate::handlers::auth::{ensure_not_locked, record_failed_login};
+use crate::models::{
+    auth::{AuthUser, LoginResponse},
+    two_factor::*,
+};
+use crate::utils::*;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use validator::Validate;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// Check a TOTP code or a single-use recovery code for a user with 2FA enabled.
+// Wrong codes count towards the same per-account lockout as wrong passwords
+pub async fn verify_second_factor(
+    pool: &SqlitePool,
+    user_id: i64,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<(), AppError> {
+    let totp_state = pool
+        .totp_state(user_id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
+
+    let secret = match (totp_state.enabled, totp_state.secret) {
+        (true, Some(secret)) => secret,
+        _ => {
+            return Err(AppError::BadRequest(
+                "Two-factor authentication is not enabled".to_string(),
+            ))
+        }
+    };
+
+    let now = Utc::now().timestamp();
+    ensure_not_locked(totp_state.locked_until, now)?;
+
+    let checked = check_second_factor(pool, user_id, &secret, code, recovery_code, now).await;
+    match checked {
+        Err(AppError::Unauthorized(_)) => record_failed_login(pool, user_id, now).await?,
+        Ok(()) if totp_state.failed_login_attempts > 0 || totp_state.locked_until.is_some() => {
+            pool.set_login_failures(user_id, 0, None).await?
+        }
+        _ => {}
+    }
+    checked
+}
+
+async fn check_second_factor(
+    pool: &SqlitePool,
+    user_id: i64,
+    secret: &str,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+    now: i64,
+) -> Result<(), AppError> {
+    if let Some(code) = code {
+        let totp = build_totp(secret, "")?;
+        let step = matching_totp_step(&totp, code, now as u64)
+            .ok_or(AppError::Unauthorized("Invalid two-factor code".to_string()))?
+            as i64;
+
+        // Edge case: The same code is replayed within its validity window
+        if !pool.use_totp_step(user_id, step).await? {
+            return Err(AppError::Unauthorized(
+                "Two-factor code already used".to_string(),
+            ));
+        }
+
+        return Ok(());
+    }
+
+    if let Some(recovery_code) = recovery_code {
+        let code_hash = hash_token(&normalize_recovery_code(recovery_code));
+        if !pool.use_recovery_code(user_id, &code_hash, now).await? {
+            return Err(AppError::Unauthorized("Invalid recovery code".to_string()));
+        }
+
+        return Ok(());
+    }
+
+    Err(AppError::BadRequest(
+        "A two-factor code or recovery code is required".to_string(),
+    ))
+}
+
+// Drop any existing recovery codes and store a fresh set, returning the plain codes once
+async fn issue_recovery_codes(pool: &SqlitePool, user_id: i64) -> Result<Vec<String>, AppError> {
+    let codes = generate_recovery_codes(RECOVERY_CODE_COUNT);
+    let code_hashes: Vec<String> = codes.iter().map(|code| hash_token(code)).collect();
+    pool.replace_recovery_codes(user_id, &code_hashes).await?;
+
+    Ok(codes)
+}
+
+pub async fn enroll_two_factor(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = pool
+        .account(auth_user.id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
+
+    if user.totp_enabled {
+        return Err(AppError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    // Label shown in the authenticator app
+    let account_name = user
+        .email
+        .or(user.wallet_address)
+        .unwrap_or_else(|| format!("user-{}", auth_user.id));
+
+    let secret = generate_totp_secret();
+    let totp = build_totp(&secret, &account_name)?;
+    let qr_code = totp
+        .get_qr_base64()
+        .map_err(|e| AppError::Server(format!("Failed to render QR code: {}", e)))?;
+
+    // Secret is stored but inactive until a code is confirmed via /2fa/enable
+    pool.start_totp_enrolment(auth_user.id, &secret).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFactorEnrollResponse {
+            secret,
+            otpauth_url: totp.get_url(),
+            qr_code,
+        }),
+    ))
+}
+
+pub async fn enable_two_factor(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let totp_state = pool
+        .totp_state(auth_user.id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
+
+    if totp_state.enabled {
+        return Err(AppError::Conflict(
+            "Two-factor authentication is already enabled".to_string(),
+        ));
+    }
+
+    let secret = totp_state.secret.ok_or(AppError::BadRequest(
+        "Start enrolment at /2fa/enroll first".to_string(),
+    ))?;
+
+    // Confirm the user's authenticator produces valid codes before switching 2FA on
+    let totp = build_totp(&secret, "")?;
+    let step = matching_totp_step(&totp, &payload.code, Utc::now().timestamp() as u64)
+        .ok_or(AppError::Unauthorized("Invalid two-factor code".to_string()))?
+        as i64;
+
+    pool.enable_totp(auth_user.id, step).await?;
+
+    let recovery_codes = issue_recovery_codes(&pool, auth_user.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(RecoveryCodesResponse {
+            message: "Two-factor authentication enabled".to_string(),
+            recovery_codes,
+        }),
+    ))
+}
+
+pub async fn disable_two_factor(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Json(payload): Json<TwoFactorVerifyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    verify_second_factor(
+        &pool,
+        auth_user.id,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await?;
+
+    pool.disable_totp(auth_user.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "message": "Two-factor authentication disabled" })),
+    ))
+}
+
+pub async fn regenerate_recovery_codes(
+    State(pool): State<SqlitePool>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    verify_second_factor(&pool, auth_user.id, Some(&payload.code), None).await?;
+    let recovery_codes = issue_recovery_codes(&pool, auth_user.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(RecoveryCodesResponse {
+            message: "Recovery codes regenerated".to_string(),
+            recovery_codes,
+        }),
+    ))
+}
+
+// Exchange the "2FA pending" token from /login plus a valid code for a full JWT
+pub async fn login_two_factor(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    Json(payload): Json<TwoFactorLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    let claims = decode_jwt(&config, &payload.pending_token)
+        .map_err(|_| AppError::Unauthorized("Invalid or expired pending token".to_string()))?;
+
+    if !claims.two_factor_pending {
+        // Edge case: A regular session token is sent instead of the pending one
+        return Err(AppError::Unauthorized("Invalid pending token".to_string()));
+    }
+
+    verify_second_factor(
+        &pool,
+        claims.user_id,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await?;
+
+    let user = pool
+        .account(claims.user_id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
+
+    let role = claims.role.clone();
+    let token = generate_two_factor_jwt(&config, claims.user_id, claims.role)
+        .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(LoginResponse {
+            message: "Logged in".to_string(),
+            token,
+            user_id: claims.user_id,
+            role,
+            wallet_user: user.wallet_user,
+            verified_wallet: user.verified_wallet,
+            email_verified: user.email_verified,
+        }),
+    ))
+}
+
+// Re-confirm 2FA from an existing session before a sensitive action
+pub async fn step_up_two_factor(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    Json(payload): Json<TwoFactorVerifyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate().map_err(AppError::Validation)?;
+
+    verify_second_factor(
+        &pool,
+        auth_user.id,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await?;
+
+    let token = generate_two_factor_jwt(&config, auth_user.id, auth_user.role.clone())
+        .map_err(|_| AppError::Server("Token generation failed".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFactorTokenResponse {
+            message: "Two-factor verified".to_string(),
+            token,
+        }),
+    ))
+}
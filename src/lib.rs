@@ -2,8 +2,13 @@ pub mod routes;
 pub mod handlers;
 pub mod models;
 pub mod config;
+pub mod state;
+pub mod cache;
+pub mod notifications;
 pub mod middleware;
 pub mod db;
 pub mod error;
 pub mod utils;
+pub mod mail;
+pub mod wallet;
 // pub mod blockchain;
\ No newline at end of file
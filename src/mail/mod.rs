@@ -0,0 +1,101 @@
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::Duration;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Delivery backend for outgoing account emails (verification, password reset)
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<(), AppError>;
+}
+
+// Shared handle kept on `AppState` and extracted in handlers via `State`
+pub type Mailer = Arc<dyn MailSender>;
+
+// Prints mails to stdout; used when no real mail provider is configured
+pub struct LogMailSender;
+
+#[async_trait]
+impl MailSender for LogMailSender {
+    async fn send(&self, mail: Mail) -> Result<(), AppError> {
+        println!(
+            "[mail] to: {}\nsubject: {}\n\n{}\n",
+            mail.to, mail.subject, mail.body
+        );
+        Ok(())
+    }
+}
+
+// Keeps every mail in memory so tests can inspect what would have been sent
+#[derive(Default)]
+pub struct InMemoryMailSender {
+    sent: Mutex<Vec<Mail>>,
+}
+
+impl InMemoryMailSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sent(&self) -> Vec<Mail> {
+        self.sent.lock().expect("mail lock poisoned").clone()
+    }
+
+    pub fn last_to(&self, to: &str) -> Option<Mail> {
+        self.sent()
+            .into_iter()
+            .rev()
+            .find(|mail| mail.to == to)
+    }
+}
+
+#[async_trait]
+impl MailSender for InMemoryMailSender {
+    async fn send(&self, mail: Mail) -> Result<(), AppError> {
+        self.sent.lock().expect("mail lock poisoned").push(mail);
+        Ok(())
+    }
+}
+
+// "1 hour", "24 hours", "90 minutes"
+fn describe_ttl(ttl: Duration) -> String {
+    let (count, unit) = if ttl.num_minutes() % 60 == 0 {
+        (ttl.num_hours(), "hour")
+    } else {
+        (ttl.num_minutes(), "minute")
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+pub fn verification_mail(to: &str, link_base: &str, token: &str, expires_in: Duration) -> Mail {
+    Mail {
+        to: to.to_string(),
+        subject: "Verify your Freelancia email".to_string(),
+        body: format!(
+            "Welcome to Freelancia!\n\nConfirm your email address by opening the link below:\n{}/verify-email?token={}\n\nThe link expires in {}.",
+            link_base,
+            token,
+            describe_ttl(expires_in)
+        ),
+    }
+}
+
+pub fn password_reset_mail(to: &str, link_base: &str, token: &str, expires_in: Duration) -> Mail {
+    Mail {
+        to: to.to_string(),
+        subject: "Reset your Freelancia password".to_string(),
+        body: format!(
+            "A password reset was requested for your Freelancia account.\n\nChoose a new password here:\n{}/reset-password?token={}\n\nThe link expires in {}. If you did not request this, you can ignore this email.",
+            link_base,
+            token,
+            describe_ttl(expires_in)
+        ),
+    }
+}
@@ -1,11 +1,19 @@
-use axum::Router;
+use axum::{middleware, Router};
 use dotenvy::dotenv;
-use freelancia_backend::handlers::auth::cleanup_blacklisted_tokens;
+use freelancia_backend::handlers::auth::{cleanup_blacklisted_tokens, cleanup_expired_nonces};
+use freelancia_backend::handlers::proposal::expire_stale_proposals;
+use freelancia_backend::handlers::saved_search::alert_saved_searches;
+use freelancia_backend::mail::{LogMailSender, Mailer};
+use freelancia_backend::middleware::request_id::{request_id, REQUEST_ID_HEADER};
+use freelancia_backend::config::Config;
+use freelancia_backend::state::AppState;
 use freelancia_backend::{db, routes};
 use http::{
-    header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+    header::{AUTHORIZATION, CONTENT_TYPE},
     Method,
 };
+use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use tower_http::cors::CorsLayer;
 
@@ -15,43 +23,101 @@ async fn main() {
     //load from .env
     dotenv().ok();
 
+    // Settings from the config file, environment and CLI flags
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    });
+
     //connect to db
-    let pool = db::init_pool()
+    let db_pool = db::DbPool::connect(&config.database_url)
         .await
         .expect("Failed to connect to database");
-    println!("connected to database");
+    println!("connected to {} database", db_pool.backend());
+
+    // Bring the schema up to date before serving anything
+    if let Err(e) = db_pool.migrate().await {
+        eprintln!("Database migration failed: {}", e);
+        std::process::exit(1);
+    }
+    if config.migrate_only {
+        println!("migrations applied");
+        return;
+    }
+
+    // The handlers still query SQLite directly; Postgres can be migrated but not served yet
+    let pool = match db_pool {
+        db::DbPool::Sqlite(pool) => pool,
+        db::DbPool::Postgres(_) => {
+            eprintln!("Serving from Postgres is not supported yet; use --migrate-only");
+            std::process::exit(1);
+        }
+    };
+
+    // Outgoing account emails are printed until a real provider is wired in
+    let mailer: Mailer = Arc::new(LogMailSender);
+
+    // Define CORS layer
+    let cors = CorsLayer::new()
+        .allow_origin(config.cors_origins.clone())
+        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+        .allow_headers([CONTENT_TYPE, AUTHORIZATION])
+        .expose_headers([REQUEST_ID_HEADER.clone()]);
+    //Define the route
+    // let app = routes::create_routes();
+    let bind_addr = config.bind_addr;
+    let state = AppState::new(pool, config, mailer);
 
     // Spawn cleanup task
-    let pool_clone = pool.clone();
+    let pool_clone = state.pool.clone();
+    let notifications = state.notifications.clone();
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(3600)); // Every hour
         loop {
             interval.tick().await;
             if let Err(e) = cleanup_blacklisted_tokens(&pool_clone).await {
-                eprintln!("Token cleanup failed: {}", e);
+                eprintln!("Token cleanup failed: {:?}", e);
+            }
+            if let Err(e) = cleanup_expired_nonces(&pool_clone).await {
+                eprintln!("Nonce cleanup failed: {:?}", e);
+            }
+            if let Err(e) = expire_stale_proposals(&pool_clone, &notifications).await {
+                eprintln!("Proposal expiry failed: {:?}", e);
+            }
+        }
+    });
+    // Saved search alerts run more often so new jobs reach freelancers quickly
+    let pool_clone = state.pool.clone();
+    let notifications = state.notifications.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(600)); // Every 10 minutes
+        loop {
+            interval.tick().await;
+            if let Err(e) = alert_saved_searches(&pool_clone, &notifications).await {
+                eprintln!("Saved search alerts failed: {:?}", e);
             }
         }
     });
-
-    // Define CORS layer
-    let cors = CorsLayer::new()
-        .allow_origin("http://localhost:5173".parse::<HeaderValue>().unwrap())
-        .allow_methods([Method::GET, Method::POST, Method::PATCH])
-        .allow_headers([CONTENT_TYPE, AUTHORIZATION]);
-    //Define the route
-    // let app = routes::create_routes();
     let app = Router::new()
-        .nest("/api", routes::create_routes(pool.clone()))
-        .nest("/api", routes::auth_routes(pool.clone()))
+        .nest("/api", routes::create_routes(state.clone()))
+        .nest("/api", routes::auth_routes(state))
+        .layer(middleware::from_fn(request_id))
         .layer(cors);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+    let listener = tokio::net::TcpListener::bind(bind_addr)
         .await
         .unwrap();
+    println!("listening on {}", bind_addr);
 
     //set the address
 
     //start the server
-    axum::serve(listener, app).await.unwrap();
+    // Peer addresses are needed by the per-IP rate limiter
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
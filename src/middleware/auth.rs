@@ -1,12 +1,19 @@
+use crate::config::Config;
+use crate::db::repo::{TokenRepo, UserRepo};
 use crate::error::AppError;
-use crate::models::{auth::AuthUser, jwt::Claims};
+use crate::models::auth::AuthUser;
+use crate::utils::decode_jwt;
 use axum::{extract::State, http::Request, middleware::Next, response::Response, Extension};
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use chrono::Utc;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
+// How long a TOTP check counts as "recent" for sensitive endpoints
+const RECENT_TWO_FACTOR_WINDOW_MINUTES: i64 = 15;
+
 pub async fn auth_middleware(
-    State(pool): axum::extract::State<SqlitePool>,
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
     mut req: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, AppError> {
@@ -27,58 +34,42 @@ pub async fn auth_middleware(
     // Note: Blacklisting is used because JWTs are stateless and cannot be invalidated
     // by modifying their exp (which requires issuing a new token the client might ignore).
     // We store tokens in blacklisted_tokens to reject them immediately until their exp.
-
-    let blacklisted = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM blacklisted_tokens WHERE token = ? AND expires_at > ?",
-    )
-    .bind(&token)
-    .bind(chrono::Utc::now().timestamp())
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| AppError::Database(e.to_string()))?;
-
-    if blacklisted > 0 {
+    let now = Utc::now().timestamp();
+    if pool.is_blacklisted(&token, now).await? {
         return Err(AppError::Unauthorized("Token is blacklisted".to_string()));
     }
 
     //clean up the expired tokens to keep the blacklisted_tokens table small
-    sqlx::query("DELETE FROM blacklisted_tokens WHERE expires_at < ?")
-        .bind(chrono::Utc::now().timestamp())
-        .execute(&pool)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-    // Decode the JWT using the secret key from environment variable
-    let claims = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(
-            std::env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set")
-                .as_ref(),
-        ),
-        &Validation::default(),
-    )
-    .map_err(|_| {
+    pool.purge_blacklisted_tokens(now).await?;
+
+    // Decode the JWT using the configured secret
+    let claims = decode_jwt(&config, &token).map_err(|_| {
         // Edge case: Invalid token (e.g., malformed, expired, or incorrect signature)
         AppError::Unauthorized("Invalid token".to_string())
-    })?
-    .claims;
+    })?;
+
+    // "2FA pending" tokens are only good for the /login/2fa exchange
+    if claims.two_factor_pending {
+        return Err(AppError::Unauthorized(
+            "Two-factor verification required".to_string(),
+        ));
+    }
 
     // Fetch user data from database using the user_id from JWT claims
-    let user = sqlx::query!(
-        "SELECT wallet_address, verified_wallet, admin FROM users WHERE id = ?",
-        claims.user_id
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        // Edge case: Database query failure (e.g., connection issues)
-        AppError::Database(e.to_string())
-    })?
-    .ok_or(AppError::Unauthorized(
-        // Edge case: User not found in database
-        "User not found".to_string(),
-    ))?;
+    let user = pool
+        .account(claims.user_id)
+        .await?
+        .ok_or(AppError::Unauthorized(
+            // Edge case: User not found in database
+            "User not found".to_string(),
+        ))?;
+
+    // Reject tokens issued before the last password change (e.g. after a reset)
+    if let Some(changed_at) = user.password_changed_at {
+        if claims.iat < changed_at {
+            return Err(AppError::Unauthorized("Token has been revoked".to_string()));
+        }
+    }
 
     // Create AuthUser struct to store authenticated user data
     let auth_user = AuthUser {
@@ -87,6 +78,8 @@ pub async fn auth_middleware(
         role: claims.role,
         verified_wallet: user.verified_wallet,
         admin: Some(user.admin),
+        two_factor_enabled: user.totp_enabled,
+        two_factor_at: claims.two_factor_at,
     };
 
     // Insert AuthUser into request extensions for downstream handlers
@@ -99,6 +92,20 @@ pub async fn auth_middleware(
     Ok(next.run(req).await)
 }
 
+// For routes anyone may call: requests without a token go through anonymously,
+// while a token that is sent still has to be valid
+pub async fn optional_auth_middleware(
+    State(pool): State<SqlitePool>,
+    State(config): State<Arc<Config>>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !req.headers().contains_key("Authorization") {
+        return Ok(next.run(req).await);
+    }
+    auth_middleware(State(pool), State(config), req, next).await
+}
+
 pub async fn freelancer_only(
     Extension(auth_user): Extension<Arc<AuthUser>>,
     req: Request<axum::body::Body>,
@@ -107,7 +114,7 @@ pub async fn freelancer_only(
     // Restrict access to users with the "freelancer" role
     if auth_user.role != "freelancer" {
         // Edge case: User attempts access with a non-freelancer role
-        return Err(AppError::Unauthorized(
+        return Err(AppError::Forbidden(
             "Freelancer role required".to_string(),
         ));
     }
@@ -124,7 +131,21 @@ pub async fn client_only(
     // Restrict access to users with the "client" role
     if auth_user.role != "client" {
         // Edge case: User attempts access with a non-client role
-        return Err(AppError::Unauthorized("Client role required".to_string()));
+        return Err(AppError::Forbidden("Client role required".to_string()));
+    }
+
+    // Proceed to the next middleware or handler
+    Ok(next.run(req).await)
+}
+
+pub async fn admin_only(
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    // Restrict access to platform admins
+    if !auth_user.admin.unwrap_or_default() {
+        return Err(AppError::Forbidden("Admin access required".to_string()));
     }
 
     // Proceed to the next middleware or handler
@@ -138,21 +159,15 @@ pub async fn wallet_verified_only(
     next: Next,
 ) -> Result<Response, AppError> {
     // Fetch verified_wallet status from database
-    let user = sqlx::query!(
-        "SELECT verified_wallet FROM users WHERE id = ?",
-        auth_user.id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| {
-        // Edge case: Database query failure (e.g., connection issues)
-        AppError::Database(e.to_string())
-    })?;
+    let user = pool
+        .account(auth_user.id)
+        .await?
+        .ok_or(AppError::Unauthorized("User not found".to_string()))?;
 
     // Require wallet verification if wallet_address is present
     if auth_user.wallet_address.is_some() && !user.verified_wallet {
         // Edge case: User has a wallet_address but it is not verified
-        return Err(AppError::Unauthorized(
+        return Err(AppError::Forbidden(
             "Wallet verification required".to_string(),
         ));
     }
@@ -160,3 +175,25 @@ pub async fn wallet_verified_only(
     // Proceed to the next middleware or handler
     Ok(next.run(req).await)
 }
+
+pub async fn recent_two_factor_only(
+    Extension(auth_user): Extension<Arc<AuthUser>>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    // 2FA is optional, so only accounts that enabled it must have passed a recent check
+    if auth_user.two_factor_enabled {
+        let cutoff = Utc::now().timestamp() - RECENT_TWO_FACTOR_WINDOW_MINUTES * 60;
+        let recent = auth_user.two_factor_at.is_some_and(|at| at >= cutoff);
+
+        if !recent {
+            // Edge case: Session is older than the window; client should call /2fa/step-up
+            return Err(AppError::Forbidden(
+                "Recent two-factor verification required".to_string(),
+            ));
+        }
+    }
+
+    // Proceed to the next middleware or handler
+    Ok(next.run(req).await)
+}
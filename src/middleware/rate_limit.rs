@@ -0,0 +1,217 @@
+use crate::error::AppError;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Auth payloads are tiny; anything bigger is rejected before parsing
+const MAX_INSPECTED_BODY_BYTES: usize = 64 * 1024;
+// Once this many keys are tracked, buckets that have fully refilled are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+// `burst` requests are allowed at once, refilled evenly over `per`
+#[derive(Debug, Clone, Copy)]
+pub struct Quota {
+    pub burst: u32,
+    pub per: Duration,
+}
+
+impl Quota {
+    pub const fn new(burst: u32, per: Duration) -> Self {
+        Self { burst, per }
+    }
+
+    // Parse "<burst>/<period>", the period in seconds or with an s, m or h
+    // suffix, e.g. "5/1m" for five requests a minute
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("`{}` must look like 5/1m", value);
+        let (burst, period) = value.trim().split_once('/').ok_or_else(invalid)?;
+        let period = period.trim();
+        let (count, unit_secs) = [("h", 3600), ("m", 60), ("s", 1)]
+            .into_iter()
+            .find_map(|(suffix, secs)| period.strip_suffix(suffix).map(|count| (count, secs)))
+            .unwrap_or((period, 1));
+
+        let burst: u32 = burst.trim().parse().map_err(|_| invalid())?;
+        let count: u64 = count.parse().map_err(|_| invalid())?;
+        if burst == 0 || count == 0 {
+            return Err("burst and period must be greater than zero".to_string());
+        }
+        Ok(Self::new(burst, Duration::from_secs(count * unit_secs)))
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.burst as f64 / self.per.as_secs_f64()
+    }
+}
+
+// Limits for one endpoint group: by client IP and, when the body names one,
+// by the email or wallet address being targeted
+#[derive(Debug, Clone, Copy)]
+pub struct EndpointLimit {
+    pub per_ip: Quota,
+    pub per_account: Option<Quota>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AuthRateLimits {
+    pub login: EndpointLimit,
+    pub signup: EndpointLimit,
+    pub nonce: EndpointLimit,
+    pub verify: EndpointLimit,
+    pub password_reset: EndpointLimit,
+}
+
+impl Default for AuthRateLimits {
+    fn default() -> Self {
+        const MINUTE: Duration = Duration::from_secs(60);
+        Self {
+            login: EndpointLimit {
+                per_ip: Quota::new(20, MINUTE),
+                per_account: Some(Quota::new(5, MINUTE)),
+            },
+            signup: EndpointLimit {
+                per_ip: Quota::new(5, Duration::from_secs(600)),
+                per_account: None,
+            },
+            nonce: EndpointLimit {
+                per_ip: Quota::new(10, MINUTE),
+                per_account: Some(Quota::new(5, MINUTE)),
+            },
+            verify: EndpointLimit {
+                per_ip: Quota::new(10, MINUTE),
+                per_account: Some(Quota::new(5, MINUTE)),
+            },
+            password_reset: EndpointLimit {
+                per_ip: Quota::new(5, MINUTE),
+                per_account: Some(Quota::new(3, Duration::from_secs(3600))),
+            },
+        }
+    }
+}
+
+struct Bucket {
+    quota: Quota,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * self.quota.refill_per_sec()).min(self.quota.burst as f64)
+    }
+}
+
+// In-process token buckets keyed by "ip:<addr>" / "account:<id>"
+pub struct RateLimiter {
+    limit: EndpointLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: EndpointLimit) -> Arc<Self> {
+        Arc::new(Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // Take one token from the key's bucket, or return how long until one is available
+    fn acquire(&self, key: String, quota: Quota) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| bucket.refilled(now) < bucket.quota.burst as f64);
+        }
+
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            quota,
+            tokens: quota.burst as f64,
+            updated_at: now,
+        });
+
+        bucket.tokens = bucket.refilled(now);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / quota.refill_per_sec();
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+
+    pub fn check_ip(&self, ip: &str) -> Result<(), Duration> {
+        self.acquire(format!("ip:{}", ip), self.limit.per_ip)
+    }
+
+    pub fn check_account(&self, account: &str) -> Result<(), Duration> {
+        match self.limit.per_account {
+            Some(quota) => self.acquire(format!("account:{}", account.to_lowercase()), quota),
+            None => Ok(()),
+        }
+    }
+}
+
+// Fields auth payloads use to name the targeted account
+#[derive(Deserialize)]
+struct RateLimitSubject {
+    email: Option<String>,
+    wallet_address: Option<String>,
+}
+
+fn too_many_requests(wait: Duration) -> AppError {
+    AppError::TooManyRequests(
+        "Too many requests, please try again later".to_string(),
+        wait.as_secs().max(1),
+    )
+}
+
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    // Peer address from `into_make_service_with_connect_info`; when deployed behind a
+    // proxy every client shares the proxy's address, so configure the proxy to limit too
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    limiter.check_ip(&ip).map_err(too_many_requests)?;
+
+    if limiter.limit.per_account.is_none() {
+        return Ok(next.run(req).await);
+    }
+
+    // Buffer the body to find the targeted email/wallet, then hand it on untouched
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_INSPECTED_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::BadRequest("Request body too large".to_string()))?;
+
+    if let Ok(subject) = serde_json::from_slice::<RateLimitSubject>(&bytes) {
+        if let Some(account) = subject.email.or(subject.wallet_address) {
+            limiter
+                .check_account(account.trim())
+                .map_err(too_many_requests)?;
+        }
+    }
+
+    Ok(next.run(Request::from_parts(parts, Body::from(bytes))).await)
+}
@@ -1,3 +1,4 @@
+use crate::wallet::WalletChain;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -27,8 +28,10 @@ pub struct SignupResponse {
 
 #[derive(Validate, Deserialize)]
 pub struct WalletConnectRequest {
-    #[validate(length(equal = 44, message = "Invalid wallet address"))]
+    #[validate(length(min = 32, max = 44, message = "Invalid wallet address"))]
     pub wallet_address: String,
+    #[serde(default)]
+    pub chain: WalletChain,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -53,12 +56,20 @@ pub struct LoginResponse {
     pub role: String,
     pub wallet_user: bool,
     pub verified_wallet: bool,
+    pub email_verified: bool,
 }
 
+// Signed like /wallet/verify, over a nonce from /wallet/request-nonce
 #[derive(Deserialize, Validate)]
 pub struct WalletLoginRequest {
-    #[validate(length(equal = 44, message = "Invalid wallet address"))]
+    #[validate(length(min = 32, max = 44, message = "Invalid wallet address"))]
     pub wallet_address: String,
+    #[serde(default)]
+    pub chain: WalletChain,
+    #[validate(length(min = 1, message = "Signature is required"))]
+    pub signature: String,
+    #[validate(length(min = 1, message = "Nonce is required"))]
+    pub nonce: String,
 }
 
 #[derive(Serialize)]
@@ -73,8 +84,10 @@ pub struct WalletLoginResponse {
 
 #[derive(Deserialize, Validate)]
 pub struct WalletSignupRequest {
-    #[validate(length(equal = 44, message = "Invalid wallet address"))]
+    #[validate(length(min = 32, max = 44, message = "Invalid wallet address"))]
     pub wallet_address: String,
+    #[serde(default)]
+    pub chain: WalletChain,
 
     #[validate(length(min = 1, message = "Role is required"))]
     pub role: String,
@@ -94,6 +107,7 @@ pub struct WalletSignupResponse {
 pub struct ProfileResponse {
     pub email: String,
     pub wallet_address: Option<String>,
+    pub wallet_chain: Option<WalletChain>,
     pub role: String,
     pub wallet_user: bool,
     pub verified_wallet: bool,
@@ -105,23 +119,30 @@ pub struct AuthUser {
     pub role: String,
     pub verified_wallet: bool,
     pub admin: Option<bool>,
+    pub two_factor_enabled: bool,
+    pub two_factor_at: Option<i64>, // from the JWT, when the user last passed a TOTP check
 }
 
 #[derive(Serialize, Deserialize, Validate)]
 pub struct NonceRequest {
-    #[validate(length(equal = 44, message = "Invalid wallet address"))]
+    #[validate(length(min = 32, max = 44, message = "Invalid wallet address"))]
     pub wallet_address: String,
+    #[serde(default)]
+    pub chain: WalletChain,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct NonceResponse {
     pub nonce: String,
+    pub message: String, // exact text the wallet must sign
 }
 
 #[derive(Serialize, Deserialize, Validate)]
 pub struct VerifyRequest {
-    #[validate(length(equal = 44, message = "Invalid wallet address"))]
+    #[validate(length(min = 32, max = 44, message = "Invalid wallet address"))]
     pub wallet_address: String,
+    #[serde(default)]
+    pub chain: WalletChain,
     #[validate(length(min = 1, message = "Signature is required"))]
     pub signature: String,
     #[validate(length(min = 1, message = "Nonce is required"))]
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use validator::{Validate, ValidationError};
+
+// A category or subcategory with the number of open public jobs filed directly under it
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i64>,
+    pub description: Option<String>,
+    pub position: i64,
+    pub job_count: i64,
+}
+
+// Listing shape: top-level categories with their subcategories; a parent's count
+// includes its subcategories' jobs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryNode {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub job_count: i64,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub subcategories: Vec<CategoryNode>,
+}
+
+// Rows arrive ordered by position, so the tree keeps that order
+pub fn category_tree(categories: Vec<Category>) -> Vec<CategoryNode> {
+    let (parents, children): (Vec<_>, Vec<_>) = categories
+        .into_iter()
+        .partition(|category| category.parent_id.is_none());
+    parents
+        .into_iter()
+        .map(|parent| {
+            let subcategories: Vec<CategoryNode> = children
+                .iter()
+                .filter(|child| child.parent_id == Some(parent.id))
+                .map(|child| CategoryNode {
+                    id: child.id,
+                    name: child.name.clone(),
+                    slug: child.slug.clone(),
+                    description: child.description.clone(),
+                    job_count: child.job_count,
+                    subcategories: Vec::new(),
+                })
+                .collect();
+            CategoryNode {
+                id: parent.id,
+                name: parent.name,
+                slug: parent.slug,
+                description: parent.description,
+                job_count: parent.job_count
+                    + subcategories.iter().map(|sub| sub.job_count).sum::<i64>(),
+                subcategories,
+            }
+        })
+        .collect()
+}
+
+// "Web Development" -> "web-development"
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn validate_slug(slug: &str) -> Result<(), ValidationError> {
+    if slug.is_empty() || slugify(slug) != slug {
+        return Err(ValidationError::new("slug_format"));
+    }
+    Ok(())
+}
+
+//DTO for adding a category; parent_id makes it a subcategory
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCategory {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    // Derived from the name when omitted
+    #[validate(custom(function = "validate_slug"), length(max = 100))]
+    pub slug: Option<String>,
+    #[validate(range(min = 1))]
+    pub parent_id: Option<i64>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    pub position: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCategory {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(custom(function = "validate_slug"), length(max = 100))]
+    pub slug: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    pub position: Option<i64>,
+}
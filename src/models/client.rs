@@ -31,8 +31,11 @@ pub struct ApprovedWorkResponse {
     pub job_id: i64,
     pub approved_at: Option<String>,
     pub applied_at: String,
-    pub freelancer_wallet: String,
+    pub freelancer_wallet: Option<String>,
     pub application_id: i64,
+    // Terms locked in at hire; the escrow is funded with agreed_amount
+    pub agreed_amount: Option<f64>,
+    pub agreed_timeline_days: Option<i64>,
     pub work_ipfs_hash: Option<String>,
     pub submitted_at: Option<String>,
     pub disputed: Option<bool>,
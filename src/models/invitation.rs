@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, Type};
+use validator::Validate;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+}
+
+//DTO for inviting a freelancer, by user id or profile username
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInvitation {
+    #[validate(range(min = 1))]
+    pub freelancer_id: Option<i64>,
+    #[validate(length(min = 1))]
+    pub username: Option<String>,
+    #[validate(length(max = 1000))]
+    pub message: Option<String>,
+}
+
+//DTO for the freelancer's answer
+#[derive(Debug, Deserialize)]
+pub struct RespondInvitation {
+    pub accept: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct InvitationResponse {
+    pub id: i64,
+    pub job_id: i64,
+    pub job_title: String,
+    pub client_id: i64,
+    pub client_username: Option<String>,
+    pub freelancer_id: i64,
+    pub freelancer_username: Option<String>,
+    pub message: Option<String>,
+    pub status: InvitationStatus,
+    pub created_at: String,
+    pub responded_at: Option<String>,
+}
@@ -1,6 +1,34 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx;
-use validator::Validate;
+use std::collections::BTreeMap;
+use validator::{Validate, ValidationError};
+
+use super::category::CategoryNode;
+use super::location::{validate_country, validate_finite, Place};
+use super::review::Reputation;
+use super::search::{FacetCount, SearchCorrection};
+use super::skill::SkillMatch;
+
+// Invite-only jobs are hidden from search for everyone but invited freelancers
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum JobVisibility {
+    #[default]
+    Public,
+    InviteOnly,
+}
+
+// Hourly jobs price the budget and bids per hour and cap the hours billed each week
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum JobType {
+    #[default]
+    Fixed,
+    Hourly,
+}
 
 #[derive(Serialize, Deserialize, Validate)]
 pub struct JobRequest {
@@ -13,9 +41,16 @@ pub struct JobRequest {
     #[validate(range(min = 0, message = "Budget must be non-negative"))]
     pub budget: i64,
     #[validate(length(min = 1, message = "Location is required"))]
-    pub location: String,
-    #[validate(length(min = 1, message = "Job type is required"))]
-    pub job_type: String,
+    pub location: String, // display label, e.g. "Berlin or remote"
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(default)]
+    #[validate(nested)]
+    pub place: Place,
+    pub job_type: JobType,
+    // Required for hourly jobs, ignored for fixed-price ones
+    #[validate(range(min = 1, max = 168, message = "Weekly hour cap must be 1-168"))]
+    pub weekly_hour_cap: Option<i64>,
     #[validate(length(min = 1, message = "IPFS hash is required"))]
     pub job_ipfs_hash: String,
     #[validate(length(min = 1, message = "Deadline is required"))]
@@ -24,6 +59,67 @@ pub struct JobRequest {
     pub category: String,
     #[validate(length(min = 1, message = "status is required"))]
     pub status: String,
+    #[serde(default)]
+    pub visibility: JobVisibility,
+}
+
+// Deadlines are ISO 8601 dates (or RFC 3339 datetimes) that haven't passed yet
+fn validate_deadline(deadline: &str) -> Result<(), ValidationError> {
+    let date = NaiveDate::parse_from_str(deadline, "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(deadline)
+                .ok()
+                .map(|d| d.date_naive())
+        })
+        .ok_or_else(|| ValidationError::new("deadline_format"))?;
+    if date < Utc::now().date_naive() {
+        return Err(ValidationError::new("deadline_in_past"));
+    }
+    Ok(())
+}
+
+// PATCH /jobs/:id; omitted fields are left as they are
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateJob {
+    #[validate(length(min = 1, message = "Title is required"))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, message = "Description is required"))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, message = "Skills are required"))]
+    pub skills: Option<String>,
+    #[validate(range(min = 0, message = "Budget must be non-negative"))]
+    pub budget: Option<i64>,
+    #[validate(length(min = 1, message = "Location is required"))]
+    pub location: Option<String>,
+    pub remote: Option<bool>,
+    // Replaces the whole structured location
+    #[validate(nested)]
+    pub place: Option<Place>,
+    pub job_type: Option<JobType>,
+    #[validate(range(min = 1, max = 168, message = "Weekly hour cap must be 1-168"))]
+    pub weekly_hour_cap: Option<i64>,
+    #[validate(length(min = 1, message = "IPFS hash is required"))]
+    pub job_ipfs_hash: Option<String>,
+    #[validate(custom(function = "validate_deadline"))]
+    pub deadline: Option<String>,
+    #[validate(length(min = 1, message = "Category is required"))]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RepostJob {
+    #[validate(custom(function = "validate_deadline"))]
+    pub deadline: String,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct JobRevision {
+    pub id: i64,
+    pub job_id: i64,
+    pub editor_id: i64,
+    pub changes: sqlx::types::Json<serde_json::Value>,
+    pub edited_at: String,
 }
 
 #[derive(Serialize, Deserialize, sqlx::FromRow)]
@@ -34,35 +130,145 @@ pub struct JobResponse {
     pub skills: String,
     pub budget: i64,
     pub location: String,
-    pub job_type: String,
+    pub remote: bool,
+    #[sqlx(flatten)]
+    pub place: Place,
+    pub job_type: JobType,
+    pub weekly_hour_cap: Option<i64>,
     pub job_ipfs_hash: String,
     pub posted_at: String,
     pub deadline: String,
     pub client_id: i64,
     pub category: String,
     pub status: String,
+    pub visibility: JobVisibility,
+}
+
+// A listed job; keyword searches add the relevance score and highlighted matches
+#[derive(Serialize, Deserialize, sqlx::FromRow)]
+pub struct JobSearchHit {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub job: JobResponse,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub relevance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub title_highlight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub snippet: Option<String>,
 }
 
+// What anonymous visitors see of a listed job: no client identity, IPFS hash or
+// exact coordinates, and only open public jobs make it here
 #[derive(Serialize, Deserialize)]
-pub struct JobsResponse {
-    pub jobs: Vec<JobResponse>,
+pub struct PublicJob {
+    pub id: i64,
+    pub title: String,
+    pub description: String,
+    pub skills: String,
+    pub budget: i64,
+    pub location: String,
+    pub remote: bool,
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub timezone: Option<String>,
+    pub job_type: JobType,
+    pub weekly_hour_cap: Option<i64>,
+    pub posted_at: String,
+    pub deadline: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub relevance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub title_highlight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub snippet: Option<String>,
 }
 
-// Query parameters for filtering jobs
-#[derive(Debug, Deserialize, Validate)]
+impl From<JobSearchHit> for PublicJob {
+    fn from(hit: JobSearchHit) -> Self {
+        let job = hit.job;
+        PublicJob {
+            id: job.id,
+            title: job.title,
+            description: job.description,
+            skills: job.skills,
+            budget: job.budget,
+            location: job.location,
+            remote: job.remote,
+            country: job.place.country,
+            city: job.place.city,
+            timezone: job.place.timezone,
+            job_type: job.job_type,
+            weekly_hour_cap: job.weekly_hour_cap,
+            posted_at: job.posted_at,
+            deadline: job.deadline,
+            category: job.category,
+            relevance: hit.relevance,
+            title_highlight: hit.title_highlight,
+            snippet: hit.snippet,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct JobsResponse<J = JobSearchHit> {
+    pub jobs: Vec<J>,
+    // Pass back as `cursor` for the next page; null on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    // Misspelled keywords that were also searched as their closest indexed term
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub corrections: Vec<SearchCorrection>,
+    // Matches across all pages, when `include_total` is set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub total: Option<i64>,
+    // Requested facets by name; each counts the matches with every other filter
+    // applied but its own, so the other values stay selectable
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub facets: BTreeMap<String, Vec<FacetCount>>,
+}
+
+impl JobsResponse {
+    // The same page with every job cut down to its public fields
+    pub fn redacted(self) -> JobsResponse<PublicJob> {
+        JobsResponse {
+            jobs: self.jobs.into_iter().map(PublicJob::from).collect(),
+            next_cursor: self.next_cursor,
+            corrections: self.corrections,
+            total: self.total,
+            facets: self.facets,
+        }
+    }
+}
+
+// Query parameters for filtering jobs; also stored as a saved search
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
 pub struct JobFilterQuery {
     #[validate(range(min = 1, message = "Id must be non-negative"))]
     pub id: Option<u64>,
     #[validate(length(min = 1))]
-    pub keyword: Option<String>, //Search in title/description
+    pub keyword: Option<String>, // full-text search; see SearchQuery::parse for the syntax
     #[validate(range(min = 0))]
     pub min_budget: Option<i32>, // Minimum budget
     #[validate(range(min = 0))]
     pub max_budget: Option<i32>, // maximum budget
     #[validate(length(min = 1))]
-    pub skills: Option<String>, // filter by skills
+    pub skills: Option<String>, // comma-separated, matched exactly against the taxonomy
+    pub skills_match: Option<SkillMatch>, // "any" (default) or "all" of the listed skills
     #[validate(length(min = 1))]
-    pub location: Option<String>, //filter by location
+    pub location: Option<String>, // the location label or city, case-insensitive
+    pub remote: Option<bool>,             // true: remote jobs only; false: on-site only
+    #[validate(custom(function = "validate_country"))]
+    pub country: Option<String>, // ISO 3166-1 alpha-2, e.g. "DE"
+    #[validate(range(min = -90.0, max = 90.0), custom(function = "validate_finite"))]
+    pub lat: Option<f64>,
+    #[validate(range(min = -180.0, max = 180.0), custom(function = "validate_finite"))]
+    pub lng: Option<f64>,
+    #[validate(range(min = 1.0, max = 1000.0), custom(function = "validate_finite"))]
+    pub radius_km: Option<f64>, // with lat and lng: jobs placed within this distance
+    #[validate(range(min = 0.5, max = 24.0), custom(function = "validate_finite"))]
+    pub min_overlap_hours: Option<f64>, // working hours shared with the viewer's profile
     #[validate(length(min = 1))]
     pub job_type: Option<String>, //filter by job type
     #[validate(range(min = 1))]
@@ -80,22 +286,36 @@ pub struct JobFilterQuery {
     #[validate(length(min = 1))]
     pub status: Option<String>, // e.g., "open"
     #[validate(length(min = 1))]
-    pub sort_by: Option<String>, // sort by e.g. "budget:asc"
+    pub sort_by: Option<String>, // e.g. "budget:asc"; keyword searches default to "relevance"
     #[validate(range(min = 1, max = 100))]
     pub limit: Option<i64>, //pagination: max 100
     #[validate(range(min = 0))]
-    pub offset: Option<i64>, // Pagination offset
+    pub offset: Option<i64>, // Pagination offset; ignored when a cursor is given
+    #[validate(length(min = 1))]
+    pub cursor: Option<String>, // next_cursor from the previous page
+    pub include_total: Option<bool>, // count matches across all pages
+    #[validate(length(min = 1))]
+    pub facets: Option<String>, // e.g. "category,budget" or "all"
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Categories {
-    pub categories: Vec<String>,
+    pub categories: Vec<CategoryNode>,
 }
 
 #[derive(Deserialize, Validate)]
 pub struct ApplyJobPayload {
     #[validate(range(min = 1, message = "Id must be non-negative"))]
     pub job_id: i64,
+    #[validate(length(min = 10, max = 1000))]
+    pub cover_letter: Option<String>,
+    #[validate(range(min = 1.0))]
+    pub bid_amount: Option<f64>,
+    #[validate(range(min = 1, max = 365))]
+    pub timeline_days: Option<i64>,
+    // Where payment is released; defaults to the freelancer's connected wallet
+    #[validate(length(min = 32, max = 64))]
+    pub payout_wallet: Option<String>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -122,7 +342,12 @@ pub struct ApplicantResponse {
     pub applied_at: String,
     pub approved: Option<bool>,
     pub approved_at: Option<String>,
-    pub freelancer_wallet: String,
+    pub freelancer_wallet: Option<String>,
+    pub cover_letter: Option<String>,
+    pub bid_amount: Option<f64>,
+    pub timeline_days: Option<i64>,
+    pub status: String,
+    pub reputation: Reputation,
 }
 #[derive(serde::Serialize)]
 pub struct MyJobsResponse {
@@ -4,6 +4,12 @@ use serde::{Serialize, Deserialize};
 pub struct Claims {
     pub user_id: i64,
     pub role: String, // "freelancer" or "client"
+    #[serde(default)]
+    pub iat: i64, // issued at, compared against users.password_changed_at
+    #[serde(default)]
+    pub two_factor_pending: bool, // password checked, TOTP code still owed
+    #[serde(default)]
+    pub two_factor_at: Option<i64>, // when a TOTP/recovery code was last verified
     pub exp: i64,
 }
 
@@ -0,0 +1,157 @@
+use chrono::{NaiveTime, Offset, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+// Where a job or freelancer is and when they work. Every part is optional; working
+// hours are local "HH:MM" times in `timezone`, and an end before the start runs
+// past midnight
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate, FromRow)]
+#[validate(schema(function = "validate_place"))]
+pub struct Place {
+    #[validate(custom(function = "validate_country"))]
+    pub country: Option<String>, // ISO 3166-1 alpha-2, e.g. "DE"
+    #[validate(length(min = 1, max = 100))]
+    pub city: Option<String>,
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: Option<f64>,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: Option<f64>,
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>, // IANA name, e.g. "Europe/Berlin"
+    #[validate(custom(function = "validate_time_of_day"))]
+    pub work_start: Option<String>,
+    #[validate(custom(function = "validate_time_of_day"))]
+    pub work_end: Option<String>,
+}
+
+// Working hours in UTC: minutes past midnight and how long they last
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtcWindow {
+    pub start: i64,
+    pub minutes: i64,
+}
+
+pub fn validate_country(country: &str) -> Result<(), ValidationError> {
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(ValidationError::new("country_code"));
+    }
+    Ok(())
+}
+
+// Range checks let NaN through, and these values end up in SQL
+pub fn validate_finite(value: f64) -> Result<(), ValidationError> {
+    if !value.is_finite() {
+        return Err(ValidationError::new("not_finite"));
+    }
+    Ok(())
+}
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<Tz>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("timezone"))
+}
+
+fn validate_time_of_day(time: &str) -> Result<(), ValidationError> {
+    minute_of_day(time)
+        .map(|_| ())
+        .ok_or_else(|| ValidationError::new("time_format"))
+}
+
+// Coordinates come in pairs, and working hours need both ends and a timezone
+fn validate_place(place: &Place) -> Result<(), ValidationError> {
+    if place.latitude.is_some() != place.longitude.is_some() {
+        return Err(ValidationError::new("coordinates_incomplete"));
+    }
+    let hours = [&place.work_start, &place.work_end];
+    if hours.iter().any(|time| time.is_some())
+        && (hours.iter().any(|time| time.is_none()) || place.timezone.is_none())
+    {
+        return Err(ValidationError::new("working_hours_incomplete"));
+    }
+    Ok(())
+}
+
+fn minute_of_day(time: &str) -> Option<i64> {
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    Some(i64::from(time.hour() * 60 + time.minute()))
+}
+
+impl Place {
+    pub fn normalize(&mut self) {
+        self.country = self.country.as_ref().map(|c| c.to_ascii_uppercase());
+        self.city = self.city.as_ref().map(|c| c.trim().to_string());
+    }
+
+    // Uses the timezone's current UTC offset, so the window follows daylight saving
+    // only when the place is saved again
+    pub fn utc_window(&self) -> Option<UtcWindow> {
+        let timezone: Tz = self.timezone.as_deref()?.parse().ok()?;
+        let start = minute_of_day(self.work_start.as_deref()?)?;
+        let end = minute_of_day(self.work_end.as_deref()?)?;
+        let offset = i64::from(
+            Utc::now()
+                .with_timezone(&timezone)
+                .offset()
+                .fix()
+                .local_minus_utc(),
+        ) / 60;
+        let minutes = match (end - start).rem_euclid(MINUTES_PER_DAY) {
+            0 => MINUTES_PER_DAY,
+            minutes => minutes,
+        };
+        Some(UtcWindow {
+            start: (start - offset).rem_euclid(MINUTES_PER_DAY),
+            minutes,
+        })
+    }
+}
+
+// SQL for the minutes a stored window (`start`, `minutes` columns) shares with
+// `other`, counting `other` on the day before and after as well for windows that
+// cross midnight UTC
+pub fn overlap_sql(start: &str, minutes: &str, other: UtcWindow) -> String {
+    let terms: Vec<String> = [-MINUTES_PER_DAY, 0, MINUTES_PER_DAY]
+        .iter()
+        .map(|shift| {
+            let from = other.start + shift;
+            format!(
+                "MAX(0, MIN({start} + {minutes}, {to}) - MAX({start}, {from}))",
+                start = start,
+                minutes = minutes,
+                to = from + other.minutes,
+                from = from
+            )
+        })
+        .collect();
+    format!("({})", terms.join(" + "))
+}
+
+// SQL matching coordinates (`lat`, `lng` columns) within `radius_km` of a point.
+// Uses the equirectangular approximation, which needs no trigonometry in SQL and is
+// close enough at these distances; it does not wrap across the antimeridian
+pub fn within_radius_sql(lat: &str, lng: &str, point: (f64, f64), radius_km: f64) -> String {
+    const KM_PER_DEGREE_LATITUDE: f64 = 110.574;
+    let (latitude, longitude) = point;
+    let km_per_degree_longitude = 111.320 * latitude.to_radians().cos();
+    let degrees = radius_km / KM_PER_DEGREE_LATITUDE;
+    format!(
+        "{lat} BETWEEN {min} AND {max}
+         AND (({lat} - {latitude}) * {ky}) * (({lat} - {latitude}) * {ky})
+           + (({lng} - {longitude}) * {kx}) * (({lng} - {longitude}) * {kx}) <= {r2}",
+        lat = lat,
+        lng = lng,
+        min = latitude - degrees,
+        max = latitude + degrees,
+        latitude = latitude,
+        longitude = longitude,
+        ky = KM_PER_DEGREE_LATITUDE,
+        kx = km_per_degree_longitude,
+        r2 = radius_km * radius_km
+    )
+}
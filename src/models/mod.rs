@@ -1,9 +1,20 @@
+pub mod account;
 pub mod auth;
 pub mod bid;
+pub mod category;
 pub mod client;
 pub mod escrow;
 pub mod freelancer;
+pub mod invitation;
 pub mod job;
 pub mod jwt;
+pub mod location;
 pub mod profile;
 pub mod proposal;
+pub mod recommendation;
+pub mod review;
+pub mod saved_search;
+pub mod search;
+pub mod skill;
+pub mod timesheet;
+pub mod two_factor;
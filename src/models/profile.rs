@@ -1,3 +1,5 @@
+use super::location::Place;
+use super::review::Reputation;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use validator::Validate;
@@ -21,6 +23,11 @@ pub struct UserProfilePayload {
 
     #[validate(length(min = 1, message = "profile ipfs hash is required"))]
     pub profile_ipfs_hash: String,
+
+    // Timezone and working hours let clients find overlapping freelancers
+    #[serde(default)]
+    #[validate(nested)]
+    pub place: Place,
 }
 
 #[derive(Serialize)]
@@ -38,7 +45,11 @@ pub struct ProfileResponseByIdOrUsername {
     pub certifications: Option<String>,
     pub work_history: Option<String>,
     pub profile_ipfs_hash: Option<String>,
+    #[sqlx(flatten)]
+    pub place: Place,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    #[sqlx(skip)]
+    pub reputation: Reputation,
 }
 
@@ -2,26 +2,30 @@ use serde::{Deserialize, Serialize};
 use sqlx::{prelude::FromRow, Type};
 use validator::Validate;
 
-//Proposal model for database operations
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Proposal {
-    pub id: i64,
-    pub job_id: i64,
-    pub freelancer_id: i64,
-    pub cover_letter: String,
-    pub bid_amount: f64,
-    pub status: ProposalStatus,
-    pub created_at: i64, //Unix timestamp
-}
+use super::job::ApplyJobPayload;
 
-//Enum for proposal status
-#[derive(Debug, Serialize, Deserialize, Type, PartialEq)]
+// Proposals are job applications that carry terms; the status lives on job_applications
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
 #[serde(rename_all = "lowercase")] // added for query param deserialization
 #[sqlx(type_name = "TEXT", rename_all = "lowercase")]
 pub enum ProposalStatus {
     Submitted,
     Accepted,
     Rejected,
+    Withdrawn, // pulled by the freelancer
+    Expired,   // job closed or its deadline passed while still submitted
+}
+
+impl ProposalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Submitted => "submitted",
+            ProposalStatus::Accepted => "accepted",
+            ProposalStatus::Rejected => "rejected",
+            ProposalStatus::Withdrawn => "withdrawn",
+            ProposalStatus::Expired => "expired",
+        }
+    }
 }
 
 //DTO for creating a proposal
@@ -33,6 +37,23 @@ pub struct CreateProposal {
     pub cover_letter: String,
     #[validate(range(min = 1.0))]
     pub bid_amount: f64,
+    #[validate(range(min = 1, max = 365))]
+    pub timeline_days: Option<i64>,
+    #[validate(length(min = 32, max = 64))]
+    pub payout_wallet: Option<String>,
+}
+
+// A proposal is an application with the cover letter and bid filled in
+impl From<CreateProposal> for ApplyJobPayload {
+    fn from(proposal: CreateProposal) -> Self {
+        ApplyJobPayload {
+            job_id: proposal.job_id,
+            cover_letter: Some(proposal.cover_letter),
+            bid_amount: Some(proposal.bid_amount),
+            timeline_days: proposal.timeline_days,
+            payout_wallet: proposal.payout_wallet,
+        }
+    }
 }
 
 //DTO for updating proposal status
@@ -41,16 +62,69 @@ pub struct UpdateProposal {
     pub status: ProposalStatus // Must be accepted or rejected
 }
 
+//DTO for a freelancer editing a submitted proposal; omitted fields are kept
+#[derive(Debug, Deserialize, Validate)]
+pub struct EditProposal {
+    #[validate(length(min = 10, max = 1000))]
+    pub cover_letter: Option<String>,
+    #[validate(range(min = 1.0))]
+    pub bid_amount: Option<f64>,
+    #[validate(range(min = 1, max = 365))]
+    pub timeline_days: Option<i64>,
+    #[validate(length(min = 32, max = 64))]
+    pub payout_wallet: Option<String>,
+}
+
+// The terms of a proposal as they were before an edit
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProposalRevision {
+    pub id: i64,
+    pub application_id: i64,
+    pub cover_letter: Option<String>,
+    pub bid_amount: Option<f64>,
+    pub timeline_days: Option<i64>,
+    pub freelancer_wallet: Option<String>,
+    pub revised_at: String,
+}
+
 //DTO for response
 #[derive(Debug, Serialize, FromRow)]
 pub struct ProposalResponse {
     pub id: i64,
     pub job_id: i64,
     pub freelancer_id: i64,
-    pub cover_letter: String,
-    pub bid_amount: f64,
+    pub cover_letter: Option<String>,
+    pub bid_amount: Option<f64>,
+    pub timeline_days: Option<i64>,
+    pub freelancer_wallet: Option<String>,
     pub status: ProposalStatus,
-    pub created_at: i64,
+    pub applied_at: String,
+    pub agreed_amount: Option<f64>,
+    pub agreed_timeline_days: Option<i64>,
+    pub updated_at: Option<String>,
+}
+
+// One bid or counter-offer in a proposal's negotiation thread
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProposalOffer {
+    pub id: i64,
+    pub application_id: i64,
+    pub author_id: i64,
+    pub amount: f64,
+    pub timeline_days: Option<i64>,
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+//DTO for a counter-offer from either party
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOffer {
+    #[validate(range(min = 1.0))]
+    pub amount: f64,
+    #[validate(range(min = 1, max = 365))]
+    pub timeline_days: Option<i64>,
+    #[validate(length(max = 1000))]
+    pub message: Option<String>,
 }
 
 //Query params for filtering freelancer proposals 
@@ -63,4 +137,4 @@ pub struct ProposalFilter{
 
 fn default_sort_by() -> String{
     "status".to_string()
-}
\ No newline at end of file
+}
@@ -0,0 +1,172 @@
+use crate::models::job::JobResponse;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::HashMap;
+use validator::Validate;
+
+const SKILLS_WEIGHT: f64 = 0.5;
+const CATEGORY_WEIGHT: f64 = 0.2;
+const BUDGET_WEIGHT: f64 = 0.15;
+const HISTORY_WEIGHT: f64 = 0.15;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecommendationQuery {
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<i64>,
+}
+
+// Each signal in 0..=1; `history` is the freelancer's past interactions when ranking
+// jobs and their hiring track record when ranking freelancers
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MatchScore {
+    pub skills: f64,
+    pub category: f64,
+    pub budget: f64,
+    pub history: f64,
+}
+
+impl MatchScore {
+    pub fn total(&self) -> f64 {
+        let total = self.skills * SKILLS_WEIGHT
+            + self.category * CATEGORY_WEIGHT
+            + self.budget * BUDGET_WEIGHT
+            + self.history * HISTORY_WEIGHT;
+        (total * 1000.0).round() / 1000.0
+    }
+}
+
+// What a freelancer's past activity says about the work they go for
+#[derive(Debug, Default)]
+pub struct FreelancerHistory {
+    // Jobs applied to or saved, per category
+    pub category_counts: HashMap<i64, i64>,
+    // Average agreed amount, bid or budget across their applications
+    pub typical_budget: Option<f64>,
+}
+
+// An open job with how its skills line up against the freelancer's
+#[derive(FromRow)]
+pub struct JobCandidate {
+    #[sqlx(flatten)]
+    pub job: JobResponse,
+    pub category_id: Option<i64>,
+    pub skill_count: i64,
+    pub profile_matches: i64,
+    // Skills also seen on jobs the freelancer applied to or saved
+    pub history_matches: i64,
+    pub matched_skills: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct FreelancerCandidate {
+    pub user_id: i64,
+    pub username: String,
+    pub skills: Option<String>,
+    pub matched_skills: Option<String>,
+    pub profile_matches: i64,
+    pub job_skill_count: i64,
+    pub applications: i64,
+    pub category_applications: i64,
+    pub hires: i64,
+    pub typical_budget: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct JobRecommendation {
+    #[serde(flatten)]
+    pub job: JobResponse,
+    pub score: f64,
+    pub breakdown: MatchScore,
+    pub matched_skills: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FreelancerRecommendation {
+    pub user_id: i64,
+    pub username: String,
+    pub skills: Option<String>,
+    pub hires: i64,
+    pub score: f64,
+    pub breakdown: MatchScore,
+    pub matched_skills: Vec<String>,
+}
+
+fn ratio(part: i64, whole: i64) -> f64 {
+    if whole <= 0 {
+        return 0.0;
+    }
+    (part as f64 / whole as f64).min(1.0)
+}
+
+// 1 at the usual amount, falling to 0 at four times (or a quarter of) it; neutral
+// without any history to compare against
+pub fn budget_fit(budget: f64, typical: Option<f64>) -> f64 {
+    match typical {
+        Some(typical) if typical > 0.0 && budget > 0.0 => {
+            (1.0 - (budget / typical).log2().abs() / 2.0).max(0.0)
+        }
+        _ => 0.5,
+    }
+}
+
+fn split_names(names: Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = names
+        .map(|names| names.split(", ").map(String::from).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+pub fn score_job(candidate: JobCandidate, history: &FreelancerHistory) -> JobRecommendation {
+    let interactions: i64 = history.category_counts.values().sum();
+    let category_interactions = candidate
+        .category_id
+        .and_then(|id| history.category_counts.get(&id))
+        .copied()
+        .unwrap_or(0);
+    let breakdown = MatchScore {
+        skills: ratio(candidate.profile_matches, candidate.skill_count),
+        category: ratio(category_interactions, interactions),
+        budget: budget_fit(candidate.job.budget as f64, history.typical_budget),
+        history: ratio(candidate.history_matches, candidate.skill_count),
+    };
+    JobRecommendation {
+        job: candidate.job,
+        score: breakdown.total(),
+        breakdown,
+        matched_skills: split_names(candidate.matched_skills),
+    }
+}
+
+pub fn score_freelancer(
+    candidate: FreelancerCandidate,
+    job_budget: f64,
+) -> FreelancerRecommendation {
+    let breakdown = MatchScore {
+        skills: ratio(candidate.profile_matches, candidate.job_skill_count),
+        category: ratio(candidate.category_applications, candidate.applications),
+        budget: budget_fit(job_budget, candidate.typical_budget),
+        // Saturates: the first few hires matter most
+        history: candidate.hires as f64 / (candidate.hires as f64 + 2.0),
+    };
+    FreelancerRecommendation {
+        user_id: candidate.user_id,
+        username: candidate.username,
+        skills: candidate.skills,
+        hires: candidate.hires,
+        score: breakdown.total(),
+        breakdown,
+        matched_skills: split_names(candidate.matched_skills),
+    }
+}
+
+// Highest score first, newest id breaking ties, cut to `limit`
+pub fn rank<T>(mut items: Vec<T>, limit: usize, key: impl Fn(&T) -> (f64, i64)) -> Vec<T> {
+    items.sort_by(|a, b| {
+        let (a_score, a_id) = key(a);
+        let (b_score, b_id) = key(b);
+        b_score.total_cmp(&a_score).then(b_id.cmp(&a_id))
+    });
+    items.truncate(limit);
+    items
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use validator::Validate;
+
+//DTO for reviewing the other side of a completed job
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReview {
+    #[validate(range(min = 1, max = 5))]
+    pub rating: i64,
+    #[validate(length(max = 2000))]
+    pub review: Option<String>,
+    #[validate(length(min = 1, message = "review ipfs hash is required"))]
+    pub review_ipfs_hash: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Review {
+    pub id: i64,
+    pub job_id: i64,
+    pub job_title: String,
+    pub reviewer_id: i64,
+    pub reviewer_username: Option<String>,
+    pub reviewee_id: i64,
+    pub rating: i64,
+    pub review: Option<String>,
+    pub review_ipfs_hash: String,
+    pub created_at: String,
+}
+
+// The two parties to a job that can review each other
+#[derive(Debug, FromRow)]
+pub struct ReviewContract {
+    pub job_id: i64,
+    pub job_title: String,
+    pub status: String,
+    pub completed_at: Option<String>,
+    pub client_id: i64,
+    pub freelancer_id: Option<i64>,
+}
+
+// A user's track record across the contracts they were hired on or hired for.
+// Only reviews that are already visible count toward the rating
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Reputation {
+    pub average_rating: Option<f64>,
+    pub review_count: i64,
+    pub contracts: i64,
+    // Share of finished contracts (completed or cancelled) that were completed
+    pub completion_rate: Option<f64>,
+    // Share of contracts that went to dispute, however it was resolved
+    pub dispute_rate: Option<f64>,
+}
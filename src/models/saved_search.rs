@@ -0,0 +1,43 @@
+use crate::models::job::{JobFilterQuery, JobResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::Json};
+use validator::Validate;
+
+#[derive(Serialize, FromRow)]
+pub struct SavedJob {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub job: JobResponse,
+    pub saved_at: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub query: Json<JobFilterQuery>,
+    pub alerts: bool,
+    // Newest job id already checked against the search
+    #[serde(skip)]
+    pub last_job_id: i64,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+}
+
+//DTO for saving the current job filters; alerts default to on
+#[derive(Deserialize, Validate)]
+pub struct CreateSavedSearch {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(nested)]
+    pub query: JobFilterQuery,
+    pub alerts: Option<bool>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateSavedSearch {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub alerts: Option<bool>,
+}
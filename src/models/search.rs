@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Longer queries are cut off rather than handed to FTS5 whole
+const MAX_TERMS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTerm {
+    Word(String),
+    // `dev*`
+    Prefix(String),
+    // `"rest api"`, or a word the tokenizer splits such as `node.js`
+    Phrase(Vec<String>),
+}
+
+// A keyword search as (a AND b) OR (c) ... minus any excluded terms. User input
+// never reaches FTS5 directly: every term is re-quoted from its alphanumeric parts
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    groups: Vec<Vec<SearchTerm>>,
+    excluded: Vec<SearchTerm>,
+}
+
+// A query word with no hits and the indexed term it was widened to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchCorrection {
+    pub term: String,
+    pub suggestion: String,
+}
+
+fn words_of(raw: &str) -> Vec<String> {
+    raw.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn term_of(raw: &str) -> Option<SearchTerm> {
+    let prefix = raw.ends_with('*');
+    let mut words = words_of(raw);
+    match words.len() {
+        0 => None,
+        1 if prefix => words.pop().map(SearchTerm::Prefix),
+        1 => words.pop().map(SearchTerm::Word),
+        _ => Some(SearchTerm::Phrase(words)),
+    }
+}
+
+impl SearchQuery {
+    // Supports "phrases", prefix*, AND / OR / NOT and -excluded; anything else is
+    // treated as plain words. Fails when nothing is left to search for
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut query = SearchQuery::default();
+        let mut current: Vec<SearchTerm> = Vec::new();
+        let mut negate_next = false;
+        let mut terms = 0;
+        let mut chars = raw.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if terms >= MAX_TERMS {
+                break;
+            }
+            if c.is_whitespace() || c == '(' || c == ')' {
+                chars.next();
+                continue;
+            }
+
+            let negated = std::mem::take(&mut negate_next) | (c == '-');
+            if c == '-' {
+                chars.next();
+            }
+            let term = if chars.peek() == Some(&'"') {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                match words_of(&phrase) {
+                    words if words.is_empty() => None,
+                    mut words if words.len() == 1 => words.pop().map(SearchTerm::Word),
+                    words => Some(SearchTerm::Phrase(words)),
+                }
+            } else {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' || c == '(' || c == ')' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                match token.as_str() {
+                    "OR" if !negated => {
+                        if !current.is_empty() {
+                            query.groups.push(std::mem::take(&mut current));
+                        }
+                        continue;
+                    }
+                    "AND" if !negated => continue,
+                    "NOT" if !negated => {
+                        negate_next = true;
+                        continue;
+                    }
+                    _ => term_of(&token),
+                }
+            };
+
+            if let Some(term) = term {
+                terms += 1;
+                if negated {
+                    query.excluded.push(term);
+                } else {
+                    current.push(term);
+                }
+            }
+        }
+        if !current.is_empty() {
+            query.groups.push(current);
+        }
+
+        if query.groups.is_empty() {
+            return Err("Search needs at least one word to look for".to_string());
+        }
+        Ok(query)
+    }
+
+    // Plain words, the only terms typo correction applies to
+    pub fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        for term in self.groups.iter().flatten() {
+            if let SearchTerm::Word(word) = term {
+                if !words.contains(word) {
+                    words.push(word.clone());
+                }
+            }
+        }
+        words
+    }
+
+    // The FTS5 MATCH expression, with each corrected word widened to
+    // ("word" OR "suggestion")
+    pub fn to_fts(&self, corrections: &HashMap<String, String>) -> String {
+        let term = |term: &SearchTerm| match term {
+            SearchTerm::Word(word) => match corrections.get(word) {
+                Some(suggestion) => format!("(\"{}\" OR \"{}\")", word, suggestion),
+                None => format!("\"{}\"", word),
+            },
+            SearchTerm::Prefix(prefix) => format!("\"{}\"*", prefix),
+            SearchTerm::Phrase(words) => format!("\"{}\"", words.join(" ")),
+        };
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let terms: Vec<String> = group.iter().map(term).collect();
+                format!("({})", terms.join(" AND "))
+            })
+            .collect();
+        let mut expression = groups.join(" OR ");
+        if !self.excluded.is_empty() {
+            let excluded: Vec<String> = self.excluded.iter().map(term).collect();
+            expression = format!("({}) NOT ({})", expression, excluded.join(" OR "));
+        }
+        expression
+    }
+
+    // The same expression for Postgres' to_tsquery: & and | for AND and OR,
+    // `word:*` for prefixes and <-> between the words of a phrase
+    pub fn to_tsquery(&self, corrections: &HashMap<String, String>) -> String {
+        let term = |term: &SearchTerm| match term {
+            SearchTerm::Word(word) => match corrections.get(word) {
+                Some(suggestion) => format!("('{}' | '{}')", word, suggestion),
+                None => format!("'{}'", word),
+            },
+            SearchTerm::Prefix(prefix) => format!("'{}':*", prefix),
+            SearchTerm::Phrase(words) => {
+                let words: Vec<String> = words.iter().map(|w| format!("'{}'", w)).collect();
+                format!("({})", words.join(" <-> "))
+            }
+        };
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let terms: Vec<String> = group.iter().map(term).collect();
+                format!("({})", terms.join(" & "))
+            })
+            .collect();
+        let mut expression = groups.join(" | ");
+        if !self.excluded.is_empty() {
+            let excluded: Vec<String> = self.excluded.iter().map(term).collect();
+            expression = format!("({}) & !({})", expression, excluded.join(" | "));
+        }
+        expression
+    }
+}
+
+// Levenshtein distance that counts swapping two adjacent letters as one edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+// Short words are too easy to "correct" into something else entirely
+pub fn max_typos(word: &str) -> usize {
+    match word.chars().count() {
+        0..=4 => 0,
+        5..=7 => 1,
+        _ => 2,
+    }
+}
+
+// Indexed terms are porter stems ("jingle" is stored as "jingl"), so a term may
+// also match the word minus a few trailing letters
+fn stem_distance(word: &str, term: &str) -> usize {
+    let chars: Vec<char> = word.chars().collect();
+    (0..=3.min(chars.len().saturating_sub(4)))
+        .map(|drop| {
+            let stem: String = chars[..chars.len() - drop].iter().collect();
+            edit_distance(&stem, term)
+        })
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+// The closest indexed term within the word's typo budget; ties go to the term
+// found in more jobs. `vocabulary` is (term, document count)
+pub fn closest_term(word: &str, vocabulary: &[(String, i64)]) -> Option<String> {
+    let budget = max_typos(word);
+    vocabulary
+        .iter()
+        .map(|(term, docs)| (stem_distance(word, term), -docs, term))
+        .filter(|(distance, _, _)| *distance > 0 && *distance <= budget)
+        .min()
+        .map(|(_, _, term)| term.clone())
+}
+
+// Job listing dimensions that can be counted alongside the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JobFacet {
+    Category,
+    JobType,
+    Location,
+    Country,
+    Budget,
+    Skill,
+}
+
+impl JobFacet {
+    pub fn name(self) -> &'static str {
+        match self {
+            JobFacet::Category => "category",
+            JobFacet::JobType => "job_type",
+            JobFacet::Location => "location",
+            JobFacet::Country => "country",
+            JobFacet::Budget => "budget",
+            JobFacet::Skill => "skill",
+        }
+    }
+
+    // "category,skill" -> the listed facets; "all" asks for every one
+    pub fn parse_list(raw: &str) -> Result<Vec<JobFacet>, String> {
+        const ALL: [JobFacet; 6] = [
+            JobFacet::Category,
+            JobFacet::JobType,
+            JobFacet::Location,
+            JobFacet::Country,
+            JobFacet::Budget,
+            JobFacet::Skill,
+        ];
+        let mut facets = Vec::new();
+        for name in raw
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if name == "all" {
+                return Ok(ALL.to_vec());
+            }
+            let facet = ALL
+                .into_iter()
+                .find(|facet| facet.name() == name)
+                .ok_or_else(|| format!("Unknown facet '{}'", name))?;
+            if !facets.contains(&facet) {
+                facets.push(facet);
+            }
+        }
+        Ok(facets)
+    }
+}
+
+// One facet value; `value` is what the matching filter parameter takes, e.g. a
+// category slug or a budget range "100-499" (min_budget=100&max_budget=499)
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FacetCount {
+    pub value: String,
+    pub label: String,
+    pub count: i64,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, FromRow, PartialEq)]
+pub struct Skill {
+    pub id: i64,
+    pub name: String,
+}
+
+// Autocomplete entry, most used first
+#[derive(Debug, Serialize, FromRow)]
+pub struct SkillSuggestion {
+    pub id: i64,
+    pub name: String,
+    pub job_count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SkillQuery {
+    #[validate(length(min = 1, max = 50))]
+    pub q: String,
+    #[validate(range(min = 1, max = 20))]
+    pub limit: Option<i64>,
+}
+
+// Whether a multi-skill job filter needs any or all of the listed skills
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillMatch {
+    #[default]
+    Any,
+    All,
+}
+
+// Split a comma-separated list, dropping blanks and case-insensitive repeats
+pub fn parse_skill_list(raw: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    raw.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && seen.insert(name.to_lowercase()))
+        .map(String::from)
+        .collect()
+}
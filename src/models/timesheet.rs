@@ -0,0 +1,108 @@
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, Type};
+use validator::{Validate, ValidationError};
+
+// A week's hours move from submitted to approved (or back via rejected). The last
+// two states only record what the client says they did on-chain: the escrow PDA
+// they funded for the week, then that they released it. Neither is verified.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum TimesheetStatus {
+    Submitted,
+    Approved,
+    Rejected,
+    EscrowRecorded,
+    ReleaseRecorded,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, ValidationError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| ValidationError::new("date_format"))
+}
+
+fn validate_work_date(date: &str) -> Result<(), ValidationError> {
+    if parse_date(date)? > Utc::now().date_naive() {
+        return Err(ValidationError::new("date_in_future"));
+    }
+    Ok(())
+}
+
+fn validate_week_start(date: &str) -> Result<(), ValidationError> {
+    if parse_date(date)?.weekday() != Weekday::Mon {
+        return Err(ValidationError::new("week_start_not_monday"));
+    }
+    Ok(())
+}
+
+// The Monday of the week a YYYY-MM-DD date falls in
+pub fn week_of(date: &str) -> Option<NaiveDate> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(date - chrono::Duration::days(date.weekday().num_days_from_monday().into()))
+}
+
+//DTO for logging hours against an hourly contract
+#[derive(Debug, Deserialize, Validate)]
+pub struct LogTime {
+    #[validate(custom(function = "validate_work_date"))]
+    pub work_date: String,
+    #[validate(range(min = 0.25, max = 24.0))]
+    pub hours: f64,
+    #[validate(length(min = 1, max = 500))]
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TimeEntry {
+    pub id: i64,
+    pub application_id: i64,
+    pub work_date: String,
+    pub hours: f64,
+    pub description: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TimeEntryQuery {
+    #[validate(custom(function = "validate_week_start"))]
+    pub week_start: Option<String>,
+}
+
+//DTO for submitting a finished week
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitTimesheet {
+    #[validate(custom(function = "validate_week_start"))]
+    pub week_start: String,
+}
+
+//DTO for the client's review
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReviewTimesheet {
+    pub approve: bool,
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
+
+//DTO for recording the escrow a client funded for a week
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordTimesheetEscrow {
+    #[validate(length(equal = 44, message = "pda must be 44 characters long"))]
+    pub escrow_pda: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TimesheetResponse {
+    pub id: i64,
+    pub application_id: i64,
+    pub week_start: String,
+    pub hours: f64,
+    pub hourly_rate: f64,
+    pub amount: f64,
+    pub status: TimesheetStatus,
+    pub review_note: Option<String>,
+    pub escrow_pda: Option<String>,
+    pub submitted_at: String,
+    pub reviewed_at: Option<String>,
+    pub escrow_recorded_at: Option<String>,
+    pub release_recorded_at: Option<String>,
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorEnrollResponse {
+    pub secret: String,
+    pub otpauth_url: String,
+    pub qr_code: String, // base64-encoded PNG of otpauth_url
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TwoFactorCodeRequest {
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecoveryCodesResponse {
+    pub message: String,
+    pub recovery_codes: Vec<String>,
+}
+
+// Either a TOTP code or one of the recovery codes must be provided
+#[derive(Deserialize, Validate)]
+pub struct TwoFactorVerifyRequest {
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: Option<String>,
+    #[validate(length(min = 1, message = "Recovery code is required"))]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TwoFactorLoginRequest {
+    #[validate(length(min = 1, message = "Pending token is required"))]
+    pub pending_token: String,
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: Option<String>,
+    #[validate(length(min = 1, message = "Recovery code is required"))]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorPendingResponse {
+    pub message: String,
+    pub two_factor_required: bool,
+    pub pending_token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorTokenResponse {
+    pub message: String,
+    pub token: String,
+}
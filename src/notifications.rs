@@ -0,0 +1,60 @@
+use crate::db::repo::{NewNotification, NotificationRepo};
+use crate::error::AppError;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+// Buffered events per subscriber before slow receivers start missing some
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationEvent {
+    pub user_id: i64,
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub job_id: Option<i64>,
+}
+
+// Fan-out of newly created notifications to in-process listeners (e.g. push or websockets)
+#[derive(Clone)]
+pub struct NotificationBroadcaster {
+    sender: broadcast::Sender<NotificationEvent>,
+}
+
+impl NotificationBroadcaster {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    // Fire and forget; having no listeners is not an error
+    pub fn publish(&self, event: NotificationEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<NotificationEvent> {
+        self.sender.subscribe()
+    }
+
+    // Store a notification, then push it to live listeners
+    pub async fn notify(
+        &self,
+        repo: &impl NotificationRepo,
+        notification: NewNotification,
+    ) -> Result<i64, AppError> {
+        let id = repo.create_notification(&notification).await?;
+        self.publish(NotificationEvent {
+            user_id: notification.user_id,
+            message: notification.message,
+            kind: notification.kind,
+            job_id: notification.job_id,
+        });
+        Ok(id)
+    }
+}
+
+impl Default for NotificationBroadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
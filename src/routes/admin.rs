@@ -0,0 +1,24 @@
+use crate::state::AppState;
+use axum::{
+    middleware,
+    routing::{patch, post},
+    Router,
+};
+
+use crate::handlers::category::{create_category, delete_category, update_category};
+use crate::middleware::auth::{admin_only, auth_middleware};
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/admin/categories", post(create_category))
+        .route(
+            "/admin/categories/:id",
+            patch(update_category).delete(delete_category),
+        )
+        .route_layer(middleware::from_fn(admin_only))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state)
+}
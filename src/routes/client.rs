@@ -3,34 +3,66 @@ use axum::{
     routing::{get, patch, post},
     Router,
 };
-use sqlx::SqlitePool;
+use crate::state::AppState;
 
 use crate::handlers::client::*;
+use crate::handlers::invitation::{get_job_invitations, invite_freelancer};
 use crate::handlers::job::*;
 use crate::handlers::proposal::{get_proposals_by_job, update_proposal};
-use crate::middleware::auth::{auth_middleware, client_only, wallet_verified_only};
+use crate::handlers::recommendation::suggest_freelancers;
+use crate::handlers::timesheet::{
+    record_timesheet_escrow, record_timesheet_release, review_timesheet,
+};
+use crate::middleware::auth::{
+    auth_middleware, client_only, recent_two_factor_only, wallet_verified_only,
+};
 
-pub fn router(pool: SqlitePool) -> Router {
+pub fn router(state: AppState) -> Router {
     Router::new()
         .route("/jobs/create", post(create_job))
+        .route("/jobs/posted", get(get_posted_jobs))
         .route("/proposals/job/:job_id", get(get_proposals_by_job))
         .route("/proposals/:id", patch(update_proposal))
         .route("/applications/approve", post(approve_application))
         .route("/jobs/:id/applicants", get(get_job_applicants))
-        .route("/jobs/:job_id", get(get_user_job_by_id))
+        .route("/jobs/:job_id", get(get_user_job_by_id).patch(update_job))
+        .route("/jobs/:job_id/close", post(close_job))
+        .route("/jobs/:job_id/repost", post(repost_job))
+        .route("/jobs/:job_id/revisions", get(get_job_revisions))
+        .route(
+            "/jobs/:job_id/suggested-freelancers",
+            get(suggest_freelancers),
+        )
+        .route(
+            "/jobs/:job_id/invitations",
+            get(get_job_invitations).post(invite_freelancer),
+        )
         .route("/my-jobs/:job_id", get(get_user_approved_job))
         .route("/jobs/create-escrow", post(create_escrow_notification))
         .route("/review-request/:application_id", post(review_request))
-        .route("/approve-work", post(approve_job_deliverable))
-        .route("/cancel-escrow", post(cancel_escrow))
-        .route_layer(middleware::from_fn_with_state(pool.clone(), client_only))
+        .route(
+            "/approve-work",
+            post(approve_job_deliverable)
+                .route_layer(middleware::from_fn(recent_two_factor_only)),
+        )
+        .route(
+            "/cancel-escrow",
+            post(cancel_escrow).route_layer(middleware::from_fn(recent_two_factor_only)),
+        )
+        .route("/timesheets/:id/review", post(review_timesheet))
+        .route("/timesheets/:id/escrow", post(record_timesheet_escrow))
+        .route(
+            "/timesheets/:id/release",
+            post(record_timesheet_release).route_layer(middleware::from_fn(recent_two_factor_only)),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), client_only))
         .route_layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             wallet_verified_only,
         ))
         .route_layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             auth_middleware,
         ))
-        .with_state(pool)
+        .with_state(state)
 }
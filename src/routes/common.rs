@@ -1,54 +1,129 @@
-use crate::handlers::{auth::*, escrow::*, job::*, profile::*};
+use crate::handlers::{
+    account::*,
+    auth::*,
+    category::get_categories,
+    escrow::*,
+    job::*,
+    profile::*,
+    proposal::*,
+    review::*,
+    skill::search_skills,
+    timesheet::{get_time_entries, get_timesheets},
+    two_factor::*,
+};
 
-use crate::middleware::auth::{auth_middleware, wallet_verified_only};
+use crate::middleware::auth::{
+    auth_middleware, optional_auth_middleware, recent_two_factor_only, wallet_verified_only,
+};
+use crate::middleware::rate_limit::{rate_limit, AuthRateLimits, RateLimiter};
 use axum::{
     middleware,
     routing::{get, post},
-    Extension, Router,
+    Router,
 };
-use sqlx::SqlitePool;
+use crate::state::AppState;
 
-pub fn public_routes(pool: SqlitePool) -> Router {
+pub fn public_routes(state: AppState) -> Router {
     Router::new()
+        // Open to anonymous visitors; a token, when sent, unlocks the full listing
         .route(
             "/jobs",
             get(get_filtered_jobs).route_layer(middleware::from_fn_with_state(
-                pool.clone(),
-                auth_middleware,
+                state.clone(),
+                optional_auth_middleware,
             )),
         )
+        .route("/jobs/categories", get(get_categories))
         .route(
-            "/jobs/categories",
-            get(get_categories).route_layer(middleware::from_fn_with_state(
-                pool.clone(),
+            "/skills",
+            get(search_skills).route_layer(middleware::from_fn_with_state(
+                state.clone(),
                 auth_middleware,
             )),
         )
         .route(
             "/notifications/mark-read",
             post(mark_notification_as_read).route_layer(middleware::from_fn_with_state(
-                pool.clone(),
+                state.clone(),
                 auth_middleware,
             )),
         )
-        .with_state(pool)
+        .with_state(state)
 }
 
-pub fn auth_routes(pool: SqlitePool) -> Router {
+pub fn auth_routes(state: AppState, limits: AuthRateLimits) -> Router {
+    let login_limiter = RateLimiter::new(limits.login);
+    let signup_limiter = RateLimiter::new(limits.signup);
+    let nonce_limiter = RateLimiter::new(limits.nonce);
+    let verify_limiter = RateLimiter::new(limits.verify);
+    let password_reset_limiter = RateLimiter::new(limits.password_reset);
+
     Router::new()
-        .route("/signup", post(signup))
-        .route("/wallet/signup", post(wallet_signup))
-        .route("/login", post(login))
-        .route("/wallet/login", post(wallet_login))
-        .route("/wallet/request-nonce", post(request_nonce))
-        .route("/wallet/verify", post(verify))
-        .with_state(pool)
+        .route(
+            "/signup",
+            post(signup).route_layer(middleware::from_fn_with_state(
+                signup_limiter.clone(),
+                rate_limit,
+            )),
+        )
+        .route(
+            "/wallet/signup",
+            post(wallet_signup)
+                .route_layer(middleware::from_fn_with_state(signup_limiter, rate_limit)),
+        )
+        .route(
+            "/login",
+            post(login).route_layer(middleware::from_fn_with_state(
+                login_limiter.clone(),
+                rate_limit,
+            )),
+        )
+        .route(
+            "/login/2fa",
+            post(login_two_factor).route_layer(middleware::from_fn_with_state(
+                login_limiter.clone(),
+                rate_limit,
+            )),
+        )
+        .route(
+            "/wallet/login",
+            post(wallet_login)
+                .route_layer(middleware::from_fn_with_state(login_limiter, rate_limit)),
+        )
+        .route(
+            "/wallet/request-nonce",
+            post(request_nonce)
+                .route_layer(middleware::from_fn_with_state(nonce_limiter, rate_limit)),
+        )
+        .route(
+            "/wallet/verify",
+            post(verify).route_layer(middleware::from_fn_with_state(verify_limiter, rate_limit)),
+        )
+        .route("/email/verify", post(verify_email))
+        .route(
+            "/password/forgot",
+            post(forgot_password).route_layer(middleware::from_fn_with_state(
+                password_reset_limiter,
+                rate_limit,
+            )),
+        )
+        .route("/password/reset", post(reset_password))
+        .with_state(state)
 }
 
-pub fn protected_routes(pool: SqlitePool) -> Router {
+pub fn protected_routes(state: AppState) -> Router {
     Router::new()
-        .route("/wallet/connect", post(wallet_connect))
+        .route(
+            "/wallet/connect",
+            post(wallet_connect).route_layer(middleware::from_fn(recent_two_factor_only)),
+        )
         .route("/logout", post(logout))
+        .route("/email/resend-verification", post(resend_verification_email))
+        .route("/2fa/enroll", post(enroll_two_factor))
+        .route("/2fa/enable", post(enable_two_factor))
+        .route("/2fa/disable", post(disable_two_factor))
+        .route("/2fa/recovery-codes", post(regenerate_recovery_codes))
+        .route("/2fa/step-up", post(step_up_two_factor))
         .route("/notifications", get(get_notifications))
         .route("/profile", post(create_or_update_profile))
         .route("/get-profile-userId/:user_id", get(get_profile_by_user_id))
@@ -60,20 +135,38 @@ pub fn protected_routes(pool: SqlitePool) -> Router {
         .route("/profile/basic", get(check_username_availability))
         .route("/escrow/:escrow_pda", get(get_escrow))
         .route("/my-jobs", get(get_user_jobs))
+        // The job's client and hired freelancer review each other once it completes
+        .route(
+            "/jobs/:job_id/reviews",
+            get(get_job_reviews).post(create_review),
+        )
+        .route("/users/:user_id/reviews", get(get_user_reviews))
+        // Negotiation is open to both parties on a proposal
+        .route(
+            "/proposals/:id/offers",
+            get(get_proposal_offers).post(counter_proposal),
+        )
+        .route("/proposals/:id/offers/accept", post(accept_proposal_offer))
+        .route("/proposals/:id/revisions", get(get_proposal_revisions))
+        // Both parties to an hourly contract can see its hours and timesheets
+        .route(
+            "/contracts/:application_id/time-entries",
+            get(get_time_entries),
+        )
+        .route("/contracts/:application_id/timesheets", get(get_timesheets))
         .route("/raise-dispute", post(raise_dispute))
         .route("/get-disputed-jobs", get(get_disputed_jobs_for_arbiter))
         .route("/handle-resolve", post(arbiter_resolve))
         .route(
             "/profile/verified",
             get(profile_verified).route_layer(middleware::from_fn_with_state(
-                pool.clone(),
+                state.clone(),
                 wallet_verified_only,
             )),
         )
         .route_layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             auth_middleware,
         ))
-        .layer(Extension(pool.clone()))
-        .with_state(pool)
+        .with_state(state)
 }
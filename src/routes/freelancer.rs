@@ -1,41 +1,68 @@
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{get, patch, post},
     Router,
 };
-use sqlx::SqlitePool;
+use crate::state::AppState;
 
 // use crate::handlers::freelancer::submit_bid;
 use crate::handlers::{
     freelancer::*,
+    invitation::{get_my_invitations, respond_to_invitation},
     job::apply_for_job,
-    proposal::{create_proposal, get_my_proposals},
+    proposal::{create_proposal, edit_proposal, get_my_proposals, withdraw_proposal},
+    recommendation::recommend_jobs,
+    saved_search::{
+        create_saved_search, delete_saved_search, get_saved_jobs, get_saved_searches, save_job,
+        unsave_job, update_saved_search,
+    },
+    timesheet::{log_time, submit_timesheet},
 };
 use crate::middleware::auth::{auth_middleware, freelancer_only};
 
-// pub fn router(pool: SqlitePool) -> Router {
+// pub fn router(state: AppState) -> Router {
 //     Router::new()
 //         .route("/jobs/:id/bid", post(submit_bid))
-//         .with_state(pool)
+//         .with_state(state)
 // }
 
-pub fn router(pool: SqlitePool) -> Router {
+pub fn router(state: AppState) -> Router {
     Router::new()
         // .route("/jobs", get(view_jobs))
         .route("/proposals", post(create_proposal))
         .route("/proposals/me", get(get_my_proposals))
+        .route("/proposals/:id/edit", post(edit_proposal))
+        .route("/proposals/:id/withdraw", post(withdraw_proposal))
         .route("/jobs/apply", post(apply_for_job))
+        .route("/jobs/recommended", get(recommend_jobs))
+        .route("/jobs/saved", get(get_saved_jobs))
+        .route("/jobs/:job_id/save", post(save_job).delete(unsave_job))
+        .route(
+            "/saved-searches",
+            get(get_saved_searches).post(create_saved_search),
+        )
+        .route(
+            "/saved-searches/:id",
+            patch(update_saved_search).delete(delete_saved_search),
+        )
+        .route("/invitations", get(get_my_invitations))
+        .route("/invitations/:id/respond", post(respond_to_invitation))
         .route("/jobs/:job_id/status", get(get_job_user_status))
         .route("/my_jobs/:application_id", get(get_user_job_by_id))
         .route("/my_jobs/submit-deliverable", post(submit_job_deliverable))
         .route("/claim-timeout", post(claim_timeout))
+        .route("/contracts/:application_id/time-entries", post(log_time))
+        .route(
+            "/contracts/:application_id/timesheets",
+            post(submit_timesheet),
+        )
         .route_layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             freelancer_only,
         ))
         .route_layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             auth_middleware,
         ))
-        .with_state(pool)
+        .with_state(state)
 }
@@ -1,7 +1,8 @@
 use axum::{Router, routing::get};
-use sqlx::SqlitePool;
+use crate::state::AppState;
 
 use crate::handlers::{print_msg, check_health, hello};
+use crate::middleware::rate_limit::AuthRateLimits;
 
 
 
@@ -9,20 +10,27 @@ pub mod common;
 pub mod freelancer;
 pub mod client;
 pub mod blockchain;
+pub mod admin;
 
-pub fn create_routes(pool: SqlitePool) -> Router{
+pub fn create_routes(state: AppState) -> Router{
     Router::new()
         .route("/", get(print_msg))
         .route("/api/v1/ping", get(check_health))
         .route("/api/v1/hello", get(hello))
-        .merge(common::public_routes(pool.clone()))
-        .merge(client::router(pool.clone()))
-        .merge(freelancer::router(pool))
+        .merge(common::public_routes(state.clone()))
+        .merge(admin::router(state.clone()))
+        .merge(client::router(state.clone()))
+        .merge(freelancer::router(state))
 }
 
-pub fn auth_routes(pool:SqlitePool) -> Router{
+pub fn auth_routes(state: AppState) -> Router{
+    let limits = state.config.rate_limits;
+    auth_routes_with_limits(state, limits)
+}
+
+pub fn auth_routes_with_limits(state: AppState, limits: AuthRateLimits) -> Router {
     Router::new()
-        .merge(common::protected_routes(pool.clone()))
-        .merge(common::auth_routes(pool))
+        .merge(common::protected_routes(state.clone()))
+        .merge(common::auth_routes(state, limits))
 }
 
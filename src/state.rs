@@ -0,0 +1,71 @@
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::mail::Mailer;
+use crate::notifications::NotificationBroadcaster;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use axum::extract::FromRef;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+// State shared by every router and middleware. Handlers extract the whole
+// `State<AppState>` or just the part they need (e.g. `State<SqlitePool>`).
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: SqlitePool,
+    pub config: Arc<Config>,
+    pub mailer: Mailer,
+    pub solana: Arc<RpcClient>,
+    pub notifications: NotificationBroadcaster,
+    pub cache: Cache,
+}
+
+impl AppState {
+    pub fn new(pool: SqlitePool, config: Config, mailer: Mailer) -> Self {
+        // The RPC client connects lazily, so building it never blocks startup
+        let solana = Arc::new(RpcClient::new(config.solana_rpc_url.clone()));
+        Self {
+            pool,
+            config: Arc::new(config),
+            mailer,
+            solana,
+            notifications: NotificationBroadcaster::new(),
+            cache: Cache::new(),
+        }
+    }
+}
+
+impl FromRef<AppState> for SqlitePool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Config> {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<AppState> for Mailer {
+    fn from_ref(state: &AppState) -> Self {
+        state.mailer.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<RpcClient> {
+    fn from_ref(state: &AppState) -> Self {
+        state.solana.clone()
+    }
+}
+
+impl FromRef<AppState> for NotificationBroadcaster {
+    fn from_ref(state: &AppState) -> Self {
+        state.notifications.clone()
+    }
+}
+
+impl FromRef<AppState> for Cache {
+    fn from_ref(state: &AppState) -> Self {
+        state.cache.clone()
+    }
+}
@@ -1,45 +1,104 @@
+use crate::config::Config;
 use crate::error::AppError;
 use crate::models::jwt::Claims;
 use bcrypt::{hash, verify, DEFAULT_COST};
-use bs58;
 use chrono::{Duration, Utc};
-use ed25519_dalek::{VerifyingKey, Signature};
-use jsonwebtoken::{encode, EncodingKey, Header};
-use std::env;
-use std::convert::TryInto;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
 
+// Lifetime of the "2FA pending" token returned by login for 2FA-enabled accounts
+pub const TWO_FACTOR_PENDING_TTL_MINUTES: i64 = 5;
 
-pub fn hash_password(password: &String) -> Result<String, bcrypt::BcryptError> {
+
+pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
     // Hash the provided password using bcrypt with default cost factor
     hash(password, DEFAULT_COST)
     // Edge case: Password too long or bcrypt internal error
 }
 
-pub fn verify_password(password: &String, hashed: &String) -> Result<bool, bcrypt::BcryptError> {
+pub fn verify_password(password: &str, hashed: &str) -> Result<bool, bcrypt::BcryptError> {
     // Verify if the provided password matches the hashed password
     verify(password, hashed)
     // Edge case: Invalid hash format or bcrypt internal error
 }
 
-pub fn generate_jwt(user_id: i64, role: String) -> Result<String, jsonwebtoken::errors::Error> {
-    // Create JWT claims with user_id, role, and 24-hour expiration
-    let claims = Claims {
-        user_id,
-        role,
-        exp: (Utc::now() + Duration::hours(24)).timestamp() as i64,
-    };
+pub fn generate_jwt(
+    config: &Config,
+    user_id: i64,
+    role: String,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    // Create JWT claims with user_id, role, issue time and the configured expiration
+    let now = Utc::now();
+    encode_claims(
+        config,
+        &Claims {
+            user_id,
+            role,
+            iat: now.timestamp(),
+            exp: (now + config.token_ttl).timestamp(),
+            two_factor_pending: false,
+            two_factor_at: None,
+        },
+    )
+}
+
+pub fn generate_two_factor_jwt(
+    config: &Config,
+    user_id: i64,
+    role: String,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    // Full session token for a user who has just passed a TOTP check
+    let now = Utc::now();
+    encode_claims(
+        config,
+        &Claims {
+            user_id,
+            role,
+            iat: now.timestamp(),
+            exp: (now + config.token_ttl).timestamp(),
+            two_factor_pending: false,
+            two_factor_at: Some(now.timestamp()),
+        },
+    )
+}
+
+pub fn generate_two_factor_pending_jwt(
+    config: &Config,
+    user_id: i64,
+    role: String,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    // Short-lived token that can only be exchanged at /login/2fa
+    let now = Utc::now();
+    encode_claims(
+        config,
+        &Claims {
+            user_id,
+            role,
+            iat: now.timestamp(),
+            exp: (now + Duration::minutes(TWO_FACTOR_PENDING_TTL_MINUTES)).timestamp(),
+            two_factor_pending: true,
+            two_factor_at: None,
+        },
+    )
+}
 
-    // Encode JWT using the secret key from environment variable
+fn encode_claims(config: &Config, claims: &Claims) -> Result<String, jsonwebtoken::errors::Error> {
     encode(
         &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(
-            env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set")
-                .as_ref(),
-        ),
+        claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_ref()),
+    )
+}
+
+// Check the signature and expiry of a JWT and return its claims
+pub fn decode_jwt(config: &Config, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_ref()),
+        &Validation::default(),
     )
-    // Edge case: Missing JWT_SECRET env variable or encoding failure
+    .map(|data| data.claims)
 }
 
 pub fn generate_nonce() -> String {
@@ -54,58 +113,72 @@ pub fn generate_nonce() -> String {
     // Edge case: Random number generator failure (highly unlikely)
 }
 
-pub fn create_solana_sign_message(nonce: &str, wallet_address: &str) -> String {
-    format!(
-        "Welcome to Freelancia!\n\nWallet: {}\nNonce: {}\n\nSign this message to authenticate.",
-        wallet_address, nonce
+pub fn generate_email_token() -> String {
+    // Generate a 48-character random alphanumeric token for email links
+    use rand::distributions::Alphanumeric;
+    use rand::{thread_rng, Rng};
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(48)
+        .map(char::from)
+        .collect()
+}
+
+pub fn hash_token(token: &str) -> String {
+    // Email tokens are stored as SHA-256 hex so a leaked table can't be replayed
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+pub fn generate_totp_secret() -> String {
+    // 160-bit random secret, base32 encoded as authenticator apps expect
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+pub fn build_totp(secret_base32: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let secret = Secret::Encoded(secret_base32.to_string())
+        .to_bytes()
+        .map_err(|_| AppError::Server("Invalid TOTP secret".to_string()))?;
+
+    // SHA1 / 6 digits / 30s step is what every common authenticator app supports
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret,
+        Some("Freelancia".to_string()),
+        account_name.replace(':', ""),
     )
+    .map_err(|e| AppError::Server(format!("Failed to build TOTP: {}", e)))
+}
+
+pub fn matching_totp_step(totp: &TOTP, code: &str, now: u64) -> Option<u64> {
+    // Return the time step the code belongs to (allowing one step of clock skew),
+    // so callers can refuse to accept the same step twice
+    let code = code.trim();
+    let current_step = now / totp.step;
+    (current_step.saturating_sub(totp.skew as u64)..=current_step + totp.skew as u64)
+        .find(|step| totp.generate(step * totp.step) == code)
+}
+
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    // Codes look like "k3f9-x2ma" so they are easy to copy by hand
+    use rand::distributions::Alphanumeric;
+    use rand::{thread_rng, Rng};
+    let mut rng = thread_rng();
+    (0..count)
+        .map(|_| {
+            let raw: String = (&mut rng)
+                .sample_iter(&Alphanumeric)
+                .take(8)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+            format!("{}-{}", &raw[..4], &raw[4..])
+        })
+        .collect()
 }
 
-pub fn verify_solana_signature(
-    message: &str,
-    signature_base58: &str,
-    wallet_address_base58: &str,
-) -> Result<bool, AppError> {
-  // Trim inputs to prevent whitespace issues
-    let signature_base58 = signature_base58.trim();
-    let wallet_address_base58 = wallet_address_base58.trim();
-
-    // Decode public key
-    let pubkey_bytes = bs58::decode(wallet_address_base58)
-        .into_vec()
-        .map_err(|e| AppError::Unauthorized(format!("Invalid wallet address: {}", e)))?;
-
-    // Convert to fixed-size array
-    let pubkey_arr: [u8; 32] = pubkey_bytes
-        .try_into()
-        .map_err(|_| AppError::Unauthorized("Invalid public key length".into()))?;
-
-    let public_key = VerifyingKey::from_bytes(&pubkey_arr)
-        .map_err(|e| AppError::Unauthorized(format!("Invalid public key: {}", e)))?;
-
-    // Decode signature
-    let signature_bytes = bs58::decode(signature_base58)
-        .into_vec()
-        .map_err(|e| AppError::Unauthorized(format!("Invalid signature format: {}", e)))?;
-
-    // Convert to fixed-size array
-    let signature_arr: [u8; 64] = signature_bytes
-        .try_into()
-        .map_err(|_| AppError::Unauthorized("Invalid signature length".into()))?;
-
-    let signature = Signature::from_bytes(&signature_arr);
-
-    // Reconstruct signed message
-    let formatted_message = format!(
-        "\x18Solana Signed Message:\n{}{}",
-        message.chars().count(),  // Character count
-        message
-    );
-
-    // Verify signature
-    public_key
-        .verify_strict(formatted_message.as_bytes(), &signature)
-        .map(|_| true)
-        .map_err(|e| AppError::Unauthorized(format!("Verification failed: {}", e)))
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_ascii_lowercase()
 }
 
@@ -0,0 +1,60 @@
+use crate::error::AppError;
+use ethers::types::{Address, Signature};
+use ethers::utils::to_checksum;
+use std::str::FromStr;
+
+// EVM addresses are stored in EIP-55 checksum form so lookups don't depend on client casing
+pub fn normalize_address(wallet_address: &str) -> Result<String, AppError> {
+    let wallet_address = wallet_address.trim();
+    if !wallet_address.starts_with("0x") || wallet_address.len() != 42 {
+        return Err(AppError::BadRequest("Invalid EVM wallet address".to_string()));
+    }
+
+    let address = Address::from_str(wallet_address)
+        .map_err(|_| AppError::BadRequest("Invalid EVM wallet address".to_string()))?;
+
+    Ok(to_checksum(&address, None))
+}
+
+// Sign-In with Ethereum (EIP-4361) message; the nonce's own timestamps bound its validity
+pub fn create_siwe_message(
+    domain: &str,
+    uri: &str,
+    chain_id: u64,
+    wallet_address: &str,
+    nonce: &str,
+    issued_at: &str,
+    expires_at: &str,
+) -> String {
+    format!(
+        "{domain} wants you to sign in with your Ethereum account:\n\
+         {wallet_address}\n\
+         \n\
+         Sign in to Freelancia.\n\
+         \n\
+         URI: {uri}\n\
+         Version: 1\n\
+         Chain ID: {chain_id}\n\
+         Nonce: {nonce}\n\
+         Issued At: {issued_at}\n\
+         Expiration Time: {expires_at}"
+    )
+}
+
+// Recover the signer of an EIP-191 `personal_sign` signature and compare it to the wallet
+pub fn verify_signature(
+    message: &str,
+    signature_hex: &str,
+    wallet_address: &str,
+) -> Result<bool, AppError> {
+    let signature = Signature::from_str(signature_hex.trim())
+        .map_err(|e| AppError::Unauthorized(format!("Invalid signature format: {}", e)))?;
+
+    let address = Address::from_str(wallet_address.trim())
+        .map_err(|e| AppError::Unauthorized(format!("Invalid wallet address: {}", e)))?;
+
+    signature
+        .verify(message, address)
+        .map(|_| true)
+        .map_err(|e| AppError::Unauthorized(format!("Verification failed: {}", e)))
+}
@@ -0,0 +1,73 @@
+pub mod evm;
+pub mod solana;
+
+use crate::config::Config;
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+// Chain a wallet address belongs to; decides address format and signature scheme
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum WalletChain {
+    #[default]
+    Solana,
+    Evm,
+}
+
+// A nonce issued to a wallet, with the timestamps stored alongside it
+pub struct WalletChallenge<'a> {
+    pub chain: WalletChain,
+    pub wallet_address: &'a str,
+    pub nonce: &'a str,
+    pub issued_at: &'a str,
+    pub expires_at: &'a str,
+}
+
+impl WalletChain {
+    // Validate an address for this chain and return its canonical stored form
+    pub fn normalize_address(self, wallet_address: &str) -> Result<String, AppError> {
+        match self {
+            WalletChain::Solana => solana::normalize_address(wallet_address),
+            WalletChain::Evm => evm::normalize_address(wallet_address),
+        }
+    }
+}
+
+impl WalletChallenge<'_> {
+    // The exact text the wallet is asked to sign for this nonce
+    pub fn message(&self, config: &Config) -> String {
+        match self.chain {
+            WalletChain::Solana => solana::create_sign_message(self.nonce, self.wallet_address),
+            WalletChain::Evm => {
+                let uri = &config.frontend_url;
+                let domain = uri
+                    .split("://")
+                    .nth(1)
+                    .unwrap_or(uri)
+                    .split('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                evm::create_siwe_message(
+                    &domain,
+                    uri,
+                    config.evm_chain_id,
+                    self.wallet_address,
+                    self.nonce,
+                    self.issued_at,
+                    self.expires_at,
+                )
+            }
+        }
+    }
+
+    pub fn verify(&self, config: &Config, signature: &str) -> Result<bool, AppError> {
+        let message = self.message(config);
+        match self.chain {
+            WalletChain::Solana => solana::verify_signature(&message, signature, self.wallet_address),
+            WalletChain::Evm => evm::verify_signature(&message, signature, self.wallet_address),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::AppError;
+use ed25519_dalek::{Signature, VerifyingKey};
+use std::convert::TryInto;
+
+// Solana addresses are base58-encoded 32-byte ed25519 public keys
+pub fn normalize_address(wallet_address: &str) -> Result<String, AppError> {
+    let wallet_address = wallet_address.trim();
+    let bytes = bs58::decode(wallet_address)
+        .into_vec()
+        .map_err(|_| AppError::BadRequest("Invalid Solana wallet address".to_string()))?;
+
+    if bytes.len() != 32 {
+        return Err(AppError::BadRequest(
+            "Invalid Solana wallet address".to_string(),
+        ));
+    }
+
+    Ok(wallet_address.to_string())
+}
+
+pub fn create_sign_message(nonce: &str, wallet_address: &str) -> String {
+    format!(
+        "Welcome to Freelancia!\n\nWallet: {}\nNonce: {}\n\nSign this message to authenticate.",
+        wallet_address, nonce
+    )
+}
+
+pub fn verify_signature(
+    message: &str,
+    signature_base58: &str,
+    wallet_address_base58: &str,
+) -> Result<bool, AppError> {
+  // Trim inputs to prevent whitespace issues
+    let signature_base58 = signature_base58.trim();
+    let wallet_address_base58 = wallet_address_base58.trim();
+
+    // Decode public key
+    let pubkey_bytes = bs58::decode(wallet_address_base58)
+        .into_vec()
+        .map_err(|e| AppError::Unauthorized(format!("Invalid wallet address: {}", e)))?;
+
+    // Convert to fixed-size array
+    let pubkey_arr: [u8; 32] = pubkey_bytes
+        .try_into()
+        .map_err(|_| AppError::Unauthorized("Invalid public key length".into()))?;
+
+    let public_key = VerifyingKey::from_bytes(&pubkey_arr)
+        .map_err(|e| AppError::Unauthorized(format!("Invalid public key: {}", e)))?;
+
+    // Decode signature
+    let signature_bytes = bs58::decode(signature_base58)
+        .into_vec()
+        .map_err(|e| AppError::Unauthorized(format!("Invalid signature format: {}", e)))?;
+
+    // Convert to fixed-size array
+    let signature_arr: [u8; 64] = signature_bytes
+        .try_into()
+        .map_err(|_| AppError::Unauthorized("Invalid signature length".into()))?;
+
+    let signature = Signature::from_bytes(&signature_arr);
+
+    // Reconstruct signed message
+    let formatted_message = format!(
+        "\x18Solana Signed Message:\n{}{}",
+        message.chars().count(),  // Character count
+        message
+    );
+
+    // Verify signature
+    public_key
+        .verify_strict(formatted_message.as_bytes(), &signature)
+        .map(|_| true)
+        .map_err(|e| AppError::Unauthorized(format!("Verification failed: {}", e)))
+}
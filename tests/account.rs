@@ -0,0 +1,193 @@
+mod common;
+
+use axum::{http::StatusCode, Router};
+use chrono::Duration;
+use common::{post_json, setup_db, test_state_with_mailer};
+use freelancia_backend::{
+    mail::{password_reset_mail, verification_mail, InMemoryMailSender},
+    routes,
+    utils::hash_password,
+};
+use serde_json::json;
+use sqlx::{Pool, Sqlite};
+use std::sync::Arc;
+
+fn app(pool: Pool<Sqlite>, mailer: Arc<InMemoryMailSender>) -> Router {
+    routes::auth_routes(test_state_with_mailer(pool, mailer))
+}
+
+fn token_from_mail(body: &str) -> String {
+    body.split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_signup_sends_verification_email() {
+    let pool = setup_db().await;
+    let mailer = Arc::new(InMemoryMailSender::new());
+    let app = app(pool.clone(), mailer.clone());
+
+    let (status, _) = post_json(
+        &app,
+        "/signup",
+        json!({ "email": "new@example.com", "password": "password123", "role": "client" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mail = mailer
+        .last_to("new@example.com")
+        .expect("verification mail");
+    assert!(mail.body.contains("The link expires in 24 hours."));
+    let token = token_from_mail(&mail.body);
+
+    // Only the hash is persisted
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_tokens WHERE token_hash = ?")
+        .bind(&token)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0);
+
+    let (status, _) = post_json(&app, "/email/verify", json!({ "token": token })).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let verified: bool = sqlx::query_scalar("SELECT email_verified FROM users WHERE email = ?")
+        .bind("new@example.com")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(verified);
+
+    // Tokens are single use
+    let (status, _) = post_json(&app, "/email/verify", json!({ "token": token })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_password_reset_flow() {
+    let pool = setup_db().await;
+    let mailer = Arc::new(InMemoryMailSender::new());
+    let app = app(pool.clone(), mailer.clone());
+
+    sqlx::query("INSERT INTO users (email, password, role) VALUES (?, ?, ?)")
+        .bind("reset@example.com")
+        .bind(hash_password("old_password").unwrap())
+        .bind("freelancer")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Unknown emails get the same answer and no mail
+    let (status, _) = post_json(
+        &app,
+        "/password/forgot",
+        json!({ "email": "nobody@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(mailer.sent().is_empty());
+
+    let (status, _) = post_json(
+        &app,
+        "/password/forgot",
+        json!({ "email": "reset@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let token = token_from_mail(&mailer.last_to("reset@example.com").unwrap().body);
+
+    let (status, _) = post_json(
+        &app,
+        "/password/reset",
+        json!({ "token": token, "password": "new_password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = post_json(
+        &app,
+        "/login",
+        json!({ "email": "reset@example.com", "password": "old_password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = post_json(
+        &app,
+        "/login",
+        json!({ "email": "reset@example.com", "password": "new_password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["email_verified"], json!(true));
+
+    // The reset invalidated every outstanding token
+    let (status, _) = post_json(
+        &app,
+        "/password/reset",
+        json!({ "token": token, "password": "another_password" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let open_tokens: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM email_tokens WHERE used_at IS NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(open_tokens, 0);
+}
+
+#[tokio::test]
+async fn test_signup_error_responses() {
+    let pool = setup_db().await;
+    let app = app(pool, Arc::new(InMemoryMailSender::new()));
+
+    // Validation failures come back per field with a 422
+    let (status, body) = post_json(
+        &app,
+        "/signup",
+        json!({ "email": "not-an-email", "password": "short", "role": "client" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"]["code"], "validation_failed");
+    assert_eq!(
+        body["error"]["fields"]["email"][0]["message"],
+        "Invalid email format"
+    );
+    assert!(body["error"]["fields"]["password"].is_array());
+
+    let signup = json!({ "email": "dup@example.com", "password": "password123", "role": "client" });
+    let (status, _) = post_json(&app, "/signup", signup.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Duplicate emails are a conflict, and no database text reaches the client
+    let (status, body) = post_json(&app, "/signup", signup).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "conflict");
+    assert!(!body.to_string().contains("UNIQUE"));
+}
+
+#[test]
+fn test_mail_bodies_state_the_configured_expiry() {
+    let mail = verification_mail("a@example.com", "http://app", "token", Duration::hours(48));
+    assert!(mail.body.contains("expires in 48 hours"));
+    let mail = password_reset_mail(
+        "a@example.com",
+        "http://app",
+        "token",
+        Duration::minutes(60),
+    );
+    assert!(mail.body.contains("expires in 1 hour."));
+    let mail = password_reset_mail(
+        "a@example.com",
+        "http://app",
+        "token",
+        Duration::minutes(30),
+    );
+    assert!(mail.body.contains("expires in 30 minutes"));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::{json, Value};
+
+fn job(category: &str) -> Value {
+    json!({
+        "title": "Landing page",
+        "description": "Build the marketing site",
+        "skills": "React",
+        "budget": 300,
+        "location": "remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": category,
+        "status": "open"
+    })
+}
+
+#[tokio::test]
+async fn test_admins_manage_categories_that_jobs_must_use() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role, admin) VALUES
+             (1, 'admin@example.com', 'client', 1),
+             (2, 'client@example.com', 'client', 0)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let admin = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let client = generate_jwt(&state.config, 2, "client".to_string()).unwrap();
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/admin/categories",
+        &client,
+        json!({ "name": "Frontend" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = call(&app, Method::GET, "/jobs/categories", &client, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let web = body["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["slug"] == "web-development")
+        .unwrap()["id"]
+        .as_i64()
+        .unwrap();
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/admin/categories",
+        &admin,
+        json!({ "name": "Frontend", "parent_id": web }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let frontend = body["category"]["id"].as_i64().unwrap();
+    assert_eq!(body["category"]["slug"], "frontend");
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/admin/categories",
+        &admin,
+        json!({ "name": "frontend" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Free text is rejected; a name or slug picks the category
+    let (status, _) = call(&app, Method::POST, "/jobs/create", &client, job("Webdev")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = call(&app, Method::POST, "/jobs/create", &client, job("frontend")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/jobs/create",
+        &client,
+        job("web development"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // The parent counts its subcategory's jobs; the cache was cleared by the create
+    let (_, body) = call(&app, Method::GET, "/jobs/categories", &client, json!({})).await;
+    let web_node = body["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["id"] == web)
+        .unwrap()
+        .clone();
+    assert_eq!(web_node["job_count"], 2);
+    assert_eq!(web_node["subcategories"][0]["name"], "Frontend");
+    assert_eq!(web_node["subcategories"][0]["job_count"], 1);
+
+    let (_, body) = call(
+        &app,
+        Method::GET,
+        "/jobs?category=web-development",
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(body["jobs"].as_array().unwrap().len(), 2);
+
+    let (status, _) = call(
+        &app,
+        Method::DELETE,
+        &format!("/admin/categories/{}", frontend),
+        &admin,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
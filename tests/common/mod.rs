@@ -0,0 +1,92 @@
+// Fixtures shared by the integration tests; each test binary uses a subset
+#![allow(dead_code)]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use freelancia_backend::{
+    config::Config,
+    db,
+    mail::{InMemoryMailSender, Mailer},
+    state::AppState,
+};
+use serde_json::Value;
+use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use std::sync::Arc;
+use tower::util::ServiceExt;
+
+// The real schema, on a single connection so every query sees the same
+// in-memory database
+pub async fn setup_db() -> Pool<Sqlite> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    db::SQLITE_MIGRATOR.run(&pool).await.unwrap();
+    pool
+}
+
+pub fn test_config() -> Config {
+    Config::from_lookup(|key| match key {
+        "JWT_SECRET" => Some("test_secret".to_string()),
+        "DATABASE_URL" => Some("sqlite::memory:".to_string()),
+        _ => None,
+    })
+    .unwrap()
+}
+
+// For tests that never look at the mail sent
+pub fn test_state(pool: Pool<Sqlite>) -> AppState {
+    test_state_with_mailer(pool, Arc::new(InMemoryMailSender::new()))
+}
+
+pub fn test_state_with_mailer(pool: Pool<Sqlite>, mailer: Mailer) -> AppState {
+    AppState::new(pool, test_config(), mailer)
+}
+
+// Send a JSON request, as the bearer of `token` when one is given
+pub async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Value,
+) -> Response {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    app.clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap()
+}
+
+// The status and JSON body; Null when the body is empty or not JSON
+pub async fn json_response(response: Response) -> (StatusCode, Value) {
+    let status = response.status();
+    let body = to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+pub async fn call(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    json_response(send(app, method, uri, Some(token), body).await).await
+}
+
+// An anonymous POST, as the auth endpoints receive
+pub async fn post_json(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    json_response(send(app, Method::POST, uri, None, body).await).await
+}
@@ -0,0 +1,111 @@
+use freelancia_backend::config::{Config, ConfigError};
+use std::collections::HashMap;
+
+fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    Config::from_lookup(|key| vars.get(key).cloned())
+}
+
+#[test]
+fn test_config_defaults_and_overrides() {
+    let config = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+    ])
+    .unwrap();
+    assert_eq!(config.bind_addr.to_string(), "127.0.0.1:3000");
+    assert_eq!(config.cors_origins, vec!["http://localhost:5173"]);
+    assert_eq!(config.token_ttl.num_hours(), 24);
+    assert_eq!(config.claim_window.num_days(), 3);
+
+    let config = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+        ("BIND_ADDR", "0.0.0.0:8080"),
+        ("FRONTEND_URL", "https://app.freelancia.io/"),
+        ("CORS_ORIGINS", "https://app.freelancia.io, https://admin.freelancia.io"),
+        ("CLAIM_WINDOW_DAYS", "7"),
+    ])
+    .unwrap();
+    assert_eq!(config.bind_addr.port(), 8080);
+    assert_eq!(config.frontend_url, "https://app.freelancia.io");
+    assert_eq!(config.cors_origins.len(), 2);
+    assert_eq!(config.claim_window.num_days(), 7);
+}
+
+#[test]
+fn test_config_validation_errors() {
+    let err = load(&[("DATABASE_URL", "sqlite://freelancia.db")]).unwrap_err();
+    assert_eq!(err.to_string(), "JWT_SECRET must be set");
+
+    let err = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+        ("TOKEN_TTL_HOURS", "0"),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "TOKEN_TTL_HOURS is invalid: must be greater than zero"
+    );
+
+    let err = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+        ("BIND_ADDR", "localhost"),
+    ])
+    .unwrap_err();
+    assert!(err.to_string().starts_with("BIND_ADDR is invalid"));
+
+    let err = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "mysql://localhost/freelancia"),
+    ])
+    .unwrap_err();
+    assert!(err.to_string().starts_with("DATABASE_URL is invalid"));
+    assert!(load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "postgres://localhost/freelancia"),
+    ])
+    .is_ok());
+}
+
+#[test]
+fn test_config_rate_limits() {
+    let config = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+    ])
+    .unwrap();
+    assert_eq!(config.rate_limits.login.per_ip.burst, 20);
+    assert!(config.rate_limits.signup.per_account.is_none());
+
+    let config = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+        ("RATE_LIMIT_LOGIN_PER_ACCOUNT", "3/30s"),
+        ("RATE_LIMIT_SIGNUP_PER_ACCOUNT", "2/1h"),
+        ("RATE_LIMIT_VERIFY_PER_ACCOUNT", "off"),
+    ])
+    .unwrap();
+    let login = config.rate_limits.login.per_account.unwrap();
+    assert_eq!((login.burst, login.per.as_secs()), (3, 30));
+    let signup = config.rate_limits.signup.per_account.unwrap();
+    assert_eq!((signup.burst, signup.per.as_secs()), (2, 3600));
+    assert!(config.rate_limits.verify.per_account.is_none());
+    // Untouched limits keep their defaults
+    assert_eq!(config.rate_limits.nonce.per_ip.burst, 10);
+
+    let err = load(&[
+        ("JWT_SECRET", "secret"),
+        ("DATABASE_URL", "sqlite://freelancia.db"),
+        ("RATE_LIMIT_LOGIN_PER_IP", "off"),
+    ])
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("RATE_LIMIT_LOGIN_PER_IP is invalid"));
+}
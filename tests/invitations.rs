@@ -0,0 +1,103 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::json;
+
+async fn visible_job_ids(app: &Router, token: &str) -> Vec<i64> {
+    let (status, body) = call(app, Method::GET, "/jobs", token, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["id"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_invite_only_job_is_reserved_for_invited_freelancers() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'invited@example.com', 'freelancer', 'invited-wallet'),
+             (3, 'outsider@example.com', 'freelancer', 'outsider-wallet');
+         INSERT INTO profiles (user_id, username, role) VALUES (2, 'rustacean', 'freelancer');",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let invited = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+    let outsider = generate_jwt(&state.config, 3, "freelancer".to_string()).unwrap();
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/jobs/create",
+        &client,
+        json!({
+            "title": "Audit our escrow program",
+            "description": "Private engagement",
+            "skills": "rust,solana",
+            "budget": 900,
+            "location": "remote",
+            "job_type": "fixed",
+            "job_ipfs_hash": "hash",
+            "deadline": "2030-01-01",
+            "category": "Web Development",
+            "status": "open",
+            "visibility": "invite_only"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let job_id = body["job_id"].as_i64().unwrap();
+
+    assert!(visible_job_ids(&app, &invited).await.is_empty());
+    let apply = json!({ "job_id": job_id });
+    let (status, _) = call(&app, Method::POST, "/jobs/apply", &outsider, apply.clone()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let invite_uri = format!("/jobs/{}/invitations", job_id);
+    let invite = json!({ "username": "rustacean", "message": "Would love your eyes on this" });
+    let (status, body) = call(&app, Method::POST, &invite_uri, &client, invite.clone()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["invitation"]["status"], "pending");
+    let (status, _) = call(&app, Method::POST, &invite_uri, &client, invite).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let kind: String = sqlx::query_scalar("SELECT type FROM notifications WHERE user_id = 2")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(kind, "invited");
+
+    // Only the invited freelancer can now find and apply to the job
+    assert_eq!(visible_job_ids(&app, &invited).await, vec![job_id]);
+    assert!(visible_job_ids(&app, &outsider).await.is_empty());
+    let (status, _) = call(&app, Method::POST, "/jobs/apply", &invited, apply).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Applying answered the invitation
+    let (_, body) = call(&app, Method::GET, &invite_uri, &client, json!({})).await;
+    assert_eq!(body["invitations"][0]["status"], "accepted");
+    let (_, body) = call(&app, Method::GET, "/invitations", &invited, json!({})).await;
+    let invitation_id = body["invitations"][0]["id"].as_i64().unwrap();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/invitations/{}/respond", invitation_id),
+        &invited,
+        json!({ "accept": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::json;
+use sqlx::{Pool, Sqlite};
+
+// A client with an open job and two freelancers with connected wallets
+async fn marketplace(pool: &Pool<Sqlite>) -> i64 {
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'freelancer@example.com', 'freelancer', 'freelancer-wallet'),
+             (3, 'second@example.com', 'freelancer', 'second-wallet')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO jobs (title, description, skills, budget, location, job_type, job_ipfs_hash, deadline, client_id, category, status)
+         VALUES ('Build an API', 'Axum backend', 'rust', 500, 'remote', 'fixed', 'hash', '2030-01-01', 1, 'Web Development', 'open')",
+    )
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid()
+}
+
+async fn propose(app: &Router, token: &str, job_id: i64) -> i64 {
+    let (status, body) = call(
+        app,
+        Method::POST,
+        "/proposals",
+        token,
+        json!({ "job_id": job_id, "cover_letter": "Happy to help", "bid_amount": 500.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["proposal"]["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_job_edits_are_recorded_and_locked_after_hiring() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+    let proposal_id = propose(&app, &freelancer, job_id).await;
+    let job_uri = format!("/jobs/{}", job_id);
+
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &job_uri,
+        &client,
+        json!({ "deadline": "soon" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &job_uri,
+        &client,
+        json!({ "budget": 500 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let edit = json!({ "title": "Build a REST API", "budget": 650 });
+    let (status, body) = call(&app, Method::PATCH, &job_uri, &client, edit).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["changes"]["budget"], json!({ "from": 500, "to": 650 }));
+
+    let (_, body) = call(&app, Method::GET, &job_uri, &client, json!({})).await;
+    assert_eq!(body["title"], "Build a REST API");
+    let (_, body) = call(
+        &app,
+        Method::GET,
+        &format!("{}/revisions", job_uri),
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(
+        body["revisions"][0]["changes"]["title"]["from"],
+        "Build an API"
+    );
+
+    // Only the budget change is material enough to tell the applicant about
+    let message: String = sqlx::query_scalar(
+        "SELECT message FROM notifications WHERE user_id = 2 AND type = 'job_updated'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(message.ends_with("(budget)."));
+
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &format!("/proposals/{}", proposal_id),
+        &client,
+        json!({ "status": "accepted" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &job_uri,
+        &client,
+        json!({ "budget": 700 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("{}/close", job_uri),
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_closed_job_can_be_reposted() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+    let proposal_id = propose(&app, &freelancer, job_id).await;
+    let other_job = sqlx::query(
+        "INSERT INTO jobs (title, description, skills, budget, location, job_type, job_ipfs_hash, deadline, client_id, category, status)
+         VALUES ('Design a logo', 'Vector art', 'design', 200, 'remote', 'fixed', 'hash', '2030-01-01', 1, 'Graphic Design', 'open')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+    let other_proposal = propose(&app, &freelancer, other_job).await;
+    // Past its deadline, but that is the scheduled sweep's business
+    sqlx::query("UPDATE jobs SET deadline = '2020-01-01' WHERE id = ?")
+        .bind(other_job)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let repost = json!({ "deadline": "2031-06-30" });
+    let repost_uri = format!("/jobs/{}/repost", job_id);
+
+    // Still open and before its deadline
+    let (status, _) = call(&app, Method::POST, &repost_uri, &client, repost.clone()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/jobs/{}/close", job_id),
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let statuses: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, status FROM job_applications ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        statuses,
+        vec![
+            (proposal_id, "expired".to_string()),
+            (other_proposal, "submitted".to_string()),
+        ]
+    );
+    let expired_notices: Vec<Option<i64>> =
+        sqlx::query_scalar("SELECT job_id FROM notifications WHERE type = 'expired'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(expired_notices, vec![Some(job_id)]);
+
+    let (status, body) = call(&app, Method::POST, &repost_uri, &client, repost).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let new_job_id = body["job_id"].as_i64().unwrap();
+    let (reposted_from, job_status, deadline): (Option<i64>, String, String) =
+        sqlx::query_as("SELECT reposted_from, status, deadline FROM jobs WHERE id = ?")
+            .bind(new_job_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(reposted_from, Some(job_id));
+    assert_eq!(job_status, "open");
+    assert_eq!(deadline, "2031-06-30");
+}
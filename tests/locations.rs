@@ -0,0 +1,238 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::{json, Value};
+
+fn job(title: &str, remote: bool, place: Value) -> Value {
+    json!({
+        "title": title,
+        "description": "Somewhere in the world",
+        "skills": "Rust",
+        "budget": 500,
+        "location": if remote { "Remote" } else { title },
+        "remote": remote,
+        "place": place,
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open"
+    })
+}
+
+async fn post_job(app: &Router, token: &str, payload: Value) -> i64 {
+    let (status, body) = call(app, Method::POST, "/jobs/create", token, payload).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    body["job_id"].as_i64().unwrap()
+}
+
+async fn ids(app: &Router, token: &str, uri: &str) -> Vec<i64> {
+    let (status, body) = call(app, Method::GET, uri, token, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{}: {}", uri, body);
+    let mut ids: Vec<i64> = body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["id"].as_i64().unwrap())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+async fn setup() -> (Router, String, String) {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool);
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+    (app, client, freelancer)
+}
+
+#[tokio::test]
+async fn test_jobs_filter_by_remote_country_and_radius() {
+    let (app, client, freelancer) = setup().await;
+    let berlin = post_job(
+        &app,
+        &client,
+        job(
+            "Berlin",
+            false,
+            json!({ "country": "de", "city": "Berlin", "latitude": 52.52, "longitude": 13.405 }),
+        ),
+    )
+    .await;
+    // About 27 km from Berlin
+    let potsdam = post_job(
+        &app,
+        &client,
+        job(
+            "Potsdam",
+            false,
+            json!({ "country": "DE", "city": "Potsdam", "latitude": 52.39, "longitude": 13.06 }),
+        ),
+    )
+    .await;
+    let paris = post_job(
+        &app,
+        &client,
+        job(
+            "Paris",
+            false,
+            json!({ "country": "FR", "city": "Paris", "latitude": 48.8566, "longitude": 2.3522 }),
+        ),
+    )
+    .await;
+    let remote = post_job(&app, &client, job("Anywhere", true, json!({}))).await;
+
+    for (uri, expected) in [
+        ("/jobs?remote=true", vec![remote]),
+        ("/jobs?remote=false", vec![berlin, potsdam, paris]),
+        ("/jobs?country=de", vec![berlin, potsdam]),
+        ("/jobs?location=BERLIN", vec![berlin]),
+        (
+            "/jobs?lat=52.52&lng=13.405&radius_km=50",
+            vec![berlin, potsdam],
+        ),
+        ("/jobs?lat=52.52&lng=13.405&radius_km=10", vec![berlin]),
+        (
+            "/jobs?lat=52.52&lng=13.405&radius_km=1000",
+            vec![berlin, potsdam, paris],
+        ),
+    ] {
+        assert_eq!(ids(&app, &freelancer, uri).await, expected, "{}", uri);
+    }
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/jobs?id={}", berlin),
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["jobs"][0]["place"]["country"], "DE");
+    assert_eq!(body["jobs"][0]["remote"], false);
+
+    let (status, _) = call(&app, Method::GET, "/jobs?lat=52.52", &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // NaN slips past range checks, so it is rejected before reaching the SQL
+    for uri in [
+        "/jobs?lat=NaN&lng=13.405&radius_km=50",
+        "/jobs?lat=52.52&lng=13.405&radius_km=NaN",
+        "/jobs?min_overlap_hours=NaN",
+    ] {
+        let (status, _) = call(&app, Method::GET, uri, &freelancer, json!({})).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", uri);
+    }
+    for place in [
+        json!({ "country": "Germany" }),
+        json!({ "latitude": 52.52 }),
+        json!({ "timezone": "Mars/Olympus_Mons" }),
+        json!({ "work_start": "09:00", "work_end": "17:00" }),
+    ] {
+        let (status, _) = call(
+            &app,
+            Method::POST,
+            "/jobs/create",
+            &client,
+            job("Invalid", false, place.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", place);
+    }
+}
+
+#[tokio::test]
+async fn test_jobs_filter_by_working_hours_overlap() {
+    let (app, client, freelancer) = setup().await;
+    // Zones without daylight saving, so the UTC windows are fixed:
+    // Kolkata 09-17 is 03:30-11:30 UTC, Honolulu 09-17 is 19:00-03:00 UTC
+    let kolkata = post_job(
+        &app,
+        &client,
+        job(
+            "Kolkata",
+            true,
+            json!({ "timezone": "Asia/Kolkata", "work_start": "09:00", "work_end": "17:00" }),
+        ),
+    )
+    .await;
+    let honolulu = post_job(
+        &app,
+        &client,
+        job(
+            "Honolulu",
+            true,
+            json!({ "timezone": "Pacific/Honolulu", "work_start": "09:00", "work_end": "17:00" }),
+        ),
+    )
+    .await;
+    post_job(&app, &client, job("No hours", true, json!({}))).await;
+
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        "/jobs?min_overlap_hours=1",
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let profile = |timezone: &str, start: &str, end: &str| {
+        json!({
+            "username": "night_owl",
+            "role": "freelancer",
+            "skills": "Rust",
+            "profile_ipfs_hash": "hash",
+            "place": { "timezone": timezone, "work_start": start, "work_end": end }
+        })
+    };
+    // Lagos 08-16 is 07:00-15:00 UTC: 4.5 hours with Kolkata, none with Honolulu
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/profile",
+        &freelancer,
+        profile("Africa/Lagos", "08:00", "16:00"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        ids(&app, &freelancer, "/jobs?min_overlap_hours=4").await,
+        vec![kolkata]
+    );
+
+    // Tokyo 06-14 is 21:00-05:00 UTC: 6 hours with Honolulu across midnight, 1.5
+    // with Kolkata
+    call(
+        &app,
+        Method::POST,
+        "/profile",
+        &freelancer,
+        profile("Asia/Tokyo", "06:00", "14:00"),
+    )
+    .await;
+    assert_eq!(
+        ids(&app, &freelancer, "/jobs?min_overlap_hours=4").await,
+        vec![honolulu]
+    );
+    assert_eq!(
+        ids(&app, &freelancer, "/jobs?min_overlap_hours=1.5").await,
+        vec![kolkata, honolulu]
+    );
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::{json, Value};
+
+fn job(title: &str, budget: i64) -> Value {
+    json!({
+        "title": title,
+        "description": "Paging through listings",
+        "skills": "Rust",
+        "budget": budget,
+        "location": "remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open"
+    })
+}
+
+// Follows next_cursor to the end, calling `between` after the first page
+async fn walk(
+    app: &Router,
+    token: &str,
+    uri: &str,
+    list: &str,
+    between: impl std::future::Future<Output = ()>,
+) -> Vec<i64> {
+    let mut between = Some(between);
+    let mut ids = Vec::new();
+    let mut page = call(app, Method::GET, uri, token, json!({})).await;
+    loop {
+        let (status, body) = page;
+        assert_eq!(status, StatusCode::OK);
+        let rows = body[list].as_array().unwrap();
+        assert!(rows.len() <= 2);
+        ids.extend(rows.iter().map(|row| row["id"].as_i64().unwrap()));
+        if let Some(between) = between.take() {
+            between.await;
+        }
+        let Some(cursor) = body["next_cursor"].as_str() else {
+            return ids;
+        };
+        let next = format!("{}&cursor={}", uri, cursor);
+        page = call(app, Method::GET, &next, token, json!({})).await;
+    }
+}
+
+#[tokio::test]
+async fn test_job_pages_follow_the_cursor_without_skips_or_repeats() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let mut jobs = Vec::new();
+    for (title, budget) in [
+        ("Cheap", 100),
+        ("Even A", 300),
+        ("Even B", 300),
+        ("Even C", 300),
+        ("Pricey", 900),
+    ] {
+        let (status, body) = call(
+            &app,
+            Method::POST,
+            "/jobs/create",
+            &client,
+            job(title, budget),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        jobs.push(body["job_id"].as_i64().unwrap());
+    }
+    let [cheap, even_a, even_b, even_c, pricey] = jobs[..] else {
+        unreachable!()
+    };
+
+    // A job posted mid-way sorts ahead of the pages already seen, so it neither
+    // shifts the later pages nor shows up in them
+    let ids = walk(
+        &app,
+        &freelancer,
+        "/jobs?sort_by=budget:desc&limit=2",
+        "jobs",
+        async {
+            let (status, _) = call(
+                &app,
+                Method::POST,
+                "/jobs/create",
+                &client,
+                job("Late", 1000),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        },
+    )
+    .await;
+    assert_eq!(ids, vec![pricey, even_c, even_b, even_a, cheap]);
+
+    // Jobs posted in the same second still page in a stable order
+    let ids = walk(
+        &app,
+        &freelancer,
+        "/jobs?sort_by=posted_at:asc&limit=2",
+        "jobs",
+        async {},
+    )
+    .await;
+    assert_eq!(ids.len(), 6);
+    assert_eq!(ids[..5], jobs[..]);
+
+    let (_, first) = call(
+        &app,
+        Method::GET,
+        "/jobs?sort_by=budget:desc&limit=2",
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    let cursor = first["next_cursor"].as_str().unwrap();
+    for uri in [
+        "/jobs?cursor=not-a-cursor".to_string(),
+        format!("/jobs?sort_by=budget:asc&cursor={}", cursor),
+    ] {
+        let (status, _) = call(&app, Method::GET, &uri, &freelancer, json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_notifications_and_proposals_are_paged() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'freelancer@example.com', 'freelancer', 'freelancer-wallet')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    for message in ["first", "second", "third"] {
+        sqlx::query(
+            "INSERT INTO notifications (user_id, message, read, type) VALUES (1, ?, 0, 'applied')",
+        )
+        .bind(message)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let ids = walk(
+        &app,
+        &client,
+        "/notifications?limit=2",
+        "notifications",
+        async {},
+    )
+    .await;
+    assert_eq!(ids, vec![3, 2, 1]);
+
+    let mut jobs = Vec::new();
+    for title in ["Beta", "Alpha", "Gamma"] {
+        let (_, body) = call(&app, Method::POST, "/jobs/create", &client, job(title, 500)).await;
+        let job_id = body["job_id"].as_i64().unwrap();
+        let (status, body) = call(
+            &app,
+            Method::POST,
+            "/proposals",
+            &freelancer,
+            json!({
+                "job_id": job_id,
+                "cover_letter": "I have built several Axum services",
+                "bid_amount": 450.0
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        jobs.push(body["proposal"]["id"].as_i64().unwrap());
+    }
+    let ids = walk(
+        &app,
+        &freelancer,
+        "/proposals/me?sort_by=job.title&limit=2",
+        "proposals",
+        async {},
+    )
+    .await;
+    assert_eq!(ids, vec![jobs[1], jobs[0], jobs[2]]);
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{
+    db::repo::ApplicationRepo, handlers::proposal::expire_stale_proposals, routes,
+    utils::generate_jwt,
+};
+use serde_json::json;
+use sqlx::{Pool, Sqlite};
+
+// A client with an open job and freelancers 2-4 with connected wallets
+async fn marketplace(pool: &Pool<Sqlite>) -> i64 {
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'freelancer@example.com', 'freelancer', 'freelancer-wallet'),
+             (3, 'second@example.com', 'freelancer', 'second-wallet'),
+             (4, 'third@example.com', 'freelancer', 'third-wallet')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO jobs (title, description, skills, budget, location, job_type, job_ipfs_hash, deadline, client_id, category, status)
+         VALUES ('Build an API', 'Axum backend', 'rust', 500, 'remote', 'fixed', 'hash', '2030-01-01', 1, 'Web Development', 'open')",
+    )
+    .execute(pool)
+    .await
+    .unwrap()
+    .last_insert_rowid()
+}
+
+async fn propose(app: &Router, token: &str, job_id: i64, bid_amount: f64) -> i64 {
+    let (status, body) = call(
+        app,
+        Method::POST,
+        "/proposals",
+        token,
+        json!({
+            "job_id": job_id,
+            "cover_letter": "I have built several Axum services",
+            "bid_amount": bid_amount
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["proposal"]["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_counter_offer_terms_become_the_escrow_amount() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/proposals",
+        &freelancer,
+        json!({
+            "job_id": job_id,
+            "cover_letter": "I have built several Axum services",
+            "bid_amount": 500.0,
+            "timeline_days": 10
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let proposal_id = body["proposal"]["id"].as_i64().unwrap();
+    let offers_uri = format!("/proposals/{}/offers", proposal_id);
+
+    // Freelancer's bid is standing, so they can't counter themselves
+    let counter = json!({ "amount": 450.0, "timeline_days": 12 });
+    let (status, _) = call(&app, Method::POST, &offers_uri, &freelancer, counter).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let counter = json!({ "amount": 400.0, "timeline_days": 14, "message": "Tighter budget" });
+    let (status, _) = call(&app, Method::POST, &offers_uri, &client, counter).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // The client can't accept the counter they just made
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &format!("/proposals/{}", proposal_id),
+        &client,
+        json!({ "status": "accepted" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        &format!("{}/accept", offers_uri),
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["proposal"]["status"], "accepted");
+    assert_eq!(body["proposal"]["agreed_amount"], 400.0);
+
+    let (status, body) = call(&app, Method::GET, &offers_uri, &client, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let amounts: Vec<f64> = body["offers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|offer| offer["amount"].as_f64().unwrap())
+        .collect();
+    assert_eq!(amounts, vec![500.0, 400.0]);
+
+    let work = pool.approved_work(job_id).await.unwrap().unwrap();
+    assert_eq!(work.agreed_amount, Some(400.0));
+    assert_eq!(work.agreed_timeline_days, Some(14));
+}
+
+#[tokio::test]
+async fn test_edit_withdraw_and_auto_reject() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let [first, second, third] = [2, 3, 4]
+        .map(|id| generate_jwt(&state.config, id, "freelancer".to_string()).unwrap());
+
+    let hired = propose(&app, &first, job_id, 500.0).await;
+    let withdrawn = propose(&app, &second, job_id, 480.0).await;
+    let passed_over = propose(&app, &third, job_id, 520.0).await;
+
+    let edit = json!({ "bid_amount": 450.0 });
+    let uri = format!("/proposals/{}/edit", hired);
+    let (status, body) = call(&app, Method::POST, &uri, &first, edit.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["proposal"]["bid_amount"], 450.0);
+    // Only the freelancer who sent it may edit
+    let (status, _) = call(&app, Method::POST, &uri, &second, edit).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let uri = format!("/proposals/{}/revisions", hired);
+    let (_, body) = call(&app, Method::GET, &uri, &client, json!({})).await;
+    assert_eq!(body["revisions"][0]["bid_amount"], 500.0);
+
+    let uri = format!("/proposals/{}/withdraw", withdrawn);
+    let (status, _) = call(&app, Method::POST, &uri, &second, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(&app, Method::POST, &uri, &second, json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Accepting takes the edited bid and rejects everyone still waiting
+    let (status, body) = call(
+        &app,
+        Method::PATCH,
+        &format!("/proposals/{}", hired),
+        &client,
+        json!({ "status": "accepted" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["proposal"]["agreed_amount"], 450.0);
+
+    let statuses: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, status FROM job_applications ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        statuses,
+        vec![
+            (hired, "accepted".to_string()),
+            (withdrawn, "withdrawn".to_string()),
+            (passed_over, "rejected".to_string()),
+        ]
+    );
+    let notified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = 4 AND type = 'rejected'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(notified, 1);
+}
+
+// Proposals migrated from before the freelancer connected a wallet have none
+#[tokio::test]
+async fn test_accepting_needs_a_payout_wallet() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let proposal_id = propose(&app, &freelancer, job_id, 500.0).await;
+    sqlx::query("UPDATE job_applications SET freelancer_wallet = NULL WHERE id = ?")
+        .bind(proposal_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let accept = json!({ "status": "accepted" });
+    let uri = format!("/proposals/{}", proposal_id);
+    let (status, _) = call(&app, Method::PATCH, &uri, &client, accept.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let wallet = json!({ "payout_wallet": "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV" });
+    let edit_uri = format!("/proposals/{}/edit", proposal_id);
+    let (status, _) = call(&app, Method::POST, &edit_uri, &freelancer, wallet).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = call(&app, Method::PATCH, &uri, &client, accept).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["proposal"]["freelancer_wallet"],
+        "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV"
+    );
+}
+
+#[tokio::test]
+async fn test_proposals_expire_after_the_deadline() {
+    let pool = setup_db().await;
+    let job_id = marketplace(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let proposal_id = propose(&app, &freelancer, job_id, 500.0).await;
+    assert_eq!(expire_stale_proposals(&pool, &state.notifications).await.unwrap(), 0);
+
+    sqlx::query("UPDATE jobs SET deadline = '2020-01-01' WHERE id = ?")
+        .bind(job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(expire_stale_proposals(&pool, &state.notifications).await.unwrap(), 1);
+
+    let proposal = pool.proposal(proposal_id).await.unwrap().unwrap();
+    assert_eq!(proposal.status.as_str(), "expired");
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("/proposals/{}/edit", proposal_id),
+        &freelancer,
+        json!({ "cover_letter": "Still keen on this one" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use axum::{
+    http::{header, HeaderMap, Method, StatusCode},
+    Router,
+};
+use common::{json_response, send, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::{json, Value};
+
+// `token` is None for anonymous requests
+async fn call(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Value,
+) -> (StatusCode, HeaderMap, Value) {
+    let response = send(app, method, uri, token, body).await;
+    let headers = response.headers().clone();
+    let (status, body) = json_response(response).await;
+    (status, headers, body)
+}
+
+fn job(title: &str, visibility: &str) -> Value {
+    json!({
+        "title": title,
+        "description": "Looking for help",
+        "skills": "Rust",
+        "budget": 500,
+        "location": "Remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open",
+        "visibility": visibility
+    })
+}
+
+fn ids(body: &Value) -> Vec<i64> {
+    let mut ids: Vec<i64> = body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["id"].as_i64().unwrap())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[tokio::test]
+async fn test_anonymous_visitors_see_open_public_jobs_redacted() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'other@example.com', 'client')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let other = generate_jwt(&state.config, 2, "client".to_string()).unwrap();
+
+    let mut jobs = Vec::new();
+    for (token, title, visibility) in [
+        (&client, "Public", "public"),
+        (&client, "Invite only", "invite_only"),
+        (&client, "Closed", "public"),
+        (&other, "Someone else's", "public"),
+    ] {
+        let (status, _, body) = call(
+            &app,
+            Method::POST,
+            "/jobs/create",
+            Some(token),
+            job(title, visibility),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        jobs.push(body["job_id"].as_i64().unwrap());
+    }
+    let [public, invite_only, closed, elsewhere] = jobs[..] else {
+        unreachable!()
+    };
+    sqlx::query("UPDATE jobs SET status = 'closed' WHERE id = ?")
+        .bind(closed)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Anonymous: open public jobs only, whatever status is asked for
+    let (status, headers, body) =
+        call(&app, Method::GET, "/jobs?status=closed", None, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec![public, elsewhere]);
+    assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=60");
+    let listed = body["jobs"][0].as_object().unwrap();
+    for hidden in ["client_id", "job_ipfs_hash", "visibility", "place"] {
+        assert!(!listed.contains_key(hidden), "{}", hidden);
+    }
+
+    // The anonymous listing is served from cache for a while
+    let (_, _, body) = call(&app, Method::GET, "/jobs", None, json!({})).await;
+    assert_eq!(ids(&body), vec![public, elsewhere]);
+    call(
+        &app,
+        Method::POST,
+        "/jobs/create",
+        Some(&other),
+        job("Fresh", "public"),
+    )
+    .await;
+    let (_, _, body) = call(&app, Method::GET, "/jobs", None, json!({})).await;
+    assert_eq!(ids(&body), vec![public, elsewhere]);
+
+    let (status, _, _) = call(&app, Method::GET, "/jobs", Some("not-a-token"), json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, headers, _) = call(&app, Method::GET, "/jobs/categories", None, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=60");
+
+    // Signed-in clients browse everyone's public jobs plus their own, and list
+    // just their own under /jobs/posted
+    let (status, _, body) = call(&app, Method::GET, "/jobs", Some(&client), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(ids(&body).contains(&elsewhere) && ids(&body).contains(&invite_only));
+    assert!(body["jobs"][0]["client_id"].is_i64());
+    let (status, _, body) = call(&app, Method::GET, "/jobs/posted", Some(&client), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec![public, invite_only, closed]);
+    let (status, _, _) = call(&app, Method::GET, "/jobs/posted", None, json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
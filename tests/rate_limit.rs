@@ -0,0 +1,185 @@
+mod common;
+
+use axum::{
+    body::to_bytes,
+    http::{header::RETRY_AFTER, Method, StatusCode},
+    response::Response,
+    Router,
+};
+use common::{send, setup_db, test_state};
+use freelancia_backend::{
+    middleware::rate_limit::{AuthRateLimits, EndpointLimit, Quota},
+    routes,
+    utils::{generate_totp_secret, hash_password},
+};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+use std::time::Duration;
+
+fn app(pool: Pool<Sqlite>, limits: AuthRateLimits) -> Router {
+    routes::auth_routes_with_limits(test_state(pool), limits)
+}
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> Response {
+    send(app, Method::POST, uri, None, body).await
+}
+
+#[tokio::test]
+async fn test_login_lockout_after_repeated_failures() {
+    let pool = setup_db().await;
+    // Generous request quotas so only the account lockout is exercised
+    let limits = AuthRateLimits {
+        login: EndpointLimit {
+            per_ip: Quota::new(100, Duration::from_secs(60)),
+            per_account: None,
+        },
+        ..AuthRateLimits::default()
+    };
+    let app = app(pool.clone(), limits);
+
+    sqlx::query("INSERT INTO users (email, password, role) VALUES (?, ?, ?)")
+        .bind("client@example.com")
+        .bind(hash_password("password123").unwrap())
+        .bind("client")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for _ in 0..5 {
+        let response = post_json(
+            &app,
+            "/login",
+            json!({ "email": "client@example.com", "password": "wrong_password" }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Even the right password is refused while locked
+    let response = post_json(
+        &app,
+        "/login",
+        json!({ "email": "client@example.com", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key(RETRY_AFTER));
+
+    // Once the lock has lapsed a correct login succeeds and clears the counters
+    sqlx::query("UPDATE users SET locked_until = 0")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let response = post_json(
+        &app,
+        "/login",
+        json!({ "email": "client@example.com", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let locked_until: Option<i64> = sqlx::query_scalar("SELECT locked_until FROM users")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(locked_until, None);
+}
+
+#[tokio::test]
+async fn test_wrong_second_factors_lock_the_account() {
+    let pool = setup_db().await;
+    let limits = AuthRateLimits {
+        login: EndpointLimit {
+            per_ip: Quota::new(100, Duration::from_secs(60)),
+            per_account: None,
+        },
+        ..AuthRateLimits::default()
+    };
+    let app = app(pool.clone(), limits);
+
+    sqlx::query(
+        "INSERT INTO users (email, password, role, totp_secret, totp_enabled) VALUES (?, ?, ?, ?, TRUE)",
+    )
+    .bind("client@example.com")
+    .bind(hash_password("password123").unwrap())
+    .bind("client")
+    .bind(generate_totp_secret())
+    .execute(&pool)
+    .await
+    .unwrap();
+    let login = json!({ "email": "client@example.com", "password": "password123" });
+
+    // A correct password doesn't reset the count of wrong codes, so logging in
+    // again can't buy an attacker fresh guesses
+    for _ in 0..5 {
+        let response = post_json(&app, "/login", login.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), 4096).await.unwrap()).unwrap();
+        let response = post_json(
+            &app,
+            "/login/2fa",
+            json!({ "pending_token": body["pending_token"], "recovery_code": "wrong-code" }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = post_json(&app, "/login", login).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_wallet_login_is_rate_limited_per_wallet() {
+    let pool = setup_db().await;
+    let app = app(pool, AuthRateLimits::default());
+    let login = json!({
+        "wallet_address": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+        "nonce": "unknown",
+        "signature": "forged",
+    });
+
+    for _ in 0..5 {
+        let response = post_json(&app, "/wallet/login", login.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = post_json(&app, "/wallet/login", login).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_request_nonce_is_rate_limited_per_wallet() {
+    let pool = setup_db().await;
+    let app = app(pool.clone(), AuthRateLimits::default());
+    let wallet = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+    for _ in 0..5 {
+        let response = post_json(
+            &app,
+            "/wallet/request-nonce",
+            json!({ "wallet_address": wallet }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = post_json(
+        &app,
+        "/wallet/request-nonce",
+        json!({ "wallet_address": wallet }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body: Value =
+        serde_json::from_slice(&to_bytes(response.into_body(), 4096).await.unwrap()).unwrap();
+    assert!(body["error"]["retry_after"].as_u64().unwrap() >= 1);
+
+    // Repeated requests replace the nonce rather than piling up rows
+    let nonces: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM nonces WHERE wallet_address = ?")
+        .bind(wallet)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(nonces, 1);
+}
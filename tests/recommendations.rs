@@ -0,0 +1,140 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{db::repo::SkillRepo, routes, utils::generate_jwt};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+
+fn job(skills: &str, budget: i64) -> Value {
+    json!({
+        "title": "Dashboard work",
+        "description": "Build the admin dashboard",
+        "skills": skills,
+        "budget": budget,
+        "location": "remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open"
+    })
+}
+
+async fn add_profile(pool: &Pool<Sqlite>, user_id: i64, skills: &str) {
+    sqlx::query("INSERT INTO profiles (user_id, username, role) VALUES (?, ?, 'freelancer')")
+        .bind(user_id)
+        .bind(format!("freelancer{}", user_id))
+        .execute(pool)
+        .await
+        .unwrap();
+    pool.set_profile_skills(user_id, Some(skills))
+        .await
+        .unwrap();
+}
+
+fn ids(body: &Value, list: &str, key: &str) -> Vec<i64> {
+    body[list]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item[key].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_jobs_and_freelancers_are_ranked_for_each_other() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'react@example.com', 'freelancer'),
+             (3, 'go@example.com', 'freelancer'),
+             (4, 'frontend@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    add_profile(&pool, 2, "React, TypeScript").await;
+    add_profile(&pool, 3, "Go").await;
+    add_profile(&pool, 4, "React").await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let mut jobs = Vec::new();
+    for (skills, budget) in [
+        ("React", 400),
+        ("React, TypeScript", 500),
+        ("Go", 500),
+        ("React, Python", 5000),
+    ] {
+        let (status, body) = call(
+            &app,
+            Method::POST,
+            "/jobs/create",
+            &client,
+            job(skills, budget),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        jobs.push(body["job_id"].as_i64().unwrap());
+    }
+    let [past, full, go, partial] = jobs[..] else {
+        unreachable!()
+    };
+
+    // A past hire sets the freelancer's usual budget and category
+    sqlx::query(
+        "INSERT INTO job_applications (user_id, job_id, freelancer_wallet, status, approved, agreed_amount)
+         VALUES (2, ?, 'wallet', 'accepted', 1, 450)",
+    )
+    .bind(past)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO job_user_interactions (user_id, job_id, applied) VALUES (2, ?, TRUE)")
+        .bind(past)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        "/jobs/recommended",
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body, "jobs", "id"), vec![full, partial, go]);
+    assert_eq!(
+        body["jobs"][0]["matched_skills"],
+        json!(["React", "TypeScript"])
+    );
+    assert_eq!(body["jobs"][1]["breakdown"]["budget"], 0.0);
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        &format!("/jobs/{}/suggested-freelancers", full),
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body, "freelancers", "user_id"), vec![2, 4]);
+    assert_eq!(body["freelancers"][0]["hires"], 1);
+
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        &format!("/jobs/{}/suggested-freelancers", full),
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
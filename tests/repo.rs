@@ -0,0 +1,209 @@
+mod common;
+
+use common::setup_db;
+use freelancia_backend::{
+    db::{
+        pagination::PageQuery,
+        repo::{
+            ApplicationRepo, CategoryRepo, DeliverableRepo, JobRepo, NewApplication,
+            NewNotification, NotificationRepo,
+        },
+    },
+    models::{
+        job::{JobRequest, JobType, JobVisibility},
+        location::Place,
+        proposal::{ProposalFilter, ProposalStatus},
+    },
+    notifications::NotificationBroadcaster,
+};
+use sqlx::{Pool, Sqlite};
+
+async fn insert_user(pool: &Pool<Sqlite>, email: &str, role: &str) -> i64 {
+    sqlx::query("INSERT INTO users (email, wallet_address, role) VALUES (?, ?, ?)")
+        .bind(email)
+        .bind(format!("wallet-{}", email))
+        .bind(role)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid()
+}
+
+fn job_request() -> JobRequest {
+    JobRequest {
+        title: "Build an API".to_string(),
+        description: "Axum backend".to_string(),
+        skills: "rust".to_string(),
+        budget: 500,
+        location: "remote".to_string(),
+        remote: true,
+        place: Place::default(),
+        job_type: JobType::Fixed,
+        weekly_hour_cap: None,
+        job_ipfs_hash: "hash".to_string(),
+        deadline: "2030-01-01".to_string(),
+        category: "Web Development".to_string(),
+        status: "open".to_string(),
+        visibility: JobVisibility::Public,
+    }
+}
+
+async fn create_job(pool: &Pool<Sqlite>, client: i64) -> i64 {
+    let category = pool
+        .resolve_category("Web Development")
+        .await
+        .unwrap()
+        .unwrap();
+    pool.create_job(client, &job_request(), category.id)
+        .await
+        .unwrap()
+}
+
+// A job with two applicants where only the second has been hired and delivered
+async fn hired_job(pool: &Pool<Sqlite>) -> (i64, i64, i64, i64) {
+    let client = insert_user(pool, "client@example.com", "client").await;
+    let first = insert_user(pool, "first@example.com", "freelancer").await;
+    let hired = insert_user(pool, "hired@example.com", "freelancer").await;
+
+    let job_id = create_job(pool, client).await;
+    pool.create_application(&NewApplication::new(first, job_id, "wallet-first"))
+        .await
+        .unwrap();
+    let application_id = pool
+        .create_application(&NewApplication::new(hired, job_id, "wallet-hired"))
+        .await
+        .unwrap();
+    pool.approve_application(application_id, job_id, 500.0, None)
+        .await
+        .unwrap();
+    pool.create_deliverable(application_id, "work-hash").await.unwrap();
+
+    (client, hired, job_id, application_id)
+}
+
+#[tokio::test]
+async fn test_deliverable_lookup_follows_the_approved_application() {
+    let pool = setup_db().await;
+    let (client, hired, job_id, application_id) = hired_job(&pool).await;
+
+    assert_eq!(pool.job_status(job_id).await.unwrap().as_deref(), Some("closed"));
+
+    let deliverable = pool.deliverable_for_job(job_id).await.unwrap().unwrap();
+    assert_eq!(deliverable.application_id, application_id);
+    assert_eq!(deliverable.freelancer_id, hired);
+    assert_eq!(deliverable.client_id, client);
+    assert!(deliverable.submitted && !deliverable.disputed);
+
+    // No profiles exist, which must not hide the application
+    let application = pool.find_application(application_id).await.unwrap().unwrap();
+    assert!(application.approved);
+    assert_eq!(application.freelancer_username, None);
+
+    // Applying twice is a conflict, not a database error
+    let err = pool
+        .create_application(&NewApplication::new(hired, job_id, "wallet-hired"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "conflict");
+}
+
+#[tokio::test]
+async fn test_approving_is_a_conflict_once_the_job_has_a_hire() {
+    let pool = setup_db().await;
+    let (_, _, job_id, application_id) = hired_job(&pool).await;
+
+    // A late applicant still awaiting a decision can't become a second hire
+    let late = insert_user(&pool, "late@example.com", "freelancer").await;
+    let late_application = pool
+        .create_application(&NewApplication::new(late, job_id, "wallet-late"))
+        .await
+        .unwrap();
+    let err = pool
+        .approve_application(late_application, job_id, 400.0, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "conflict");
+    assert_eq!(pool.approved_freelancers(job_id).await.unwrap().len(), 1);
+
+    // Nor can the hire be approved twice
+    let err = pool
+        .approve_application(application_id, job_id, 500.0, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "conflict");
+}
+
+#[tokio::test]
+async fn test_notify_stores_and_publishes_once() {
+    let pool = setup_db().await;
+    let (client, hired, job_id, _) = hired_job(&pool).await;
+    let notifications = NotificationBroadcaster::new();
+    let mut events = notifications.subscribe();
+
+    notifications
+        .notify(
+            &pool,
+            NewNotification::for_job(client, "review", "Work submitted", job_id, hired),
+        )
+        .await
+        .unwrap();
+
+    let event = events.try_recv().unwrap();
+    assert_eq!(event.user_id, client);
+    assert_eq!(event.kind, "review");
+
+    // Two applications on the job still list the notification once
+    let (stored, next_cursor) = pool
+        .notifications_for_user(client, &PageQuery::default())
+        .await
+        .unwrap();
+    assert!(next_cursor.is_none());
+    assert_eq!(stored.len(), 1);
+    assert!(!stored[0].read);
+
+    assert!(!pool.mark_notification_read(stored[0].id, hired).await.unwrap());
+    assert!(pool.mark_notification_read(stored[0].id, client).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_proposal_terms_live_on_the_application() {
+    let pool = setup_db().await;
+    let client = insert_user(&pool, "client@example.com", "client").await;
+    let freelancer = insert_user(&pool, "bidder@example.com", "freelancer").await;
+    let job_id = create_job(&pool, client).await;
+
+    let application_id = pool
+        .create_application(&NewApplication {
+            cover_letter: Some("I have shipped three Axum services".to_string()),
+            bid_amount: Some(450.0),
+            timeline_days: Some(14),
+            ..NewApplication::new(freelancer, job_id, "payout-wallet")
+        })
+        .await
+        .unwrap();
+
+    let proposals = pool.proposals_for_job(job_id).await.unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].id, application_id);
+    assert_eq!(proposals[0].bid_amount, Some(450.0));
+    assert_eq!(
+        proposals[0].freelancer_wallet.as_deref(),
+        Some("payout-wallet")
+    );
+    assert_eq!(proposals[0].status, ProposalStatus::Submitted);
+
+    // Rejecting is a one-way decision
+    assert!(pool.reject_application(application_id).await.unwrap());
+    assert!(!pool.reject_application(application_id).await.unwrap());
+
+    let filter = ProposalFilter {
+        status: Some(ProposalStatus::Rejected),
+        sort_by: "status".to_string(),
+    };
+    let (mine, _) = pool
+        .proposals_for_freelancer(freelancer, &filter, &PageQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine[0].status, ProposalStatus::Rejected);
+}
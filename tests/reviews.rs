@@ -0,0 +1,163 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{db::repo::JobRepo, routes, utils::generate_jwt};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+
+// Client 1 hired freelancer 2 on job 1 (with a disputed deliverable); freelancer
+// 3 is an outsider, and freelancer 2 has also applied to client 1's open job 2
+async fn seed(pool: &Pool<Sqlite>) {
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer'),
+             (3, 'outsider@example.com', 'freelancer');
+         INSERT INTO profiles (user_id, username, role) VALUES
+             (1, 'acme', 'client'),
+             (2, 'rustacean', 'freelancer'),
+             (3, 'bystander', 'freelancer');
+         INSERT INTO jobs (id, title, description, skills, budget, location, job_type, job_ipfs_hash, deadline, client_id, category, status)
+             VALUES (1, 'Build an API', 'Axum backend', 'rust', 500, 'remote', 'fixed', 'hash', '2030-01-01', 1, 'Web Development', 'closed'),
+                    (2, 'Add a cache', 'Redis layer', 'rust', 300, 'remote', 'fixed', 'hash', '2030-01-01', 1, 'Web Development', 'open');
+         INSERT INTO job_applications (id, user_id, job_id, freelancer_wallet, status, approved) VALUES
+             (1, 2, 1, 'wallet', 'accepted', 1),
+             (2, 2, 2, 'wallet', 'submitted', 0);
+         INSERT INTO job_deliverables (application_id, ipfs_hash, submitted, disputed_at)
+             VALUES (1, 'work', 1, '2025-01-01 00:00:00');",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn review(rating: i64) -> Value {
+    json!({ "rating": rating, "review": "Good to work with", "review_ipfs_hash": "review-hash" })
+}
+
+#[tokio::test]
+async fn test_reviews_stay_hidden_until_both_sides_submit() {
+    let pool = setup_db().await;
+    seed(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+    let outsider = generate_jwt(&state.config, 3, "freelancer".to_string()).unwrap();
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/jobs/1/reviews",
+        &freelancer,
+        review(5),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    pool.set_job_status(1, "completed").await.unwrap();
+    let (status, _) = call(&app, Method::POST, "/jobs/1/reviews", &outsider, review(1)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/jobs/1/reviews",
+        &freelancer,
+        review(5),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["review"]["reviewee_id"], 1);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/jobs/1/reviews",
+        &freelancer,
+        review(4),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Only the author sees it until the client reviews back
+    let (_, body) = call(&app, Method::GET, "/jobs/1/reviews", &client, json!({})).await;
+    assert!(body["reviews"].as_array().unwrap().is_empty());
+    let (_, body) = call(&app, Method::GET, "/jobs/1/reviews", &freelancer, json!({})).await;
+    assert_eq!(body["reviews"].as_array().unwrap().len(), 1);
+    let (_, body) = call(&app, Method::GET, "/users/1/reviews", &outsider, json!({})).await;
+    assert!(body["reviews"].as_array().unwrap().is_empty());
+    assert_eq!(body["reputation"]["average_rating"], Value::Null);
+
+    let (status, _) = call(&app, Method::POST, "/jobs/1/reviews", &client, review(4)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, body) = call(&app, Method::GET, "/jobs/1/reviews", &outsider, json!({})).await;
+    assert_eq!(body["reviews"].as_array().unwrap().len(), 2);
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        "/get-profile-userId/2",
+        &outsider,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["reputation"],
+        json!({
+            "average_rating": 4.0,
+            "review_count": 1,
+            "contracts": 1,
+            "completion_rate": 1.0,
+            "dispute_rate": 1.0
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_closed_window_reveals_one_sided_reviews_and_rejects_new_ones() {
+    let pool = setup_db().await;
+    seed(&pool).await;
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    pool.set_job_status(1, "completed").await.unwrap();
+    let (status, _) = call(&app, Method::POST, "/jobs/1/reviews", &client, review(3)).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Completed longer ago than the default 14 day window
+    sqlx::query("UPDATE jobs SET completed_at = datetime('now', '-15 days') WHERE id = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        "/jobs/1/reviews",
+        &freelancer,
+        review(5),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = call(&app, Method::GET, "/users/2/reviews", &client, json!({})).await;
+    assert_eq!(body["reviews"].as_array().unwrap().len(), 1);
+    assert_eq!(body["reputation"]["average_rating"], 3.0);
+
+    let (status, body) = call(&app, Method::GET, "/jobs/2/applicants", &client, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let reputation = &body["applicants"][0]["reputation"];
+    assert_eq!(reputation["review_count"], 1);
+    assert_eq!(reputation["contracts"], 1);
+
+    // Another client can't list someone else's applicants
+    sqlx::query("INSERT INTO users (id, email, role) VALUES (4, 'rival@example.com', 'client')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let rival = generate_jwt(&state.config, 4, "client".to_string()).unwrap();
+    let (status, _) = call(&app, Method::GET, "/jobs/2/applicants", &rival, json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
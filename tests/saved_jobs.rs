@@ -0,0 +1,112 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{
+    handlers::saved_search::alert_saved_searches, routes, utils::generate_jwt,
+};
+use serde_json::{json, Value};
+
+fn job(skills: &str) -> Value {
+    json!({
+        "title": format!("{} work", skills),
+        "description": "Build the admin dashboard",
+        "skills": skills,
+        "budget": 500,
+        "location": "remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open"
+    })
+}
+
+#[tokio::test]
+async fn test_saved_jobs_and_search_alerts() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let (_, body) = call(&app, Method::POST, "/jobs/create", &client, job("React")).await;
+    let existing = body["job_id"].as_i64().unwrap();
+
+    let save = format!("/jobs/{}/save", existing);
+    let (status, _) = call(&app, Method::POST, &save, &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = call(&app, Method::POST, &save, &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = call(&app, Method::GET, "/jobs/saved", &freelancer, json!({})).await;
+    assert_eq!(body["jobs"][0]["id"], existing);
+    let (_, body) = call(
+        &app,
+        Method::GET,
+        &format!("/jobs/{}/status", existing),
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(body["saved"], true);
+    let (status, _) = call(&app, Method::DELETE, &save, &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(&app, Method::DELETE, &save, &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        "/saved-searches",
+        &freelancer,
+        json!({ "name": "React gigs", "query": { "skills": "react" } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["saved_search"]["alerts"], true);
+
+    // Jobs that existed before the search was saved don't alert
+    assert_eq!(
+        alert_saved_searches(&pool, &state.notifications)
+            .await
+            .unwrap(),
+        0
+    );
+    let (_, body) = call(&app, Method::POST, "/jobs/create", &client, job("React")).await;
+    let fresh = body["job_id"].as_i64().unwrap();
+    call(&app, Method::POST, "/jobs/create", &client, job("Go")).await;
+
+    assert_eq!(
+        alert_saved_searches(&pool, &state.notifications)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        alert_saved_searches(&pool, &state.notifications)
+            .await
+            .unwrap(),
+        0
+    );
+    let alerts: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT message, job_id FROM notifications WHERE user_id = 2 AND type = 'saved_search'",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        alerts,
+        vec![(
+            "New job matching your saved search \"React gigs\": React work".to_string(),
+            fresh
+        )]
+    );
+}
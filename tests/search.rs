@@ -0,0 +1,231 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::{json, Value};
+
+fn job(title: &str, description: &str, skills: &str, category: &str) -> Value {
+    json!({
+        "title": title,
+        "description": description,
+        "skills": skills,
+        "budget": 500,
+        "location": "remote",
+        "job_type": "fixed",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": category,
+        "status": "open"
+    })
+}
+
+async fn search(app: &Router, token: &str, keyword: &str) -> (StatusCode, Value) {
+    let keyword: String = keyword
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    call(
+        app,
+        Method::GET,
+        &format!("/jobs?keyword={}", keyword),
+        token,
+        json!({}),
+    )
+    .await
+}
+
+fn ids(body: &Value) -> Vec<i64> {
+    body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["id"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_keyword_search_ranks_highlights_and_forgives_typos() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let mut jobs = Vec::new();
+    for payload in [
+        job(
+            "Website redesign",
+            "Our designer left; the old backend was written in Rust",
+            "Figma",
+            "Graphic Design",
+        ),
+        job(
+            "Senior Rust developer",
+            "Build a payment API with Axum",
+            "Rust",
+            "Web Development",
+        ),
+        job(
+            "Podcast intro",
+            "A short jingle for the show",
+            "Audio",
+            "Music Production",
+        ),
+    ] {
+        let (status, body) = call(&app, Method::POST, "/jobs/create", &client, payload).await;
+        assert_eq!(status, StatusCode::CREATED);
+        jobs.push(body["job_id"].as_i64().unwrap());
+    }
+    let [redesign, rust, podcast] = jobs[..] else {
+        unreachable!()
+    };
+
+    // A title and skill match outranks a passing mention in the description
+    let (status, body) = search(&app, &freelancer, "rust").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec![rust, redesign]);
+    assert_eq!(
+        body["jobs"][0]["title_highlight"],
+        "Senior <mark>Rust</mark> developer"
+    );
+    assert!(body["jobs"][1]["snippet"]
+        .as_str()
+        .unwrap()
+        .contains("<mark>Rust</mark>"));
+    assert!(
+        body["jobs"][0]["relevance"].as_f64().unwrap()
+            > body["jobs"][1]["relevance"].as_f64().unwrap()
+    );
+
+    // Query syntax, and skills and category are searchable too
+    for (keyword, expected) in [
+        ("rust -designer", vec![rust]),
+        ("\"payment API\"", vec![rust]),
+        ("dev*", vec![rust]),
+        ("figma OR jingle", vec![podcast, redesign]),
+        ("NOT", vec![]),
+        ("music production", vec![podcast]),
+        ("rust AND (axum", vec![rust]),
+    ] {
+        let (status, body) = search(&app, &freelancer, keyword).await;
+        if expected.is_empty() {
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", keyword);
+            continue;
+        }
+        assert_eq!(status, StatusCode::OK, "{}", keyword);
+        let mut found = ids(&body);
+        found.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(found, expected, "{}", keyword);
+    }
+
+    let (status, body) = search(&app, &freelancer, "jinlge").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&body), vec![podcast]);
+    assert_eq!(
+        body["corrections"],
+        json!([{ "term": "jinlge", "suggestion": "jingl" }])
+    );
+}
+
+fn facet(body: &Value, name: &str) -> Vec<(String, i64)> {
+    body["facets"][name]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| {
+            (
+                value["value"].as_str().unwrap().to_string(),
+                value["count"].as_i64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_facets_count_matches_without_their_own_filter() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    for (skills, category, budget, job_type) in [
+        ("Rust", "Web Development", 50, "fixed"),
+        ("Rust, Go", "Web Development", 700, "hourly"),
+        ("Figma", "Graphic Design", 700, "fixed"),
+    ] {
+        let mut payload = job("Contract work", "Details inside", skills, category);
+        payload["budget"] = json!(budget);
+        payload["job_type"] = json!(job_type);
+        payload["weekly_hour_cap"] = json!(20);
+        let (status, _) = call(&app, Method::POST, "/jobs/create", &client, payload).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (status, body) = call(
+        &app,
+        Method::GET,
+        "/jobs?category=web-development&include_total=true&facets=all&limit=1",
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["jobs"].as_array().unwrap().len(), 1);
+    assert_eq!(body["total"], 2);
+    // The category facet ignores the category filter; the others apply it
+    assert_eq!(
+        facet(&body, "category"),
+        vec![
+            ("web-development".to_string(), 2),
+            ("graphic-design".to_string(), 1)
+        ]
+    );
+    assert_eq!(
+        facet(&body, "budget"),
+        vec![("0-99".to_string(), 1), ("500-999".to_string(), 1)]
+    );
+    assert_eq!(
+        facet(&body, "skill"),
+        vec![("Rust".to_string(), 2), ("Go".to_string(), 1)]
+    );
+    assert_eq!(
+        facet(&body, "job_type"),
+        vec![("fixed".to_string(), 1), ("hourly".to_string(), 1)]
+    );
+
+    let (_, body) = call(&app, Method::GET, "/jobs", &freelancer, json!({})).await;
+    assert!(body.get("total").is_none() && body.get("facets").is_none());
+    let (status, _) = call(
+        &app,
+        Method::GET,
+        "/jobs?facets=salary",
+        &freelancer,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{routes, utils::generate_jwt};
+use serde_json::json;
+
+async fn job_ids(app: &Router, token: &str, query: &str) -> Vec<i64> {
+    let (status, body) = call(
+        app,
+        Method::GET,
+        &format!("/jobs?{}", query),
+        token,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let mut ids: Vec<i64> = body["jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["id"].as_i64().unwrap())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[tokio::test]
+async fn test_skill_filters_match_canonical_skills_exactly() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role) VALUES
+             (1, 'client@example.com', 'client'),
+             (2, 'freelancer@example.com', 'freelancer')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let mut ids = Vec::new();
+    for skills in ["Java, Spring", "JavaScript, React", "js,node, javascript"] {
+        let (status, body) = call(
+            &app,
+            Method::POST,
+            "/jobs/create",
+            &client,
+            json!({
+                "title": "Frontend work",
+                "description": "Build the dashboard",
+                "skills": skills,
+                "budget": 300,
+                "location": "remote",
+                "job_type": "fixed",
+                "job_ipfs_hash": "hash",
+                "deadline": "2030-01-01",
+                "category": "Web Development",
+                "status": "open"
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        ids.push(body["job_id"].as_i64().unwrap());
+    }
+    let [java, react, node] = ids[..] else {
+        unreachable!()
+    };
+
+    // "Java" no longer matches "JavaScript", and aliases resolve to one skill
+    assert_eq!(job_ids(&app, &freelancer, "skills=java").await, vec![java]);
+    assert_eq!(
+        job_ids(&app, &freelancer, "skills=JS").await,
+        vec![react, node]
+    );
+    assert_eq!(
+        job_ids(&app, &freelancer, "skills=React,Node.js").await,
+        vec![react, node]
+    );
+    assert!(
+        job_ids(&app, &freelancer, "skills=React,Node.js&skills_match=all")
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        job_ids(
+            &app,
+            &freelancer,
+            "skills=javascript,nodejs&skills_match=all"
+        )
+        .await,
+        vec![node]
+    );
+    assert!(job_ids(&app, &freelancer, "skills=cobol").await.is_empty());
+
+    let skills: String = sqlx::query_scalar("SELECT skills FROM jobs WHERE id = ?")
+        .bind(node)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(skills, "JavaScript, Node.js");
+
+    let (status, body) = call(&app, Method::GET, "/skills?q=jav", &freelancer, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = body["skills"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|skill| skill["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["JavaScript", "Java"]);
+}
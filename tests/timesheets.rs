@@ -0,0 +1,206 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use common::{call, setup_db, test_state};
+use freelancia_backend::{
+    db::{repo::TimesheetRepo, DbPool},
+    models::timesheet::LogTime,
+    routes,
+    utils::generate_jwt,
+};
+use serde_json::json;
+
+// The Monday `weeks` weeks before this one, as YYYY-MM-DD
+fn past_monday(weeks: i64) -> NaiveDate {
+    let today = Utc::now().date_naive();
+    today - Duration::days(today.weekday().num_days_from_monday() as i64 + 7 * weeks)
+}
+
+#[tokio::test]
+async fn test_hourly_contract_bills_approved_weeks() {
+    let pool = setup_db().await;
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'freelancer@example.com', 'freelancer', 'freelancer-wallet')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let state = test_state(pool.clone());
+    let app = routes::create_routes(state.clone()).merge(routes::auth_routes(state.clone()));
+    let client = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+    let freelancer = generate_jwt(&state.config, 2, "freelancer".to_string()).unwrap();
+
+    let mut job = json!({
+        "title": "Maintain our indexer",
+        "description": "Ongoing Rust maintenance",
+        "skills": "rust",
+        "budget": 60,
+        "location": "remote",
+        "job_type": "hourly",
+        "job_ipfs_hash": "hash",
+        "deadline": "2030-01-01",
+        "category": "Web Development",
+        "status": "open"
+    });
+    let (status, _) = call(&app, Method::POST, "/jobs/create", &client, job.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    job["weekly_hour_cap"] = json!(10);
+    let (status, body) = call(&app, Method::POST, "/jobs/create", &client, job).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let job_id = body["job_id"].as_i64().unwrap();
+
+    // The bid on an hourly job is the hourly rate
+    let proposal =
+        json!({ "job_id": job_id, "cover_letter": "Indexers are my thing", "bid_amount": 50.0 });
+    let (_, body) = call(&app, Method::POST, "/proposals", &freelancer, proposal).await;
+    let contract_id = body["proposal"]["id"].as_i64().unwrap();
+    let (status, _) = call(
+        &app,
+        Method::PATCH,
+        &format!("/proposals/{}", contract_id),
+        &client,
+        json!({ "status": "accepted" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let week = past_monday(1);
+    let entries_uri = format!("/contracts/{}/time-entries", contract_id);
+    for (day, hours, expected) in [
+        (0, 6.0, StatusCode::CREATED),
+        (1, 4.0, StatusCode::CREATED),
+        (2, 1.0, StatusCode::BAD_REQUEST), // over the 10 hour cap
+    ] {
+        let entry = json!({
+            "work_date": (week + Duration::days(day)).to_string(),
+            "hours": hours,
+            "description": "Reindexing"
+        });
+        let (status, _) = call(&app, Method::POST, &entries_uri, &freelancer, entry).await;
+        assert_eq!(status, expected);
+    }
+
+    let timesheets_uri = format!("/contracts/{}/timesheets", contract_id);
+    let submit = json!({ "week_start": week.to_string() });
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &timesheets_uri,
+        &freelancer,
+        json!({ "week_start": past_monday(0).to_string() }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = call(&app, Method::POST, &timesheets_uri, &freelancer, submit).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["timesheet"]["amount"], 500.0);
+    let timesheet_id = body["timesheet"]["id"].as_i64().unwrap();
+
+    // The submitted week is locked
+    let late = json!({ "work_date": week.to_string(), "hours": 1.0, "description": "Late" });
+    let (status, _) = call(&app, Method::POST, &entries_uri, &freelancer, late).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let timesheet_uri = format!("/timesheets/{}", timesheet_id);
+    let pda = json!({ "escrow_pda": "E".repeat(44) });
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("{}/escrow", timesheet_uri),
+        &client,
+        pda.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("{}/review", timesheet_uri),
+        &client,
+        json!({ "approve": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        &format!("{}/escrow", timesheet_uri),
+        &client,
+        pda,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = call(
+        &app,
+        Method::POST,
+        &format!("{}/release", timesheet_uri),
+        &client,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["timesheet"]["status"], "release_recorded");
+
+    let (_, body) = call(&app, Method::GET, &timesheets_uri, &freelancer, json!({})).await;
+    assert_eq!(body["timesheets"].as_array().unwrap().len(), 1);
+    let kind: String = sqlx::query_scalar(
+        "SELECT type FROM notifications WHERE user_id = 2 ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(kind, "payment_released");
+}
+
+#[tokio::test]
+async fn test_weekly_cap_holds_under_concurrent_logging() {
+    // Several connections to one file, so the inserts really do race
+    let dir = std::env::temp_dir().join(format!("freelancia-cap-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db_pool = DbPool::connect(&format!("sqlite://{}/cap.db", dir.display()))
+        .await
+        .unwrap();
+    db_pool.migrate().await.unwrap();
+    let DbPool::Sqlite(pool) = db_pool else {
+        panic!("expected a sqlite pool");
+    };
+    sqlx::query(
+        "INSERT INTO users (id, email, role, wallet_address) VALUES
+             (1, 'client@example.com', 'client', NULL),
+             (2, 'freelancer@example.com', 'freelancer', 'freelancer-wallet');
+         INSERT INTO jobs (id, title, description, skills, budget, location, job_type, weekly_hour_cap, job_ipfs_hash, deadline, client_id, category, status)
+             VALUES (1, 'Maintain our indexer', 'Ongoing', 'rust', 60, 'remote', 'hourly', 40, 'hash', '2030-01-01', 1, 'Web Development', 'open');
+         INSERT INTO job_applications (id, user_id, job_id, freelancer_wallet, status, approved)
+             VALUES (1, 2, 1, 'freelancer-wallet', 'accepted', 1);",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let week = past_monday(1).to_string();
+    let mut attempts = tokio::task::JoinSet::new();
+    for _ in 0..10 {
+        let pool = pool.clone();
+        let week = week.clone();
+        attempts.spawn(async move {
+            let entry = LogTime {
+                work_date: week.clone(),
+                hours: 8.0,
+                description: "Indexer fixes".to_string(),
+            };
+            pool.log_time(1, &week, Some(40), &entry).await.unwrap()
+        });
+    }
+    let mut logged = 0;
+    while let Some(entry) = attempts.join_next().await {
+        logged += usize::from(entry.unwrap().is_some());
+    }
+    assert_eq!(logged, 5);
+    assert_eq!(pool.hours_logged(1, &week).await.unwrap(), 40.0);
+
+    pool.close().await;
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{json_response, send, setup_db, test_state};
+use ed25519_dalek::{Signer, SigningKey};
+use freelancia_backend::{
+    routes,
+    utils::{build_totp, generate_jwt, generate_totp_secret, hash_password},
+};
+use serde_json::{json, Value};
+
+async fn call(app: &Router, uri: &str, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+    json_response(send(app, Method::POST, uri, token, body).await).await
+}
+
+fn current_code(secret: &str) -> String {
+    build_totp(secret, "").unwrap().generate_current().unwrap()
+}
+
+#[tokio::test]
+async fn test_two_factor_login_flow() {
+    let pool = setup_db().await;
+    let state = test_state(pool.clone());
+    let app = routes::auth_routes(state.clone());
+
+    sqlx::query("INSERT INTO users (email, password, role) VALUES (?, ?, ?)")
+        .bind("client@example.com")
+        .bind(hash_password("password123").unwrap())
+        .bind("client")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let session = generate_jwt(&state.config, 1, "client".to_string()).unwrap();
+
+    let (status, body) = call(&app, "/2fa/enroll", Some(&session), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = body["secret"].as_str().unwrap().to_string();
+    assert!(body["otpauth_url"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/Freelancia:"));
+
+    let enable_code = current_code(&secret);
+    let (status, body) = call(
+        &app,
+        "/2fa/enable",
+        Some(&session),
+        json!({ "code": enable_code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let recovery_codes = body["recovery_codes"].as_array().unwrap().clone();
+    assert_eq!(recovery_codes.len(), 10);
+
+    // Password alone now only yields a pending token
+    let (status, body) = call(
+        &app,
+        "/login",
+        None,
+        json!({ "email": "client@example.com", "password": "password123" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["two_factor_required"], json!(true));
+    let pending_token = body["pending_token"].as_str().unwrap().to_string();
+
+    // The pending token can't be used as a session
+    let (status, _) = call(&app, "/2fa/step-up", Some(&pending_token), json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // The code that enabled 2FA can't be replayed
+    let (status, _) = call(
+        &app,
+        "/login/2fa",
+        None,
+        json!({ "pending_token": pending_token, "code": enable_code }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = call(
+        &app,
+        "/login/2fa",
+        None,
+        json!({ "pending_token": pending_token, "recovery_code": recovery_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].as_str().is_some());
+
+    // Recovery codes are single use
+    let (status, _) = call(
+        &app,
+        "/login/2fa",
+        None,
+        json!({ "pending_token": pending_token, "recovery_code": recovery_codes[0] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_wallet_login_requires_the_second_factor() {
+    let pool = setup_db().await;
+    let app = routes::auth_routes(test_state(pool.clone()));
+
+    let key = SigningKey::from_bytes(&[3u8; 32]);
+    let address = bs58::encode(key.verifying_key().to_bytes()).into_string();
+    let secret = generate_totp_secret();
+    sqlx::query(
+        "INSERT INTO users (email, role, wallet_address, wallet_chain, wallet_user, verified_wallet, totp_secret, totp_enabled) VALUES (?, 'client', ?, 'solana', 1, 1, ?, 1)",
+    )
+    .bind(format!("{}@freelancia.wallet", address))
+    .bind(&address)
+    .bind(&secret)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = call(
+        &app,
+        "/wallet/request-nonce",
+        None,
+        json!({ "wallet_address": address }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let message = body["message"].as_str().unwrap();
+    let prefixed = format!(
+        "\x18Solana Signed Message:\n{}{}",
+        message.chars().count(),
+        message
+    );
+    let signature = bs58::encode(key.sign(prefixed.as_bytes()).to_bytes()).into_string();
+
+    // A valid signature only stands in for the password
+    let (status, body) = call(
+        &app,
+        "/wallet/login",
+        None,
+        json!({ "wallet_address": address, "nonce": body["nonce"], "signature": signature }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].is_null());
+    assert_eq!(body["two_factor_required"], json!(true));
+
+    let (status, body) = call(
+        &app,
+        "/login/2fa",
+        None,
+        json!({ "pending_token": body["pending_token"], "code": current_code(&secret) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].as_str().is_some());
+}
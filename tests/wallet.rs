@@ -0,0 +1,180 @@
+mod common;
+
+use axum::{http::StatusCode, Router};
+use common::{post_json, setup_db, test_state};
+use ed25519_dalek::{Signer, SigningKey};
+use ethers::signers::{LocalWallet, Signer as _};
+use ethers::utils::to_checksum;
+use freelancia_backend::routes;
+use serde_json::json;
+use sqlx::{Pool, Sqlite};
+
+fn app(pool: Pool<Sqlite>) -> Router {
+    routes::auth_routes(test_state(pool))
+}
+
+#[tokio::test]
+async fn test_evm_wallet_sign_in() {
+    let pool = setup_db().await;
+    let app = app(pool.clone());
+
+    let wallet: LocalWallet = "0x59c6995e998f97a5a0044966f09453886bddd4f74efc3b8554c2046df9c6e26d"
+        .parse()
+        .unwrap();
+    let address = to_checksum(&wallet.address(), None);
+
+    // Clients may send any casing; the checksum form is what gets stored
+    let (status, body) = post_json(
+        &app,
+        "/wallet/request-nonce",
+        json!({ "wallet_address": address.to_lowercase(), "chain": "evm" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let nonce = body["nonce"].as_str().unwrap().to_string();
+    let message = body["message"].as_str().unwrap().to_string();
+    assert!(message.contains("wants you to sign in with your Ethereum account:"));
+    assert!(message.contains(&address));
+
+    // A signature from another key is rejected
+    let other: LocalWallet = "0x8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba"
+        .parse()
+        .unwrap();
+    let forged = other.sign_message(&message).await.unwrap();
+    let (status, _) = post_json(
+        &app,
+        "/wallet/verify",
+        json!({
+            "wallet_address": address,
+            "chain": "evm",
+            "nonce": nonce,
+            "signature": format!("0x{}", forged),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let signature = wallet.sign_message(&message).await.unwrap();
+    let (status, body) = post_json(
+        &app,
+        "/wallet/verify",
+        json!({
+            "wallet_address": address,
+            "chain": "evm",
+            "nonce": nonce,
+            "signature": format!("0x{}", signature),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].as_str().is_some());
+
+    let (stored_address, chain): (String, String) =
+        sqlx::query_as("SELECT wallet_address, wallet_chain FROM users WHERE verified_wallet = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(stored_address, address);
+    assert_eq!(chain, "evm");
+}
+
+#[tokio::test]
+async fn test_solana_wallet_sign_in_is_the_default_chain() {
+    let pool = setup_db().await;
+    let app = app(pool.clone());
+
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let address = bs58::encode(key.verifying_key().to_bytes()).into_string();
+
+    let (status, body) = post_json(
+        &app,
+        "/wallet/request-nonce",
+        json!({ "wallet_address": address }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let nonce = body["nonce"].as_str().unwrap().to_string();
+    let message = body["message"].as_str().unwrap();
+
+    // Solana wallets sign with the off-chain message prefix
+    let prefixed = format!(
+        "\x18Solana Signed Message:\n{}{}",
+        message.chars().count(),
+        message
+    );
+    let signature = bs58::encode(key.sign(prefixed.as_bytes()).to_bytes()).into_string();
+
+    let (status, _) = post_json(
+        &app,
+        "/wallet/verify",
+        json!({ "wallet_address": address, "nonce": nonce, "signature": signature }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let chain: String =
+        sqlx::query_scalar("SELECT wallet_chain FROM users WHERE wallet_address = ?")
+            .bind(&address)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(chain, "solana");
+}
+
+// Ask for a nonce and sign the returned message with a Solana key
+async fn signed_solana_nonce(app: &Router, key: &SigningKey) -> (String, String) {
+    let address = bs58::encode(key.verifying_key().to_bytes()).into_string();
+    let (status, body) = post_json(
+        app,
+        "/wallet/request-nonce",
+        json!({ "wallet_address": address }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let message = body["message"].as_str().unwrap();
+    let prefixed = format!(
+        "\x18Solana Signed Message:\n{}{}",
+        message.chars().count(),
+        message
+    );
+    let signature = bs58::encode(key.sign(prefixed.as_bytes()).to_bytes()).into_string();
+    (body["nonce"].as_str().unwrap().to_string(), signature)
+}
+
+#[tokio::test]
+async fn test_wallet_login_requires_a_signed_nonce() {
+    let pool = setup_db().await;
+    let app = app(pool.clone());
+
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    let address = bs58::encode(key.verifying_key().to_bytes()).into_string();
+    let (nonce, signature) = signed_solana_nonce(&app, &key).await;
+    let (status, _) = post_json(
+        &app,
+        "/wallet/verify",
+        json!({ "wallet_address": address, "nonce": nonce, "signature": signature }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Knowing the address alone is not enough
+    let (nonce, signature) = signed_solana_nonce(&app, &key).await;
+    let other = SigningKey::from_bytes(&[10u8; 32]);
+    let (_, forged) = signed_solana_nonce(&app, &other).await;
+    let (status, _) = post_json(
+        &app,
+        "/wallet/login",
+        json!({ "wallet_address": address, "nonce": nonce, "signature": forged }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let login = json!({ "wallet_address": address, "nonce": nonce, "signature": signature });
+    let (status, body) = post_json(&app, "/wallet/login", login.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["token"].as_str().is_some());
+
+    // The nonce is consumed by the login
+    let (status, _) = post_json(&app, "/wallet/login", login).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}